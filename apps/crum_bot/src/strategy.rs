@@ -0,0 +1,177 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use crum_bls::types::SecretKey;
+use crum_pkr::{
+    poker_bets::PokerAction, poker_deck::PokerCard, poker_hand::PokerHand,
+    poker_odds::estimate_strength,
+};
+use rand::{
+    Rng,
+    distributions::{Uniform, WeightedIndex},
+    rngs::ThreadRng,
+    thread_rng,
+};
+
+/// Betting situation of the player to act
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BettingView {
+    pub player: usize,
+    pub round: usize,
+    pub pot: u64,
+    pub chips: u64,
+    pub to_call: u64,
+    pub min_raise: u64,
+    pub min_raise_to: u64,
+    pub small_blind: u64,
+    /// Own hole cards, empty until every other player removed their mask
+    pub hole_cards: Vec<PokerCard>,
+    /// Community cards revealed so far
+    pub board: Vec<PokerCard>,
+    /// Number of other players still in the hand
+    pub opponents: usize,
+}
+
+impl BettingView {
+    pub fn new(hand: &PokerHand, player: usize, sk: &SecretKey) -> Self {
+        let (_, round, _) = hand.get_current_state().to_tuple();
+        let cards = hand.visible_cards_for_with(player, sk);
+        Self {
+            player,
+            round,
+            pot: hand.get_pot(),
+            chips: hand.get_chips_remaining(player),
+            to_call: hand.get_call_amount_required(player).unwrap_or(0),
+            min_raise: hand.get_min_raise_amount(player),
+            min_raise_to: hand.get_min_raise_to(player),
+            small_blind: hand.get_small_blind(),
+            hole_cards: cards
+                .hole_cards
+                .into_iter()
+                .collect::<Option<_>>()
+                .unwrap_or_default(),
+            board: cards.community_cards.into_iter().flatten().collect(),
+            opponents: (0..hand.get_num_players())
+                .filter(|&other| other != player && hand.is_player_active(other))
+                .count(),
+        }
+    }
+
+    /// Tell share of the pot after calling that the call makes up, i.e.
+    /// strength needed for the call to break even
+    pub fn pot_odds(&self) -> f64 {
+        let to_call = self.to_call.min(self.chips);
+        if to_call == 0 {
+            return 0.0;
+        }
+        to_call as f64 / (self.pot + to_call) as f64
+    }
+
+    /// Check when nothing to call, otherwise call
+    pub fn check_or_call(&self) -> PokerAction {
+        if self.to_call > 0 {
+            PokerAction::Call
+        } else {
+            PokerAction::Check
+        }
+    }
+}
+
+/// Decides how the bot bets
+pub trait BettingStrategy {
+    fn decide(&mut self, view: &BettingView) -> PokerAction;
+}
+
+/// Mostly raises, sometimes calls and rarely folds
+pub struct RandomStrategy {
+    rng: ThreadRng,
+}
+
+impl RandomStrategy {
+    pub fn new() -> Self {
+        Self { rng: thread_rng() }
+    }
+}
+
+impl Default for RandomStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BettingStrategy for RandomStrategy {
+    fn decide(&mut self, view: &BettingView) -> PokerAction {
+        if view.chips < view.to_call {
+            return PokerAction::Fold;
+        }
+        let weights = [1, 4, 8];
+        let dist = WeightedIndex::new(weights).expect("Failed to create weighted index");
+        match self.rng.sample(dist) {
+            0 if view.to_call > 0 => PokerAction::Fold,
+            1 => view.check_or_call(),
+            2 if view.min_raise < view.chips => {
+                let end_unit = ((view.chips - view.min_raise) / view.small_blind).min(10);
+                let units = self.rng.sample(Uniform::new_inclusive(0, end_unit));
+                PokerAction::Raise(view.min_raise_to + units * view.small_blind)
+            }
+            _ => view.check_or_call(),
+        }
+    }
+}
+
+/// Never folds and never raises
+pub struct CallingStationStrategy;
+
+impl BettingStrategy for CallingStationStrategy {
+    fn decide(&mut self, view: &BettingView) -> PokerAction {
+        view.check_or_call()
+    }
+}
+
+/// Folds when hand is too weak for the price, calls when pot odds are
+/// favourable and raises on strong hands
+pub struct PotOddsStrategy {
+    samples: usize,
+    raise_strength: f64,
+}
+
+impl PotOddsStrategy {
+    /// Number of deals sampled to estimate hand strength
+    pub const DEFAULT_SAMPLES: usize = 500;
+    /// Share of pots won above which the bot raises
+    pub const DEFAULT_RAISE_STRENGTH: f64 = 0.75;
+
+    pub fn new(samples: usize, raise_strength: f64) -> Self {
+        Self {
+            samples,
+            raise_strength,
+        }
+    }
+}
+
+impl Default for PotOddsStrategy {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SAMPLES, Self::DEFAULT_RAISE_STRENGTH)
+    }
+}
+
+impl BettingStrategy for PotOddsStrategy {
+    fn decide(&mut self, view: &BettingView) -> PokerAction {
+        if view.hole_cards.is_empty() || view.opponents == 0 {
+            return view.check_or_call();
+        }
+        let strength =
+            estimate_strength(&view.hole_cards, &view.board, view.opponents, self.samples);
+        if strength >= self.raise_strength && view.min_raise < view.chips {
+            PokerAction::Raise(view.min_raise_to)
+        } else if strength < view.pot_odds() {
+            PokerAction::Fold
+        } else {
+            view.check_or_call()
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use std::collections::VecDeque;
+
+use crum_pkr::{
+    poker_bets::PokerAction,
+    poker_deck::PokerCard,
+    poker_driver::HandDriver,
+    poker_event::PokerEvent,
+    poker_state::{POKER_HOLDEM_ROUNDS, POKER_HOLDEM_TURN},
+    poker_table::PokerTable,
+};
+
+use crate::{
+    PokerBot,
+    strategy::{BettingStrategy, BettingView, CallingStationStrategy, PotOddsStrategy},
+};
+
+/// Plays scripted actions first, then checks or calls
+struct ScriptedStrategy(VecDeque<PokerAction>);
+
+impl BettingStrategy for ScriptedStrategy {
+    fn decide(&mut self, view: &BettingView) -> PokerAction {
+        self.0.pop_front().unwrap_or_else(|| view.check_or_call())
+    }
+}
+
+#[test]
+fn test_pluggable_strategy() {
+    let bots = [
+        PokerBot::new(
+            1,
+            Box::new(ScriptedStrategy([PokerAction::Raise(40)].into())),
+        ),
+        PokerBot::new(2, Box::new(CallingStationStrategy)),
+    ];
+
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    for bot in &bots {
+        poker_table.join(bot.player_id).unwrap();
+    }
+    poker_table.start_hand(100, 10).unwrap();
+
+    let mut driver = HandDriver::new(bots.into_iter().map(|bot| (bot.player_id, bot)).collect());
+    driver.run(&mut poker_table).unwrap();
+
+    let hand = poker_table.get_current_hand_mut().unwrap();
+    let bets: Vec<_> = hand
+        .take_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            PokerEvent::BetPlaced { player, action } => Some((player, action)),
+            _ => None,
+        })
+        .collect();
+
+    let mut expected = vec![(0, PokerAction::Raise(40)), (1, PokerAction::Call)];
+    for _ in 1..POKER_HOLDEM_ROUNDS {
+        expected.extend([(1, PokerAction::Check), (0, PokerAction::Check)]);
+    }
+    assert_eq!(bets, expected);
+    assert_eq!(
+        hand.get_chips_remaining(0) + hand.get_chips_remaining(1),
+        200
+    );
+}
+
+#[test]
+fn test_bot_debug_hides_secret() {
+    let bot = PokerBot::new(1, Box::new(CallingStationStrategy));
+    let printed = format!("{bot:?}");
+    let secret = format!("{:?}", bot.sk.expose());
+
+    assert!(printed.contains("SecretKey(***)"));
+    assert!(!printed.contains(&secret[2..]));
+}
+
+#[test]
+fn test_pot_odds_strategy() {
+    let cards = |codes: &[&str]| -> Vec<PokerCard> {
+        codes.iter().map(|code| code.parse().unwrap()).collect()
+    };
+    let view = |hole: &[&str], board: &[&str], pot: u64, to_call: u64| BettingView {
+        player: 0,
+        round: POKER_HOLDEM_TURN,
+        pot,
+        chips: 1000,
+        to_call,
+        min_raise: to_call * 2,
+        min_raise_to: to_call * 2,
+        small_blind: 10,
+        hole_cards: cards(hole),
+        board: cards(board),
+        opponents: 1,
+    };
+    let mut strategy = PotOddsStrategy::new(2000, PotOddsStrategy::DEFAULT_RAISE_STRENGTH);
+
+    // Flush draw facing a pot-sized bet folds
+    let draw = ["5h", "4h"];
+    let board = ["Ks", "Qd", "2h", "9h"];
+    let weak = view(&draw, &board, 400, 400);
+    assert!(weak.pot_odds() > 0.45);
+    assert_eq!(strategy.decide(&weak), PokerAction::Fold);
+
+    // Same draw getting five to one calls
+    let cheap = view(&draw, &board, 500, 100);
+    assert!(cheap.pot_odds() < 0.2);
+    assert_eq!(strategy.decide(&cheap), PokerAction::Call);
+
+    // Same draw checks when nothing to call
+    let free = view(&draw, &board, 400, 0);
+    assert_eq!(free.pot_odds(), 0.0);
+    assert_eq!(strategy.decide(&free), PokerAction::Check);
+
+    // Set of Aces raises
+    let strong = view(&["As", "Ad"], &["Ah", "7c", "2d"], 500, 100);
+    assert_eq!(strategy.decide(&strong), PokerAction::Raise(200));
+}
@@ -0,0 +1,59 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+//!
+//! Compares serial and parallel brute-force shuffle verification of 52-card deck.
+//!
+//! Run with `cargo bench -p crum_bls --features parallel`.
+
+use std::time::{Duration, Instant};
+
+use bls12_381::{G1Affine, Scalar};
+use crum_bls::{
+    hash_to_curve::hash_to_curve, sign, util::make_public_key_from_signing_key, verify,
+};
+use pairing::group::Curve;
+
+const DECK_SIZE: u8 = 52;
+const ITERATIONS: u32 = 3;
+
+fn measure(name: &str, f: impl Fn() -> Result<(), &'static str>) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f().expect("Shuffle should verify");
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name}: {elapsed:?} per deck");
+    elapsed
+}
+
+fn main() {
+    let sk = Scalar::from(0x5eed_u64);
+    let pk = make_public_key_from_signing_key(&sk);
+
+    let masked_before: Vec<G1Affine> = (0..DECK_SIZE)
+        .map(|i| hash_to_curve(&[i / 13, i % 13]).to_affine())
+        .collect();
+
+    // Shuffle the deck in worst order for brute-force search
+    let masked_after: Vec<G1Affine> = masked_before
+        .iter()
+        .rev()
+        .map(|card| sign::mask(*card, sk))
+        .collect();
+
+    let serial = measure("verify_shuffle", || {
+        verify::verify_shuffle(&masked_before, &masked_after, &pk)
+    });
+    let parallel = measure("verify_shuffle_parallel", || {
+        verify::verify_shuffle_parallel(&masked_before, &masked_after, &pk)
+    });
+
+    println!(
+        "speedup: {:.2}x",
+        serial.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
@@ -61,3 +61,74 @@ pub fn hash_to_curve(message: &[u8]) -> G1Projective {
     let cs = b"BLS_SIG_BLS12381G2_XMD:KECCAK-256_SSWU_RO_";
     <G1Projective as HashToCurve<ExpandMsgXmd<Keccak256Hash>>>::hash_to_curve(message, cs)
 }
+
+/// SHA-256 hasher adapted for `ExpandMsgXmd` of `bls12_381`.
+#[cfg(feature = "sha256")]
+pub struct Sha256Hash(sha2::Sha256);
+
+#[cfg(feature = "sha256")]
+impl digest::BlockInput for Sha256Hash {
+    type BlockSize = digest::generic_array::typenum::U64;
+}
+
+#[cfg(feature = "sha256")]
+impl digest::Digest for Sha256Hash {
+    type OutputSize = digest::generic_array::typenum::U32;
+
+    fn new() -> Self {
+        Self(sha2::Digest::new())
+    }
+
+    fn output_size() -> usize {
+        32
+    }
+
+    fn chain(mut self, data: impl AsRef<[u8]>) -> Self {
+        sha2::Digest::update(&mut self.0, data);
+        self
+    }
+
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        sha2::Digest::update(&mut self.0, data);
+    }
+
+    fn finalize(self) -> digest::Output<Self> {
+        let res = sha2::Digest::finalize(self.0);
+        #[allow(deprecated)]
+        let mut arr = digest::generic_array::GenericArray::default();
+        arr.copy_from_slice(&res);
+        arr
+    }
+
+    fn reset(&mut self) {
+        self.0 = sha2::Digest::new();
+    }
+
+    fn digest(data: &[u8]) -> digest::Output<Self> {
+        Self::new().chain(data).finalize()
+    }
+
+    fn finalize_reset(&mut self) -> digest::Output<Self> {
+        let res = sha2::Digest::finalize_reset(&mut self.0);
+        #[allow(deprecated)]
+        let mut arr = digest::generic_array::GenericArray::default();
+        arr.copy_from_slice(&res);
+        arr
+    }
+}
+
+/// Hash to curve using standard IETF SHA-256 suite for signatures in G1.
+///
+/// Use for interoperability with standard BLS libraries off-chain, while
+/// `hash_to_curve` (Keccak-256) remains cheap to verify on-chain.
+#[cfg(feature = "sha256")]
+pub fn hash_to_curve_sha256(message: &[u8]) -> G1Projective {
+    hash_to_curve_sha256_with_dst(message, b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_")
+}
+
+/// Hash to curve using IETF SHA-256 suite with given domain separation tag
+#[cfg(feature = "sha256")]
+pub fn hash_to_curve_sha256_with_dst(message: &[u8], dst: &[u8]) -> G1Projective {
+    use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+    <G1Projective as HashToCurve<ExpandMsgXmd<Sha256Hash>>>::hash_to_curve(message, dst)
+}
@@ -5,12 +5,31 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
+use alloc::{collections::BTreeSet, vec::Vec};
+
 use bls12_381::{G1Projective, G2Projective, Scalar};
 use pairing::group::Curve;
 
-use crate::types::{PublicKey, Signature};
+use crate::{
+    types::{PublicKey, Signature},
+    verify::verify,
+};
+
+/// Labels are x-coordinates of shares, and must be distinct and non-zero,
+/// because secret itself sits at x = 0.
+fn validate_labels(labels: impl Iterator<Item = u64>) -> Result<(), &'static str> {
+    let mut seen = BTreeSet::new();
+    for label in labels {
+        if label == 0 || !seen.insert(label) {
+            return Err("duplicate or zero label");
+        }
+    }
+    Ok(())
+}
 
 pub fn combine(shares: &[(u64, Signature)]) -> Result<Signature, &'static str> {
+    validate_labels(shares.iter().map(|(label, _)| *label))?;
+
     let mut combined = G1Projective::identity();
     let x = shares
         .iter()
@@ -20,9 +39,8 @@ pub fn combine(shares: &[(u64, Signature)]) -> Result<Signature, &'static str> {
         let s = shares[i].1;
         let x_i = x[i];
         let mut l = Scalar::one();
-        for j in 0..x.len() {
+        for (j, &x_j) in x.iter().enumerate() {
             if i != j {
-                let x_j = x[j];
                 let d = (x_j - x_i)
                     .invert()
                     .into_option()
@@ -36,14 +54,15 @@ pub fn combine(shares: &[(u64, Signature)]) -> Result<Signature, &'static str> {
 }
 
 pub fn recover(shares: &[(u64, PublicKey)]) -> Result<PublicKey, &'static str> {
+    validate_labels(shares.iter().map(|(label, _)| *label))?;
+
     let mut a = G2Projective::identity();
     for i in 0..shares.len() {
         let (label_i, pk_i) = shares[i];
         let x_i = Scalar::from(label_i);
         let mut l = Scalar::one();
-        for j in 0..shares.len() {
+        for (j, &(label_j, _)) in shares.iter().enumerate() {
             if i != j {
-                let label_j = shares[j].0;
                 let x_j = Scalar::from(label_j);
                 let d = (x_j - x_i)
                     .invert()
@@ -56,3 +75,50 @@ pub fn recover(shares: &[(u64, PublicKey)]) -> Result<PublicKey, &'static str> {
     }
     Ok(a.to_affine())
 }
+
+/// Combines signature shares of any subset of participants in k-of-n scheme,
+/// where k is the threshold.
+pub fn combine_subset(
+    shares: &[(u64, Signature)],
+    threshold: usize,
+) -> Result<Signature, &'static str> {
+    if shares.len() < threshold {
+        return Err("Not enough shares");
+    }
+    combine(shares)
+}
+
+/// Recovers master public key from public keys of any subset of participants
+/// in k-of-n scheme, where k is the threshold.
+pub fn recover_subset(
+    shares: &[(u64, PublicKey)],
+    threshold: usize,
+) -> Result<PublicKey, &'static str> {
+    if shares.len() < threshold {
+        return Err("Not enough shares");
+    }
+    recover(shares)
+}
+
+/// Combines signature shares, recovers master public key from public key
+/// shares, and verifies combined signature of the message against it.
+///
+/// Both sets of shares must come from the same participants, i.e. their
+/// labels must match, otherwise combined signature and recovered key would
+/// not belong together.
+pub fn verify_combined(
+    message: &[u8],
+    shares: &[(u64, Signature)],
+    pub_shares: &[(u64, PublicKey)],
+) -> bool {
+    let labels: BTreeSet<u64> = shares.iter().map(|(label, _)| *label).collect();
+    let pub_labels: BTreeSet<u64> = pub_shares.iter().map(|(label, _)| *label).collect();
+    if shares.is_empty() || labels != pub_labels {
+        return false;
+    }
+
+    let (Ok(sig), Ok(pk)) = (combine(shares), recover(pub_shares)) else {
+        return false;
+    };
+    verify(message, &pk, &sig)
+}
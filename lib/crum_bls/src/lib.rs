@@ -5,9 +5,24 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod hash_to_curve;
 pub mod lagrange;
+pub mod proof;
+pub mod referee;
 pub mod sign;
 pub mod types;
 pub mod util;
 pub mod verify;
+
+// Verification used by on-chain referee must stay available without std
+#[cfg(not(feature = "std"))]
+const _: () = {
+    let _ = verify::verify;
+    let _ = verify::verify_unmasking_batch;
+    let _ = verify::verify_shuffle_traced;
+    let _ = referee::verify_hand;
+};
@@ -0,0 +1,73 @@
+//! Crumble (CRyptographic gaMBLE)
+//! 
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//! 
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use alloy_primitives::Keccak256;
+use bls12_381::{G1Affine, G2Affine, G2Projective, Scalar};
+use ff::Field;
+use pairing::group::Curve;
+use rand_core::RngCore;
+
+use crate::types::{PublicKey, SigningKey};
+
+/// Chaum–Pedersen proof that card was unmasked with signing key of public key.
+///
+/// Proves that log_unmasked(masked) == log_G2(PK) without revealing the key,
+/// so that unmasking can be audited before players reveal their keys.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnmaskProof {
+    pub commitment_g1: G1Affine,
+    pub commitment_g2: G2Affine,
+    pub response: Scalar,
+}
+
+/// Prove that "unmasked" data was produced by unmasking "masked" data with
+/// signing key.
+pub fn prove_unmask(
+    masked: &G1Affine,
+    unmasked: &G1Affine,
+    sk: SigningKey,
+    rng: impl RngCore,
+) -> UnmaskProof {
+    let pk = (G2Projective::generator() * sk).to_affine();
+    let nonce = Scalar::random(rng);
+
+    let commitment_g1 = (unmasked * nonce).to_affine();
+    let commitment_g2 = (G2Projective::generator() * nonce).to_affine();
+
+    let c = challenge(masked, unmasked, &pk, &commitment_g1, &commitment_g2);
+
+    UnmaskProof {
+        commitment_g1,
+        commitment_g2,
+        response: nonce + c * sk,
+    }
+}
+
+/// Fiat–Shamir challenge binding proof to the statement and commitments
+pub(crate) fn challenge(
+    masked: &G1Affine,
+    unmasked: &G1Affine,
+    pk: &PublicKey,
+    commitment_g1: &G1Affine,
+    commitment_g2: &G2Affine,
+) -> Scalar {
+    // Two hashes give 512 bits, so reducing them modulo group order is unbiased
+    let mut wide = [0u8; 64];
+    for (half, chunk) in wide.chunks_mut(32).enumerate() {
+        let mut hasher = Keccak256::new();
+        hasher.update(b"CRUMBLE_UNMASK_PROOF");
+        hasher.update([half as u8]);
+        hasher.update(masked.to_compressed());
+        hasher.update(unmasked.to_compressed());
+        hasher.update(pk.to_compressed());
+        hasher.update(commitment_g1.to_compressed());
+        hasher.update(commitment_g2.to_compressed());
+        chunk.copy_from_slice(hasher.finalize().as_slice());
+    }
+    Scalar::from_bytes_wide(&wide)
+}
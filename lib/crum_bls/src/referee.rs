@@ -0,0 +1,289 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+//! Audit of the whole hand from its serialized record, e.g. inside a
+//! contract. Nothing here panics on malformed input, and memory used is
+//! bounded by `MAX_PLAYERS` and `MAX_DECK_LEN`.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+
+use alloy_primitives::Keccak256;
+use bls12_381::{G1Affine, G2Prepared};
+
+use crate::{
+    hash_to_curve::hash_to_curve,
+    types::PublicKey,
+    util::{PUBLIC_KEY_COMPRESSED_LEN, make_public_key_from_compressed_slice},
+    verify::{decode_traces, verify_shuffle_traced_prepared, verify_unmasking_batch},
+};
+
+/// Length of compressed card point
+pub const CARD_COMPRESSED_LEN: usize = 48;
+
+/// Length of single peel in wire format: player, unmasked card, masked card
+pub const AUDIT_ENTRY_LEN: usize = 1 + 2 * CARD_COMPRESSED_LEN;
+
+/// Length of Keccak256 deck commitment
+pub const DECK_COMMITMENT_LEN: usize = 32;
+
+pub const MAX_PLAYERS: usize = 32;
+
+/// Shuffle traces use single byte per index
+pub const MAX_DECK_LEN: usize = 256;
+
+/// Length of card code, e.g. b"As" for Ace of spades
+pub const CARD_CODE_LEN: usize = 2;
+
+/// Card ranks in order of the deck
+pub const CARD_RANKS: &[u8] = b"23456789TJQKA";
+
+/// Card suits in order of the deck
+pub const CARD_SUITS: &[u8] = b"shdc";
+
+/// Entry of dealt cards for community card
+pub const COMMUNITY_CARD: u8 = 0xFE;
+
+/// Entry of dealt cards for card burned or never dealt
+pub const UNDEALT_CARD: u8 = 0xFF;
+
+/// Serialized record of the hand.
+///
+/// Cards are concatenated compressed G1 points, and public keys are
+/// concatenated compressed G2 points in seat order.
+///
+pub struct RefereeInput<'a> {
+    /// Keccak256 of final shuffled deck, as committed on-chain
+    pub deck_commitment: &'a [u8],
+    /// Codes of cards in order of the deck before the first shuffle, e.g.
+    /// b"2s2h2d2c3s...Ac"; their base points are derived by the referee
+    pub card_codes: &'a [u8],
+    /// Deck after each shuffle, starting with the dealer's
+    pub shuffled_decks: &'a [&'a [u8]],
+    /// Traces of each shuffle as encoded by `verify::encode_traces`
+    pub shuffle_traces: &'a [&'a [u8]],
+    pub public_keys: &'a [u8],
+    /// Player who shuffled first; the rest follow in seat order
+    pub dealer: usize,
+    /// Every peel of unmasking history, `AUDIT_ENTRY_LEN` bytes each, in
+    /// the order peels were made
+    pub audit_trail: &'a [u8],
+    /// Where each card of the final deck went: seat of the player for hole
+    /// card, `COMMUNITY_CARD` or `UNDEALT_CARD`
+    pub dealt_cards: &'a [u8],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefereeVerdict {
+    /// Every shuffle and unmasking step has been proven
+    Fair,
+    /// Player failed to prove their shuffle or unmasking, or submitted
+    /// invalid key or cards
+    Cheated(usize),
+    /// Record cannot be decoded, or it does not describe committed hand
+    Malformed(&'static str),
+}
+
+fn decode_card(data: &[u8]) -> Option<G1Affine> {
+    let bytes: [u8; CARD_COMPRESSED_LEN] = data.try_into().ok()?;
+    G1Affine::from_compressed(&bytes).into_option()
+}
+
+fn decode_deck(data: &[u8]) -> Option<Vec<G1Affine>> {
+    if !data.len().is_multiple_of(CARD_COMPRESSED_LEN) {
+        return None;
+    }
+    data.chunks_exact(CARD_COMPRESSED_LEN)
+        .map(decode_card)
+        .collect()
+}
+
+/// Base points of the cards by their codes, or None unless each code is
+/// valid rank and suit, and no card is given twice
+fn canonical_deck(card_codes: &[u8]) -> Option<Vec<G1Affine>> {
+    if !card_codes.len().is_multiple_of(CARD_CODE_LEN) {
+        return None;
+    }
+    let mut seen = BTreeSet::new();
+    card_codes
+        .chunks_exact(CARD_CODE_LEN)
+        .map(|code| {
+            let valid = CARD_RANKS.contains(&code[0]) && CARD_SUITS.contains(&code[1]);
+            (valid && seen.insert(code)).then(|| hash_to_curve(code).into())
+        })
+        .collect()
+}
+
+/// Check that every dealt card has been peeled exactly once by each player,
+/// except that owner of hole cards only peels them if they showed them.
+///
+/// Peels must already be verified, so that no two cards can share a point.
+///
+fn check_completeness(
+    final_deck: &[u8],
+    audit_trail: &[u8],
+    dealt_cards: &[u8],
+    num_players: usize,
+) -> Result<(), &'static str> {
+    // Latest point of each card, and players who peeled it so far
+    let mut tips: BTreeMap<&[u8], usize> = final_deck
+        .chunks_exact(CARD_COMPRESSED_LEN)
+        .enumerate()
+        .map(|(position, card)| (card, position))
+        .collect();
+    let mut peeled_by = vec![0u64; dealt_cards.len()];
+
+    for entry in audit_trail.chunks_exact(AUDIT_ENTRY_LEN) {
+        let player = entry[0] as usize;
+        let unmasked_data = &entry[1..1 + CARD_COMPRESSED_LEN];
+        let masked_data = &entry[1 + CARD_COMPRESSED_LEN..];
+
+        let position = tips
+            .remove(masked_data)
+            .ok_or("Audit trail peels card twice")?;
+        if peeled_by[position] & (1 << player) != 0 {
+            return Err("Audit trail peels card twice");
+        }
+        peeled_by[position] |= 1 << player;
+        if tips.insert(unmasked_data, position).is_some() {
+            return Err("Audit trail duplicates card");
+        }
+    }
+
+    let everyone = (1u64 << num_players) - 1;
+    for (&owner, &peeled_by) in dealt_cards.iter().zip(&peeled_by) {
+        let complete = match owner {
+            COMMUNITY_CARD => peeled_by == everyone,
+            UNDEALT_CARD => peeled_by == 0,
+            seat if (seat as usize) < num_players => peeled_by | (1 << seat) == everyone,
+            _ => return Err("Dealt cards"),
+        };
+        if !complete {
+            return Err("Audit trail incomplete");
+        }
+    }
+    Ok(())
+}
+
+/// Verify shuffles and unmasking of the hand, and tell who cheated
+pub fn verify_hand(input: RefereeInput) -> RefereeVerdict {
+    // Public keys
+    if !input.public_keys.len().is_multiple_of(PUBLIC_KEY_COMPRESSED_LEN) {
+        return RefereeVerdict::Malformed("Public keys length");
+    }
+    let num_players = input.public_keys.len() / PUBLIC_KEY_COMPRESSED_LEN;
+    if !(2..=MAX_PLAYERS).contains(&num_players) || input.dealer >= num_players {
+        return RefereeVerdict::Malformed("Number of players");
+    }
+    let mut pks: Vec<PublicKey> = Vec::with_capacity(num_players);
+    for (player, data) in input
+        .public_keys
+        .chunks_exact(PUBLIC_KEY_COMPRESSED_LEN)
+        .enumerate()
+    {
+        match make_public_key_from_compressed_slice(data) {
+            Ok(pk) => pks.push(pk),
+            Err(_) => return RefereeVerdict::Cheated(player),
+        }
+    }
+    let prepared_pks: Vec<G2Prepared> = pks.iter().map(|pk| G2Prepared::from(*pk)).collect();
+
+    // Shuffles
+    let deck_len = input.card_codes.len() / CARD_CODE_LEN;
+    if !(1..=MAX_DECK_LEN).contains(&deck_len) || input.dealt_cards.len() != deck_len {
+        return RefereeVerdict::Malformed("Deck length");
+    }
+    let Some(mut deck) = canonical_deck(input.card_codes) else {
+        return RefereeVerdict::Malformed("Initial deck");
+    };
+    if input.shuffled_decks.len() != num_players || input.shuffle_traces.len() != num_players {
+        return RefereeVerdict::Malformed("Number of shuffles");
+    }
+
+    for step in 0..num_players {
+        let player = (input.dealer + step) % num_players;
+        let deck_data = input.shuffled_decks[step];
+
+        if deck_data.len() != deck_len * CARD_COMPRESSED_LEN {
+            return RefereeVerdict::Cheated(player);
+        }
+        let Some(next_deck) = decode_deck(deck_data) else {
+            return RefereeVerdict::Cheated(player);
+        };
+        let Ok(traces) = decode_traces(input.shuffle_traces[step], deck_len) else {
+            return RefereeVerdict::Cheated(player);
+        };
+        if verify_shuffle_traced_prepared(&deck, &next_deck, &prepared_pks[player], &traces)
+            .is_err()
+        {
+            return RefereeVerdict::Cheated(player);
+        }
+        deck = next_deck;
+    }
+
+    let final_deck = input.shuffled_decks[num_players - 1];
+    let mut hasher = Keccak256::new();
+    hasher.update(final_deck);
+    if hasher.finalize().as_slice() != input.deck_commitment {
+        return RefereeVerdict::Malformed("Deck commitment mismatch");
+    }
+
+    // Unmasking
+    if !input.audit_trail.len().is_multiple_of(AUDIT_ENTRY_LEN)
+        || input.audit_trail.len() / AUDIT_ENTRY_LEN > num_players * deck_len
+    {
+        return RefereeVerdict::Malformed("Audit trail length");
+    }
+
+    // Every peel must start from a card of the final deck, or from a card
+    // another peel left, otherwise trail would not be about this hand
+    let mut known_cards: BTreeSet<&[u8]> = final_deck.chunks_exact(CARD_COMPRESSED_LEN).collect();
+    let mut peels: Vec<(G1Affine, G1Affine, usize)> =
+        Vec::with_capacity(input.audit_trail.len() / AUDIT_ENTRY_LEN);
+
+    for entry in input.audit_trail.chunks_exact(AUDIT_ENTRY_LEN) {
+        let player = entry[0] as usize;
+        let unmasked_data = &entry[1..1 + CARD_COMPRESSED_LEN];
+        let masked_data = &entry[1 + CARD_COMPRESSED_LEN..];
+
+        if player >= num_players {
+            return RefereeVerdict::Malformed("Audit trail player");
+        }
+        if !known_cards.contains(masked_data) {
+            return RefereeVerdict::Malformed("Audit trail does not match deck");
+        }
+        let (Some(unmasked), Some(masked)) = (decode_card(unmasked_data), decode_card(masked_data))
+        else {
+            return RefereeVerdict::Cheated(player);
+        };
+        known_cards.insert(unmasked_data);
+        peels.push((unmasked, masked, player));
+    }
+
+    let batch: Vec<_> = peels
+        .iter()
+        .map(|&(unmasked, masked, player)| (unmasked, masked, &pks[player], &prepared_pks[player]))
+        .collect();
+
+    if !verify_unmasking_batch(&batch) {
+        // Batch failed, so find out who cheated
+        for (term, (_, _, player)) in batch.iter().zip(&peels) {
+            if !verify_unmasking_batch(core::slice::from_ref(term)) {
+                return RefereeVerdict::Cheated(*player);
+            }
+        }
+    }
+
+    // Trail leaving out peels would hide who withheld their key
+    match check_completeness(final_deck, input.audit_trail, input.dealt_cards, num_players) {
+        Ok(()) => RefereeVerdict::Fair,
+        Err(reason) => RefereeVerdict::Malformed(reason),
+    }
+}
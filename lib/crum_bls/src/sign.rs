@@ -5,7 +5,7 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
-use bls12_381::G1Affine;
+use bls12_381::{G1Affine, G1Projective};
 use pairing::group::Curve;
 
 use crate::{
@@ -24,8 +24,19 @@ pub fn mask(g1: G1Affine, k: SigningKey) -> G1Affine {
     p.to_affine()
 }
 
-pub fn unmask(g1: G1Affine, k: SigningKey) -> G1Affine {
-    let i = k.invert().expect("Failed to invert");
+pub fn unmask(g1: G1Affine, k: SigningKey) -> Result<G1Affine, &'static str> {
+    let i = k.invert().into_option().ok_or("Zero Key")?;
     let u = g1 * i;
-    u.to_affine()
+    Ok(u.to_affine())
+}
+
+/// Aggregates signatures by adding them up.
+///
+/// This is plain sum of points, not Lagrange interpolation as in
+/// `lagrange::combine`, and is verified using `verify::aggregate_verify`.
+///
+pub fn aggregate(sigs: &[Signature]) -> Signature {
+    sigs.iter()
+        .fold(G1Projective::identity(), |sum, sig| sum + sig)
+        .to_affine()
 }
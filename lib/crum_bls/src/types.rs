@@ -6,8 +6,104 @@
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
 use bls12_381::{G1Affine, G2Affine, Scalar};
+use ff::Field;
+use rand_core::RngCore;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+    proof::{self, UnmaskProof},
+    sign,
+    util::{make_public_key_from_signing_key, validate_signing_key},
+};
 
 pub type SigningKey = Scalar;
 pub type Signature = G1Affine;
 pub type PublicKey = G2Affine;
 
+
+/// Signing key that is wiped from memory when dropped.
+///
+/// Secret is borrowed by masking, unmasking and signing, so that it isn't
+/// copied around freely.
+///
+pub struct SecretKey(SigningKey);
+
+impl SecretKey {
+    pub fn new(sk: SigningKey) -> Self {
+        Self(sk)
+    }
+
+    /// Generate random key, which is never zero
+    pub fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let sk = Scalar::random(&mut rng);
+            if validate_signing_key(&sk).is_ok() {
+                return Self(sk);
+            }
+        }
+    }
+
+    /// Borrow underlying signing key
+    pub fn expose(&self) -> &SigningKey {
+        &self.0
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        make_public_key_from_signing_key(&self.0)
+    }
+
+    pub fn sign(&self, data: &[u8]) -> Signature {
+        sign::sign(data, self.0)
+    }
+
+    pub fn mask(&self, g1: G1Affine) -> G1Affine {
+        sign::mask(g1, self.0)
+    }
+
+    pub fn unmask(&self, g1: G1Affine) -> Result<G1Affine, &'static str> {
+        sign::unmask(g1, self.0)
+    }
+
+    /// Inverse of the key, so that masking with it unmasks many cards
+    /// without inverting the key for each of them
+    pub fn invert(&self) -> Result<Self, &'static str> {
+        self.0.invert().into_option().map(Self).ok_or("Zero Key")
+    }
+
+    /// Prove that "unmasked" data was produced by unmasking "masked" with this key
+    pub fn prove_unmask(
+        &self,
+        masked: &G1Affine,
+        unmasked: &G1Affine,
+        rng: impl RngCore,
+    ) -> UnmaskProof {
+        proof::prove_unmask(masked, unmasked, self.0, rng)
+    }
+}
+
+/// Secret never gets into logs; use `expose()` to get it explicitly
+impl core::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SecretKey(***)")
+    }
+}
+
+impl core::fmt::Display for SecretKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
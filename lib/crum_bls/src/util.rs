@@ -6,6 +6,7 @@
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
 use bls12_381::G2Projective;
+use ff::Field;
 use pairing::group::Curve;
 
 use crate::types::{PublicKey, Signature, SigningKey};
@@ -20,9 +21,14 @@ pub fn make_public_key_from_compressed_slice(data: &[u8]) -> Result<PublicKey, &
     }
     let mut bytes = [0u8; PUBLIC_KEY_COMPRESSED_LEN];
     bytes.copy_from_slice(data);
-    PublicKey::from_compressed(&bytes)
+    let pk = PublicKey::from_compressed(&bytes)
         .into_option()
-        .ok_or("Decode Error")
+        .ok_or("Decode Error")?;
+    // Identity would pass pairing checks trivially
+    if bool::from(pk.is_identity()) {
+        return Err("Invalid key: identity");
+    }
+    Ok(pk)
 }
 
 pub fn make_signature_from_compressed_slice(data: &[u8]) -> Result<Signature, &'static str> {
@@ -31,11 +37,46 @@ pub fn make_signature_from_compressed_slice(data: &[u8]) -> Result<Signature, &'
     }
     let mut bytes = [0u8; SIGNATURE_COMPRESSED_LEN];
     bytes.copy_from_slice(data);
-    Signature::from_compressed(&bytes)
+    let sig = Signature::from_compressed(&bytes)
         .into_option()
-        .ok_or("Decode Error")
+        .ok_or("Decode Error")?;
+    if bool::from(sig.is_identity()) {
+        return Err("Invalid signature: identity");
+    }
+    Ok(sig)
+}
+
+/// Signing key must be invertible to unmask, i.e. it must not be zero
+pub fn validate_signing_key(sk: &SigningKey) -> Result<(), &'static str> {
+    if bool::from(sk.is_zero()) {
+        return Err("Zero Key");
+    }
+    Ok(())
 }
 
 pub fn make_public_key_from_signing_key(sk: &SigningKey) -> PublicKey {
     (G2Projective::generator() * sk).to_affine()
 }
+
+pub fn public_key_to_bytes(pk: &PublicKey) -> [u8; PUBLIC_KEY_COMPRESSED_LEN] {
+    pk.to_compressed()
+}
+
+pub fn signature_to_bytes(sig: &Signature) -> [u8; SIGNATURE_COMPRESSED_LEN] {
+    sig.to_compressed()
+}
+
+pub fn signing_key_to_bytes(sk: &SigningKey) -> [u8; SIGNING_KEY_LEN] {
+    sk.to_bytes()
+}
+
+pub fn signing_key_from_bytes(data: &[u8]) -> Result<SigningKey, &'static str> {
+    if data.len() != SIGNING_KEY_LEN {
+        return Err("Len Error");
+    }
+    let mut bytes = [0u8; SIGNING_KEY_LEN];
+    bytes.copy_from_slice(data);
+    SigningKey::from_bytes(&bytes)
+        .into_option()
+        .ok_or("Decode Error")
+}
@@ -5,10 +5,11 @@
 //!
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
-use std::collections::HashSet;
+use alloc::{collections::BTreeSet, vec, vec::Vec};
 
 /// Verification of signatures and unmasking
-use bls12_381::{Bls12, G1Affine, G2Affine, G2Prepared};
+use alloy_primitives::Keccak256;
+use bls12_381::{Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar};
 use pairing::{
     MultiMillerLoop,
     group::{Curve, Group},
@@ -16,6 +17,7 @@ use pairing::{
 
 use crate::{
     hash_to_curve::hash_to_curve,
+    proof::{UnmaskProof, challenge},
     types::{PublicKey, Signature},
 };
 
@@ -26,8 +28,8 @@ pub fn verify(message: &[u8], pk: &PublicKey, sig: &Signature) -> bool {
     // e(sig, G1) * e(h, -PK) == 1
     // Using BLS12-381 standard pairing check
     let is_valid = Bls12::multi_miller_loop(&[
-        (&G1Affine::from(*sig), &G2Affine::generator().into()),
-        (&G1Affine::from(h), &(-G2Affine::from(*pk)).into()),
+        (sig, &G2Affine::generator().into()),
+        (&h, &(-(*pk)).into()),
     ])
     .final_exponentiation()
     .is_identity();
@@ -35,19 +37,182 @@ pub fn verify(message: &[u8], pk: &PublicKey, sig: &Signature) -> bool {
     is_valid.into()
 }
 
+/// Verifies that message has been signed by all signing keys corresponding to
+/// public keys, where aggregated signature is the sum of their signatures
+/// (see `sign::aggregate`).
+///
+/// All signers signed the same message, so public keys can be summed and
+/// verified with single pairing check. Keys must be known to be honestly
+/// generated (e.g. players proved possession), otherwise rogue key could
+/// cancel out other keys.
+///
+pub fn aggregate_verify(message: &[u8], pks: &[PublicKey], aggregated_sig: &Signature) -> bool {
+    if pks.is_empty() {
+        return false;
+    }
+
+    let aggregated_pk = pks
+        .iter()
+        .fold(G2Projective::identity(), |sum, pk| sum + pk)
+        .to_affine();
+
+    verify(message, &aggregated_pk, aggregated_sig)
+}
+
+/// Verifies that each message has been signed by signing key corresponding to
+/// public key at the same position, where aggregated signature is the sum of
+/// their signatures.
+///
+/// This is single Miller loop over all terms with one Final Exponentiation.
+/// Messages must be distinct, otherwise signature is rejected.
+///
+pub fn aggregate_verify_distinct(
+    messages: &[&[u8]],
+    pks: &[PublicKey],
+    aggregated_sig: &Signature,
+) -> bool {
+    if messages.is_empty() || messages.len() != pks.len() {
+        return false;
+    }
+
+    let mut seen_messages = BTreeSet::new();
+    if !messages.iter().all(|message| seen_messages.insert(*message)) {
+        return false;
+    }
+
+    let hashes: Vec<G1Affine> = messages
+        .iter()
+        .map(|message| hash_to_curve(message).to_affine())
+        .collect();
+    let prepared_pks: Vec<G2Prepared> = pks.iter().map(|pk| G2Prepared::from(*pk)).collect();
+    let neg_g2_prepared = G2Prepared::from(-G2Affine::generator());
+
+    // e(agg_sig, -G2) * Π e(H(m_i), PK_i) == 1
+    let miller_loop_terms: Vec<(&G1Affine, &G2Prepared)> =
+        core::iter::once((aggregated_sig, &neg_g2_prepared))
+            .chain(hashes.iter().zip(prepared_pks.iter()))
+            .collect();
+
+    Bls12::multi_miller_loop(&miller_loop_terms)
+        .final_exponentiation()
+        .is_identity()
+        .into()
+}
+
 /// Verifies that "masked" data has been "unmasked" with signing key
 /// corresponding to public key.
 pub fn verify_unmasking(masked: G1Affine, unmasked: G1Affine, pk: G2Affine) -> bool {
     let g2_gen = G2Affine::generator();
     Bls12::multi_miller_loop(&[
-        (&unmasked, &G2Affine::from(pk).into()),
-        (&masked, &(-G2Affine::from(g2_gen)).into()),
+        (&unmasked, &pk.into()),
+        (&masked, &(-g2_gen).into()),
     ])
     .final_exponentiation()
     .is_identity()
     .into()
 }
 
+/// Unmasking step as (unmasked, masked, PK, prepared PK)
+pub type UnmaskingTerm<'a> = (G1Affine, G1Affine, &'a PublicKey, &'a G2Prepared);
+
+/// Fiat–Shamir coefficients of batch terms, bound to every card and public
+/// key of the batch.
+///
+/// Number of terms goes first, so that batches of different length cannot
+/// hash to the same seed.
+///
+fn batch_coefficients(terms: &[UnmaskingTerm]) -> Vec<Scalar> {
+    let mut hasher = Keccak256::new();
+    hasher.update(b"CRUMBLE_UNMASK_BATCH");
+    hasher.update((terms.len() as u64).to_le_bytes());
+    for (unmasked, masked, pk, _) in terms {
+        hasher.update(unmasked.to_compressed());
+        hasher.update(masked.to_compressed());
+        hasher.update(pk.to_compressed());
+    }
+    let seed = hasher.finalize();
+
+    (0..terms.len() as u64)
+        .map(|index| {
+            // Two hashes give 512 bits, so reducing them modulo group order is unbiased
+            let mut wide = [0u8; 64];
+            for (half, chunk) in wide.chunks_mut(32).enumerate() {
+                let mut hasher = Keccak256::new();
+                hasher.update(seed);
+                hasher.update(index.to_le_bytes());
+                hasher.update([half as u8]);
+                chunk.copy_from_slice(hasher.finalize().as_slice());
+            }
+            Scalar::from_bytes_wide(&wide)
+        })
+        .collect()
+}
+
+/// Verifies batch of unmasking steps, each given as (unmasked, masked, PK,
+/// prepared PK).
+///
+/// Steps may come from any number of hands, and are all checked using only
+/// single Final Exponentiation call. Each step is weighted by coefficient
+/// derived from the whole batch, so that forged steps cannot cancel each
+/// other out.
+///
+pub fn verify_unmasking_batch(terms: &[UnmaskingTerm]) -> bool {
+    let neg_g2_gen = -G2Affine::generator();
+    let neg_g2_prepared = G2Prepared::from(neg_g2_gen);
+
+    // Single step has nothing to cancel against
+    let weighted: Vec<G1Affine> = if terms.len() == 1 {
+        terms
+            .iter()
+            .flat_map(|(unmasked, masked, _, _)| [*unmasked, *masked])
+            .collect()
+    } else {
+        let scaled: Vec<G1Projective> = terms
+            .iter()
+            .zip(batch_coefficients(terms))
+            .flat_map(|((unmasked, masked, _, _), r)| [unmasked * r, masked * r])
+            .collect();
+        let mut weighted = vec![G1Affine::identity(); scaled.len()];
+        G1Projective::batch_normalize(&scaled, &mut weighted);
+        weighted
+    };
+
+    // e(r * unmasked, PK) * e(r * masked, -G2) == 1 for every step
+    let miller_loop_terms: Vec<(&G1Affine, &G2Prepared)> = terms
+        .iter()
+        .zip(weighted.chunks_exact(2))
+        .flat_map(|((_, _, _, pk), points)| [(&points[0], *pk), (&points[1], &neg_g2_prepared)])
+        .collect();
+
+    Bls12::multi_miller_loop(&miller_loop_terms)
+        .final_exponentiation()
+        .is_identity()
+        .into()
+}
+
+/// Verifies proof that "masked" data has been "unmasked" with signing key
+/// corresponding to public key, without knowing the signing key.
+pub fn verify_unmask_proof(
+    masked: &G1Affine,
+    unmasked: &G1Affine,
+    pk: &PublicKey,
+    proof: &UnmaskProof,
+) -> bool {
+    if bool::from(pk.is_identity()) {
+        return false;
+    }
+
+    let c = challenge(masked, unmasked, pk, &proof.commitment_g1, &proof.commitment_g2);
+
+    // unmasked * s == T1 + masked * c
+    let is_valid_g1 = unmasked * proof.response == proof.commitment_g1 + masked * c;
+    // G2 * s == T2 + PK * c
+    let is_valid_g2 =
+        G2Projective::generator() * proof.response == proof.commitment_g2 + pk * c;
+
+    is_valid_g1 && is_valid_g2
+}
+
 /// Verifies that "masked_before" data has been shuffled into "masked_after"
 /// data with signing key corresponding to public key.
 /// 
@@ -57,12 +222,21 @@ pub fn verify_shuffle(
     masked_before: &[G1Affine],
     masked_after: &[G1Affine],
     pk: &G2Affine,
+) -> Result<(), &'static str> {
+    verify_shuffle_prepared(masked_before, masked_after, &G2Prepared::from(*pk))
+}
+
+/// Same as `verify_shuffle`, but with public key already prepared for pairing,
+/// so that verifying many shuffles by the same player prepares the key once.
+pub fn verify_shuffle_prepared(
+    masked_before: &[G1Affine],
+    masked_after: &[G1Affine],
+    pk_prepared: &G2Prepared,
 ) -> Result<(), &'static str> {
     if masked_before.len() < masked_after.len() {
         return Err("Masked before must at least same length as masked after");
     }
 
-    let pk_prepared = G2Prepared::from(*pk);
     let neg_g2_gen = -G2Affine::generator();
     let neg_g2_prepared = G2Prepared::from(neg_g2_gen);
 
@@ -75,7 +249,7 @@ pub fn verify_shuffle(
             // e(card_after, -G2) * e(card_before, PK) == 1
             let is_match: bool = Bls12::multi_miller_loop(&[
                 (point_after, &neg_g2_prepared),
-                (point_before, &pk_prepared),
+                (point_before, pk_prepared),
             ])
             .final_exponentiation()
             .is_identity()
@@ -100,11 +274,124 @@ pub fn verify_shuffle(
     Ok(())
 }
 
+/// Verifies that "masked_before" data has been shuffled into "masked_after"
+/// data with signing key corresponding to public key.
+///
+/// This is the same brute-force O(N^2) algorithm as `verify_shuffle`, with
+/// output cards checked in parallel by rayon. Each output card scans
+/// `masked_before` for an input it unmasks to, and atomically claims it, so
+/// that matched input is removed for all other output cards. Input claimed
+/// by another thread in the meantime is skipped and the scan carries on.
+///
+/// Result is deterministic regardless of thread count: output card can only
+/// match inputs equal to its unmasked value, and such inputs are
+/// interchangeable, so it does not matter which of them it claims.
+///
+#[cfg(feature = "parallel")]
+pub fn verify_shuffle_parallel(
+    masked_before: &[G1Affine],
+    masked_after: &[G1Affine],
+    pk: &G2Affine,
+) -> Result<(), &'static str> {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use rayon::prelude::*;
+
+    if masked_before.len() < masked_after.len() {
+        return Err("Masked before must at least same length as masked after");
+    }
+
+    let pk_prepared = G2Prepared::from(*pk);
+    let neg_g2_gen = -G2Affine::generator();
+    let neg_g2_prepared = G2Prepared::from(neg_g2_gen);
+
+    let available_before: Vec<AtomicBool> =
+        masked_before.iter().map(|_| AtomicBool::new(true)).collect();
+
+    let all_matched = masked_after.par_iter().all(|point_after| {
+        masked_before
+            .iter()
+            .zip(&available_before)
+            .any(|(point_before, available)| {
+                available.load(Ordering::Acquire)
+                    // e(card_after, -G2) * e(card_before, PK) == 1
+                    && bool::from(
+                        Bls12::multi_miller_loop(&[
+                            (point_after, &neg_g2_prepared),
+                            (point_before, &pk_prepared),
+                        ])
+                        .final_exponentiation()
+                        .is_identity(),
+                    )
+                    && available
+                        .compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+            })
+    });
+
+    if !all_matched {
+        return Err("Cryptographic forgery detected");
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ShuffleTrace {
     pub after_index: usize,
     pub claimed_before_index: usize,
 }
 
+/// Length of single shuffle trace in wire format, one byte per index
+pub const SHUFFLE_TRACE_LEN: usize = 2;
+
+impl ShuffleTrace {
+    /// Encode trace as bytes, which fits decks of up to 256 cards
+    pub fn to_bytes(&self) -> Result<[u8; SHUFFLE_TRACE_LEN], &'static str> {
+        let after_index = u8::try_from(self.after_index).map_err(|_| "Index Error")?;
+        let claimed_before_index =
+            u8::try_from(self.claimed_before_index).map_err(|_| "Index Error")?;
+        Ok([after_index, claimed_before_index])
+    }
+
+    /// Decode trace of a shuffle of deck with `deck_len` cards
+    pub fn from_bytes(data: &[u8], deck_len: usize) -> Result<Self, &'static str> {
+        let &[after_index, claimed_before_index] = data else {
+            return Err("Len Error");
+        };
+        let trace = Self {
+            after_index: after_index as usize,
+            claimed_before_index: claimed_before_index as usize,
+        };
+        if trace.after_index >= deck_len || trace.claimed_before_index >= deck_len {
+            return Err("Index Error");
+        }
+        Ok(trace)
+    }
+}
+
+/// Encode traces of whole shuffle, e.g. to keep calldata small
+pub fn encode_traces(traces: &[ShuffleTrace]) -> Result<Vec<u8>, &'static str> {
+    let mut data = Vec::with_capacity(traces.len() * SHUFFLE_TRACE_LEN);
+    for trace in traces {
+        data.extend(trace.to_bytes()?);
+    }
+    Ok(data)
+}
+
+/// Decode traces of shuffle of deck with `deck_len` cards.
+/// 
+/// Every card must be traced, so data shorter or longer than `deck_len`
+/// traces is rejected.
+/// 
+pub fn decode_traces(data: &[u8], deck_len: usize) -> Result<Vec<ShuffleTrace>, &'static str> {
+    if data.len() != deck_len * SHUFFLE_TRACE_LEN {
+        return Err("Len Error");
+    }
+    data.chunks_exact(SHUFFLE_TRACE_LEN)
+        .map(|chunk| ShuffleTrace::from_bytes(chunk, deck_len))
+        .collect()
+}
+
 /// Verifies that "masked_before" data has been shuffled into "masked_after"
 /// data with signing key corresponding to public key.
 /// 
@@ -116,12 +403,29 @@ pub fn verify_shuffle_traced(
     pk: &G2Affine,
     traces: &[ShuffleTrace], // Only M traces submitted
 ) -> Result<(), &'static str> {
-    let pk_prepared = G2Prepared::from(*pk);
+    verify_shuffle_traced_prepared(masked_before, masked_after, &G2Prepared::from(*pk), traces)
+}
+
+/// Same as `verify_shuffle_traced`, but with public key already prepared for
+/// pairing, so that repeated verification passes prepare the key only once.
+pub fn verify_shuffle_traced_prepared(
+    masked_before: &[G1Affine],
+    masked_after: &[G1Affine],
+    pk_prepared: &G2Prepared,
+    traces: &[ShuffleTrace],
+) -> Result<(), &'static str> {
     let neg_g2_gen = -G2Affine::generator();
     let neg_g2_prepared = G2Prepared::from(neg_g2_gen);
 
     // 1. THE BIJECTION CHECK
-    let mut used_before_indices = HashSet::new();
+    // Every output card must be traced, otherwise cheater could replace
+    // cards that were left out of the traces.
+    if traces.len() != masked_after.len() {
+        return Err("Trace count must match number of shuffled cards");
+    }
+
+    let mut used_before_indices = BTreeSet::new();
+    let mut used_after_indices = BTreeSet::new();
 
     // Create a vector to hold all pairing terms for the batched Miller Loop.
     // Each trace adds 2 terms: one for the card after, one for the card before.
@@ -139,12 +443,17 @@ pub fn verify_shuffle_traced(
             return Err("Duplicate input index! Cheater attempted to clone a card.");
         }
 
+        // Ensure every output card is traced exactly once
+        if !used_after_indices.insert(trace.after_index) {
+            return Err("Duplicate output index! Cheater attempted to skip a card.");
+        }
+
         let point_after = &masked_after[trace.after_index];
         let point_before = &masked_before[trace.claimed_before_index];
 
         // Push the tuples for this specific trace into the batch array
         miller_loop_terms.push((point_after, &neg_g2_prepared));
-        miller_loop_terms.push((point_before, &pk_prepared));
+        miller_loop_terms.push((point_before, pk_prepared));
     }
 
     // 2. THE O(M) BATCHED MILLER LOOP
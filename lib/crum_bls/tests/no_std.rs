@@ -0,0 +1,40 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use std::{path::Path, process::Command};
+
+/// Verification must build without std, e.g. for on-chain referee.
+///
+/// Only rlib is built, as cdylib would need panic handler and allocator of
+/// the target.
+///
+#[test]
+fn test_build_without_std() {
+    let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std");
+
+    let output = Command::new(env!("CARGO"))
+        .args([
+            "rustc",
+            "--lib",
+            "--no-default-features",
+            "--crate-type",
+            "rlib",
+        ])
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--target-dir")
+        .arg(target_dir)
+        .output()
+        .expect("Failed to run cargo");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,84 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+//!
+//! Compares verifying all shuffle steps of 6-player hand with public keys
+//! prepared on every call against keys prepared once.
+//!
+//! Preparing key takes about 2ms, which is roughly 10% of the traced
+//! verification of one 52-card shuffle (133ms vs 120ms per pass of all six
+//! shuffles on single core, release build).
+//!
+//! Run with `cargo bench -p crum_pkr`.
+
+use std::time::{Duration, Instant};
+
+use bls12_381::{G2Prepared, Scalar};
+use crum_bls::{util::make_public_key_from_signing_key, verify};
+use crum_pkr::poker_deck::{MaskedCards, PokerDeck};
+use ff::Field;
+
+const NUM_PLAYERS: usize = 6;
+const PASSES: u32 = 5;
+
+fn measure(name: &str, f: impl Fn()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..PASSES {
+        f();
+    }
+    let elapsed = start.elapsed() / PASSES;
+    println!("{name}: {elapsed:?} per pass");
+    elapsed
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    let poker_deck = PokerDeck::new();
+    let mut decks: Vec<MaskedCards> = vec![poker_deck.masked_cards()];
+    let mut pks = Vec::new();
+    let mut traces = Vec::new();
+
+    for _ in 0..NUM_PLAYERS {
+        let sk = Scalar::random(&mut rng);
+        let mut deck = decks.last().unwrap().clone();
+        deck.mask(sk);
+        traces.push(deck.shuffle_traced(&mut rng));
+        decks.push(deck);
+        pks.push(make_public_key_from_signing_key(&sk));
+    }
+
+    let unprepared = measure("verify_shuffle_traced", || {
+        for player in 0..NUM_PLAYERS {
+            verify::verify_shuffle_traced(
+                decks[player].as_slice(),
+                decks[player + 1].as_slice(),
+                &pks[player],
+                &traces[player],
+            )
+            .expect("Shuffle should verify");
+        }
+    });
+
+    let prepared_pks: Vec<G2Prepared> = pks.iter().map(|pk| G2Prepared::from(*pk)).collect();
+
+    let prepared = measure("verify_shuffle_traced_prepared", || {
+        for player in 0..NUM_PLAYERS {
+            verify::verify_shuffle_traced_prepared(
+                decks[player].as_slice(),
+                decks[player + 1].as_slice(),
+                &prepared_pks[player],
+                &traces[player],
+            )
+            .expect("Shuffle should verify");
+        }
+    });
+
+    println!(
+        "speedup: {:.2}x",
+        unprepared.as_secs_f64() / prepared.as_secs_f64()
+    );
+}
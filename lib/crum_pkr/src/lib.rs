@@ -6,11 +6,22 @@
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
 pub mod poker_bets;
+pub mod poker_blinds;
 pub mod poker_deck;
+pub mod poker_driver;
+pub mod poker_error;
+pub mod poker_event;
 pub mod poker_hand;
+pub mod poker_hand_rank;
+pub mod poker_hand_timeout;
 pub mod poker_hand_verify;
+pub mod poker_odds;
+pub mod poker_replay;
+pub mod poker_snapshot;
 pub mod poker_state;
 pub mod poker_table;
+pub mod poker_tournament;
+pub mod poker_view;
 
 #[cfg(test)]
 pub mod tests;
@@ -5,29 +5,150 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
+use crate::poker_error::PokerError;
+
+/// Portion of the pot and players eligible to win it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u64,
+    pub eligible_players: Vec<usize>,
+}
+
+/// Betting decision of a player
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PokerAction {
+    Fold,
+    Check,
+    Call,
+    /// Raise to given total bet on current street
+    Raise(u64),
+    AllIn,
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PokerBettingState {
     player_chips: Vec<u64>,
     current_round_bets: Vec<Option<u64>>,
+    /// Total chips each player put in the pot during the hand
+    contributions: Vec<u64>,
     pot: u64,
     active_players: Vec<bool>,
+    /// Players who put their whole stack in, and so have no more actions
+    all_in: Vec<bool>,
     current_highest_bet: u64,
+    /// Minimum size of the opening bet on each street
+    big_blind: u64,
+    /// Size of the last full bet or raise on the current street
+    last_raise_size: u64,
+    /// Big blind who has not yet acted preflop, and so may still check or raise
+    big_blind_option: Option<usize>,
 }
 
 impl PokerBettingState {
     pub fn new(num_players: usize, initial_chips: u64) -> Self {
+        Self::from_stacks(vec![initial_chips; num_players])
+    }
+
+    /// Start betting with given stack of chips for each player
+    pub fn from_stacks(player_chips: Vec<u64>) -> Self {
+        let num_players = player_chips.len();
         Self {
-            player_chips: vec![initial_chips; num_players],
+            player_chips,
             current_round_bets: vec![None; num_players],
+            contributions: vec![0; num_players],
             pot: 0,
             active_players: vec![true; num_players],
+            all_in: vec![false; num_players],
             current_highest_bet: 0,
+            big_blind: 0,
+            last_raise_size: 0,
+            big_blind_option: None,
+        }
+    }
+
+    /// Set the minimum opening bet, which is also the minimum raise size
+    pub fn set_big_blind(&mut self, big_blind: u64) {
+        self.big_blind = big_blind;
+        self.last_raise_size = big_blind;
+    }
+
+    pub const fn get_big_blind(&self) -> u64 {
+        self.big_blind
+    }
+
+    /// Tell the smallest total bet this street that player may raise to
+    pub fn min_raise_to(&self, player: usize) -> u64 {
+        let min_raise_to = self.current_highest_bet + self.last_raise_size.max(1);
+        let max_bet = self.current_round_bets[player].unwrap_or(0) + self.player_chips[player];
+        min_raise_to.min(max_bet)
+    }
+
+    /// Tell amount of chips player must put in to make the smallest raise
+    pub fn min_raise_amount(&self, player: usize) -> u64 {
+        self.min_raise_to(player) - self.current_round_bets[player].unwrap_or(0)
+    }
+
+    /// Post a forced bet (blind), which is not subject to minimum raise rules
+    ///
+    /// Returns amount posted, which is less than the blind if player is all-in.
+    ///
+    pub fn post_blind(&mut self, player: usize, amount: u64) -> Result<u64, PokerError> {
+        if !self.active_players[player] {
+            return Err(PokerError::PlayerFolded);
+        }
+
+        let amount = amount.min(self.player_chips[player]);
+
+        self.player_chips[player] -= amount;
+        let player_bet = amount + self.current_round_bets[player].unwrap_or(0);
+        self.current_round_bets[player] = Some(player_bet);
+        self.contributions[player] += amount;
+        self.pot += amount;
+        self.current_highest_bet = self.current_highest_bet.max(player_bet);
+        self.update_all_in(player);
+
+        Ok(amount)
+    }
+
+    /// Post big blind, who gets the option to act even if everyone just calls.
+    ///
+    /// Player who is all-in posting the blind has no option left.
+    ///
+    pub fn post_big_blind(&mut self, player: usize, amount: u64) -> Result<u64, PokerError> {
+        let amount = self.post_blind(player, amount)?;
+        if self.player_chips[player] > 0 {
+            self.big_blind_option = Some(player);
+        }
+        Ok(amount)
+    }
+
+    /// Every active player posts an ante into the pot before the blinds.
+    ///
+    /// Antes are dead money, so they do not count towards the bet on the
+    /// street. Player short of the full ante goes all-in for what they have.
+    /// Returns amount posted by each player.
+    ///
+    pub fn post_antes(&mut self, ante: u64) -> Vec<(usize, u64)> {
+        let mut posted = vec![];
+        for player in 0..self.player_chips.len() {
+            if !self.active_players[player] {
+                continue;
+            }
+            let amount = ante.min(self.player_chips[player]);
+            self.player_chips[player] -= amount;
+            self.contributions[player] += amount;
+            self.pot += amount;
+            self.update_all_in(player);
+            posted.push((player, amount));
         }
+        posted
     }
 
-    pub fn call_amount_required(&self, player: usize) -> Result<u64, Vec<u8>> {
+    pub fn call_amount_required(&self, player: usize) -> Result<u64, PokerError> {
         if !self.active_players[player] {
-            return Err(b"Player has already folded".to_vec());
+            return Err(PokerError::PlayerFolded);
         }
 
         let amount_needed_to_call =
@@ -44,12 +165,63 @@ impl PokerBettingState {
         &self.active_players
     }
 
+    /// Tell whether player has put their whole stack in
+    pub fn is_all_in(&self, player: usize) -> bool {
+        self.all_in[player]
+    }
+
+    /// Players who are still in the hand and have chips left to act with
+    pub fn get_acting_players(&self) -> Vec<bool> {
+        self.active_players
+            .iter()
+            .zip(&self.all_in)
+            .map(|(&is_active, &is_all_in)| is_active && !is_all_in)
+            .collect()
+    }
+
+    fn update_all_in(&mut self, player: usize) {
+        if self.player_chips[player] == 0 {
+            self.all_in[player] = true;
+        }
+    }
+
+    /// Tell whether state of every player is tracked and big blind option is on a seat
+    pub(super) fn is_consistent(&self, num_players: usize) -> bool {
+        self.player_chips.len() == num_players
+            && self.current_round_bets.len() == num_players
+            && self.contributions.len() == num_players
+            && self.active_players.len() == num_players
+            && self.all_in.len() == num_players
+            && self
+                .big_blind_option
+                .is_none_or(|player| player < num_players)
+    }
+
+    /// Tell total chips in the pot
+    pub fn pot(&self) -> u64 {
+        self.pot
+    }
+
+    /// Tell highest total bet on current street
+    pub fn current_highest_bet(&self) -> u64 {
+        self.current_highest_bet
+    }
+
+    /// Tell whether player has not folded
+    pub fn is_active(&self, player: usize) -> bool {
+        self.active_players[player]
+    }
+
     /// Process a player's betting action based purely on the amount of chips put in.
     /// amount = 0 means Check (if no bet to call) or Fold (if facing a bet).
     /// amount > 0 means Call or Raise.
-    pub fn process_action(&mut self, player: usize, amount: u64) -> Result<(), Vec<u8>> {
+    pub fn process_action(&mut self, player: usize, amount: u64) -> Result<(), PokerError> {
         if !self.active_players[player] {
-            return Err(b"Player has already folded".to_vec());
+            return Err(PokerError::PlayerFolded);
+        }
+
+        if self.big_blind_option == Some(player) {
+            self.big_blind_option = None;
         }
 
         // How much this player needs to put in to stay in the hand
@@ -61,26 +233,45 @@ impl PokerBettingState {
                 // They owe chips but put in 0. This is a Fold.
                 self.active_players[player] = false;
             } else {
-                // They owe nothing and put in 0. This is a Check.
-                self.current_round_bets[player] = Some(0);
+                // They owe nothing and put in 0. This is a Check, which keeps
+                // any blind they posted as their bet.
+                self.current_round_bets[player] = Some(self.round_bet(player));
             }
         } else {
             // They are putting chips in. Verify it's legal.
-            if amount < amount_needed_to_call {
-                return Err(b"Amount is less than the required call amount".to_vec());
-                // Note: True all-in rules (putting in less than the call amount because
-                // the stack is empty) would be handled right here.
+            if self.player_chips[player] < amount {
+                return Err(PokerError::InsufficientChips);
             }
 
-            if self.player_chips[player] < amount {
-                return Err(b"Not enough chips in stack".to_vec());
+            // Player putting in their whole stack is all-in, and may do so even
+            // for less than the call amount. They will only compete for the
+            // portion of the pot they matched.
+            let is_all_in = amount == self.player_chips[player];
+
+            if amount < amount_needed_to_call && !is_all_in {
+                return Err(PokerError::BetTooSmall);
+            }
+
+            // Raise must be at least the size of the last bet or raise on this
+            // street, unless player is all-in. All-in for less does not reopen
+            // the minimum, so the last raise size stays as it was.
+            let raise_size = amount - amount_needed_to_call.min(amount);
+            if raise_size > 0 && raise_size < self.last_raise_size && !is_all_in {
+                return Err(PokerError::RaiseTooSmall {
+                    min: self.current_highest_bet + self.last_raise_size,
+                });
+            }
+            if raise_size >= self.last_raise_size {
+                self.last_raise_size = self.last_raise_size.max(raise_size);
             }
 
             // Move chips from player stack to the pot
             self.player_chips[player] -= amount;
             self.current_round_bets[player] =
                 Some(amount + self.current_round_bets[player].unwrap_or(0));
+            self.contributions[player] += amount;
             self.pot += amount;
+            self.update_all_in(player);
 
             // If they put in more than what was needed to call, it's a raise.
             // Update the new highest bet for everyone else to match.
@@ -92,6 +283,56 @@ impl PokerBettingState {
         Ok(())
     }
 
+    /// Tell total chips player has bet on current street
+    pub fn round_bet(&self, player: usize) -> u64 {
+        self.current_round_bets[player].unwrap_or(0)
+    }
+
+    /// Tell which action is taken by putting given amount of chips in
+    pub fn action_for_amount(&self, player: usize, amount: u64) -> Result<PokerAction, PokerError> {
+        let amount_needed_to_call = self.call_amount_required(player)?;
+
+        if amount == 0 {
+            if amount_needed_to_call > 0 {
+                Ok(PokerAction::Fold)
+            } else {
+                Ok(PokerAction::Check)
+            }
+        } else if self.player_chips[player] < amount {
+            Err(PokerError::InsufficientChips)
+        } else if self.player_chips[player] == amount {
+            Ok(PokerAction::AllIn)
+        } else if amount < amount_needed_to_call {
+            Err(PokerError::BetTooSmall)
+        } else if amount == amount_needed_to_call {
+            Ok(PokerAction::Call)
+        } else {
+            Ok(PokerAction::Raise(self.round_bet(player) + amount))
+        }
+    }
+
+    /// Process a player's betting action
+    pub fn apply_action(&mut self, player: usize, action: PokerAction) -> Result<(), PokerError> {
+        let amount_needed_to_call = self.call_amount_required(player)?;
+        let chips = self.player_chips[player];
+
+        match action {
+            PokerAction::Fold => {
+                self.active_players[player] = false;
+                Ok(())
+            }
+            PokerAction::Check if amount_needed_to_call == 0 => self.process_action(player, 0),
+            PokerAction::Call if amount_needed_to_call > 0 => {
+                self.process_action(player, amount_needed_to_call.min(chips))
+            }
+            PokerAction::Raise(total) if total > self.current_highest_bet => {
+                self.process_action(player, total - self.round_bet(player))
+            }
+            PokerAction::AllIn if chips > 0 => self.process_action(player, chips),
+            _ => Err(PokerError::IllegalAction),
+        }
+    }
+
     pub fn is_betting_round_complete(&self) -> bool {
         let active_count = self.active_players.iter().filter(|&&active| active).count();
 
@@ -100,25 +341,174 @@ impl PokerBettingState {
             return true;
         }
 
+        // With everyone else all-in, there is nobody left to bet against once
+        // last player with chips has matched the highest bet
+        let mut acting_players = (0..self.active_players.len())
+            .filter(|&player| self.active_players[player] && !self.all_in[player]);
+        if acting_players.clone().count() <= 1
+            && acting_players.all(|player| self.round_bet(player) >= self.current_highest_bet)
+        {
+            return true;
+        }
+
         // The round is complete when every active player's current bet matches the highest bet
-        for (player, &is_active) in self.active_players.iter().enumerate() {
-            if !is_active {
-                continue;
+        self.players_to_act().is_empty()
+    }
+
+    /// Tell active players who have not yet acted or not matched the highest bet.
+    ///
+    /// All-in players cannot act any more, so they are never waited on.
+    ///
+    pub fn players_to_act(&self) -> Vec<usize> {
+        self.active_players
+            .iter()
+            .enumerate()
+            .filter(|&(player, &is_active)| {
+                is_active
+                    && !self.all_in[player]
+                    && (self.big_blind_option == Some(player)
+                        || self.current_round_bets[player]
+                            .is_none_or(|player_bet| player_bet < self.current_highest_bet))
+            })
+            .map(|(player, _)| player)
+            .collect()
+    }
+
+    /// Tell total chips player put in the pot during the hand
+    pub fn contribution(&self, player: usize) -> u64 {
+        self.contributions[player]
+    }
+
+    /// Splits the pot into main pot and side pots.
+    ///
+    /// Player who went all-in is only eligible for the portion of the pot
+    /// they matched, and each all-in amount starts a new side pot. Folded
+    /// players are never eligible, but their chips stay in the pots.
+    ///
+    pub fn pots(&self) -> Vec<Pot> {
+        let is_all_in = |player: usize| self.active_players[player] && self.all_in[player];
+
+        let max_contribution = self.contributions.iter().copied().max().unwrap_or(0);
+
+        let mut levels: Vec<u64> = (0..self.contributions.len())
+            .filter(|&player| is_all_in(player))
+            .map(|player| self.contributions[player])
+            .chain(std::iter::once(max_contribution))
+            .filter(|&level| level > 0)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous_level = 0;
+
+        for level in levels {
+            let amount = self
+                .contributions
+                .iter()
+                .map(|&c| c.min(level) - c.min(previous_level))
+                .sum();
+
+            let eligible_players: Vec<usize> = (0..self.contributions.len())
+                .filter(|&player| {
+                    self.active_players[player] && self.contributions[player] >= level
+                })
+                .collect();
+
+            match pots.last_mut() {
+                // Nobody left to win chips above this level, so they go to previous pot
+                Some(last) if eligible_players.is_empty() => last.amount += amount,
+                _ => pots.push(Pot {
+                    amount,
+                    eligible_players,
+                }),
             }
-            let Some(player_bet) = self.current_round_bets[player] else {
-                return false;
-            };
-            if player_bet < self.current_highest_bet {
-                return false;
+
+            previous_level = level;
+        }
+
+        pots
+    }
+
+    /// Splits the pot evenly between winners and credits their stacks.
+    /// Chips that cannot be split evenly go one each to winners in the order given,
+    /// so winners should be given in seat order starting left of the button.
+    pub fn award_pot(&mut self, winners: &[usize]) -> Vec<(usize, u64)> {
+        let awards = self.split_amount(self.pot, winners);
+        self.clear_pot();
+        awards
+    }
+
+    /// Awards each pot as returned by `pots()` to its winners, crediting their stacks.
+    /// Winners of each pot are given in the same order as pots.
+    pub fn award_pots(&mut self, winners: &[Vec<usize>]) -> Vec<(usize, u64)> {
+        self.award_pots_in_runs(&[winners.to_vec()])
+    }
+
+    /// Awards equal share of each pot to its winners on each run of the board.
+    /// Chips that cannot be split evenly between runs go to the earlier runs.
+    pub fn award_pots_in_runs(&mut self, runs: &[Vec<Vec<usize>>]) -> Vec<(usize, u64)> {
+        let mut awards: Vec<(usize, u64)> = Vec::new();
+        let num_runs = runs.len().max(1) as u64;
+
+        for (index, pot) in self.pots().iter().enumerate() {
+            let share = pot.amount / num_runs;
+            let mut odd_chips = pot.amount % num_runs;
+
+            for run_winners in runs {
+                let mut amount = share;
+                if odd_chips > 0 {
+                    amount += 1;
+                    odd_chips -= 1;
+                }
+                let Some(pot_winners) = run_winners.get(index) else {
+                    continue;
+                };
+                for (player, amount) in self.split_amount(amount, pot_winners) {
+                    match awards.iter_mut().find(|(p, _)| *p == player) {
+                        Some((_, total)) => *total += amount,
+                        None => awards.push((player, amount)),
+                    }
+                }
             }
         }
 
-        true
+        self.clear_pot();
+        awards
+    }
+
+    fn split_amount(&mut self, amount: u64, winners: &[usize]) -> Vec<(usize, u64)> {
+        if winners.is_empty() {
+            return vec![];
+        }
+
+        let share = amount / winners.len() as u64;
+        let mut odd_chips = amount % winners.len() as u64;
+
+        winners
+            .iter()
+            .map(|&player| {
+                let mut amount = share;
+                if odd_chips > 0 {
+                    amount += 1;
+                    odd_chips -= 1;
+                }
+                self.player_chips[player] += amount;
+                (player, amount)
+            })
+            .collect()
+    }
+
+    fn clear_pot(&mut self) {
+        self.pot = 0;
+        self.contributions.fill(0);
     }
 
     /// Resets the street-level tracking variables for the next round (Flop, Turn, River)
     pub fn next_street(&mut self) {
         self.current_round_bets.fill(None);
         self.current_highest_bet = 0;
+        self.big_blind_option = None;
+        self.last_raise_size = self.big_blind;
     }
 }
@@ -0,0 +1,142 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use crate::poker_error::PokerError;
+
+/// How long players play at blind level
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LevelLength {
+    /// Number of hands started at the level
+    Hands(usize),
+    /// Time since the level started, in units passed to `tick`
+    Duration(u64),
+}
+
+/// Blinds and ante posted during single level of the schedule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindLevel {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+    pub length: LevelLength,
+}
+
+impl BlindLevel {
+    pub const fn new(small_blind: u64, big_blind: u64, ante: u64, length: LevelLength) -> Self {
+        Self {
+            small_blind,
+            big_blind,
+            ante,
+            length,
+        }
+    }
+}
+
+/// Blind levels rising over a tournament.
+///
+/// Last level lasts until the end.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindSchedule {
+    levels: Vec<BlindLevel>,
+    current_level: usize,
+    /// Hands started at current level
+    hands_at_level: usize,
+    /// Time current level started, set on first tick at the level
+    level_started_at: Option<u64>,
+}
+
+impl BlindSchedule {
+    pub fn new(levels: Vec<BlindLevel>) -> Result<Self, PokerError> {
+        if levels.is_empty() {
+            return Err(PokerError::EmptyBlindSchedule);
+        }
+        Ok(Self {
+            levels,
+            current_level: 0,
+            hands_at_level: 0,
+            level_started_at: None,
+        })
+    }
+
+    /// Tell blinds of the level being played
+    pub fn current_level(&self) -> &BlindLevel {
+        &self.levels[self.current_level]
+    }
+
+    /// Tell index of the level being played
+    pub const fn current_level_index(&self) -> usize {
+        self.current_level
+    }
+
+    pub fn levels(&self) -> &[BlindLevel] {
+        &self.levels
+    }
+
+    /// Move on to next level; false when already at the last level
+    pub fn advance_level(&mut self) -> bool {
+        if self.current_level + 1 >= self.levels.len() {
+            return false;
+        }
+        self.current_level += 1;
+        self.hands_at_level = 0;
+        self.level_started_at = None;
+        true
+    }
+
+    /// Let time pass, moving on to next level once current level is over.
+    ///
+    /// Clock of the level starts on first tick at the level. Returns true
+    /// if level was advanced.
+    ///
+    pub fn tick(&mut self, now: u64) -> bool {
+        let LevelLength::Duration(duration) = self.current_level().length else {
+            return false;
+        };
+
+        let Some(started_at) = self.level_started_at else {
+            self.level_started_at = Some(now);
+            return false;
+        };
+
+        if now < started_at.saturating_add(duration) || !self.advance_level() {
+            return false;
+        }
+
+        // Next level starts when previous one was due to end
+        self.level_started_at = Some(started_at.saturating_add(duration));
+        true
+    }
+
+    /// Blinds of the next hand to start
+    pub fn next_hand_level(&self) -> &BlindLevel {
+        if self.is_level_over() {
+            let next_level = (self.current_level + 1).min(self.levels.len() - 1);
+            &self.levels[next_level]
+        } else {
+            self.current_level()
+        }
+    }
+
+    /// Count hand started at blinds of `next_hand_level()`
+    pub fn hand_started(&mut self) {
+        if self.is_level_over() {
+            self.advance_level();
+        }
+        self.hands_at_level += 1;
+    }
+
+    fn is_level_over(&self) -> bool {
+        matches!(
+            self.current_level().length,
+            LevelLength::Hands(hands) if self.hands_at_level >= hands
+        )
+    }
+}
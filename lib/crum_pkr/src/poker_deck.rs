@@ -5,62 +5,410 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
+use std::collections::HashMap;
+
+use crate::poker_error::PokerError;
+
 use alloy_primitives::Keccak256;
 use bls12_381::G1Affine;
-use crum_bls::{hash_to_curve::hash_to_curve, sign, types::SigningKey, verify};
+use crum_bls::{
+    hash_to_curve::hash_to_curve,
+    sign,
+    types::{SecretKey, SigningKey},
+    verify,
+};
+use itertools::Itertools;
 use pairing::group::Curve;
-use rand::{Rng, seq::SliceRandom};
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha20Rng;
 
-#[derive(Default, Clone, Debug)]
-pub struct PokerCard(Vec<u8>);
+/// Rank of the card, valued 2..=14 with Ace high
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Rank {
+    Two = 2,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    /// All ranks from Two to Ace
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// Tell rank value (2..=14, Ace high)
+    pub const fn value(self) -> u8 {
+        self as u8
+    }
+
+    /// Tell character used in card code, e.g. 'T' for Ten
+    pub const fn to_char(self) -> char {
+        b"23456789TJQKA"[self as usize - 2] as char
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|rank| rank.to_char() == c)
+    }
+}
+
+/// Suit of the card
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    /// All suits in the order cards are laid out in the deck
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// Tell character used in card code, e.g. 's' for Spades
+    pub const fn to_char(self) -> char {
+        match self {
+            Suit::Spades => 's',
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+        }
+    }
 
-impl ToString for PokerCard {
-    fn to_string(&self) -> String {
-        String::from_utf8(self.0.clone()).unwrap()
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|suit| suit.to_char() == c)
+    }
+}
+
+/// Playing card, ordered by rank (Ace high) and then by suit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct PokerCard {
+    rank: Rank,
+    suit: Suit,
+}
+
+impl PokerCard {
+    pub const fn new(rank: Rank, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+
+    pub const fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    pub const fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    /// Two-byte code of the card, e.g. b"As" for Ace of spades, which is
+    /// hashed to the base point of the card
+    pub const fn to_bytes(&self) -> [u8; 2] {
+        [self.rank.to_char() as u8, self.suit.to_char() as u8]
+    }
+
+    /// Index of the card (0..52) in rank-major order, matching `PokerDeck::new`,
+    /// i.e. 2s, 2h, 2d, 2c, 3s, ... Ac
+    pub const fn to_index(&self) -> u8 {
+        (self.rank as u8 - 2) * Suit::ALL.len() as u8 + self.suit as u8
+    }
+
+    /// Card at index given by `to_index()`, or None if index is out of range
+    pub const fn from_index(index: u8) -> Option<Self> {
+        let suits = Suit::ALL.len();
+        let rank = index as usize / suits;
+        if rank >= Rank::ALL.len() {
+            return None;
+        }
+        Some(Self::new(
+            Rank::ALL[rank],
+            Suit::ALL[index as usize % suits],
+        ))
+    }
+}
+
+impl std::fmt::Display for PokerCard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.rank.to_char(), self.suit.to_char())
+    }
+}
+
+/// Reason card code could not be parsed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// Code must be exactly two characters, rank and suit
+    WrongLength,
+    /// Rank must be one of 23456789TJQKA
+    InvalidRank(char),
+    /// Suit must be one of shdc
+    InvalidSuit(char),
+    /// Rank may appear only once in the deck
+    DuplicateRank(char),
+    /// Suit may appear only once in the deck
+    DuplicateSuit(char),
+}
+
+impl std::fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseCardError::WrongLength => write!(f, "Card code must be two characters"),
+            ParseCardError::InvalidRank(c) => write!(f, "Invalid card rank '{}'", c),
+            ParseCardError::InvalidSuit(c) => write!(f, "Invalid card suit '{}'", c),
+            ParseCardError::DuplicateRank(c) => write!(f, "Duplicate card rank '{}'", c),
+            ParseCardError::DuplicateSuit(c) => write!(f, "Duplicate card suit '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl From<ParseCardError> for PokerError {
+    fn from(_: ParseCardError) -> Self {
+        PokerError::InvalidCard
+    }
+}
+
+impl std::str::FromStr for PokerCard {
+    type Err = ParseCardError;
+
+    /// Parse card code, e.g. "As" for Ace of spades
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(ParseCardError::WrongLength);
+        };
+        Ok(Self::new(
+            Rank::from_char(rank).ok_or(ParseCardError::InvalidRank(rank))?,
+            Suit::from_char(suit).ok_or(ParseCardError::InvalidSuit(suit))?,
+        ))
+    }
+}
+
+impl From<PokerCard> for String {
+    fn from(card: PokerCard) -> Self {
+        card.to_string()
+    }
+}
+
+impl TryFrom<String> for PokerCard {
+    type Error = ParseCardError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        code.parse()
+    }
+}
+
+#[cfg(feature = "fancy_cards")]
+impl PokerCard {
+    /// Unicode playing card glyph, e.g. '🂡' for Ace of spades
+    pub fn to_glyph(&self) -> char {
+        // Ace comes first, and Knight sits between Jack and Queen
+        let rank = match self.rank {
+            Rank::Ace => 0x1,
+            Rank::Queen => 0xD,
+            Rank::King => 0xE,
+            rank => rank as u32,
+        };
+        char::from_u32(0x1F0A0 + 0x10 * self.suit as u32 + rank).unwrap_or(HIDDEN_CARD_GLYPH)
+    }
+}
+
+/// Glyph of card shown face down
+#[cfg(feature = "fancy_cards")]
+pub const HIDDEN_CARD_GLYPH: char = '🂠';
+
+/// Cards separated by commas, with cards not revealed shown as hidden.
+///
+/// Cards are shown by their codes, e.g. "As, _!, Td", or with `fancy_cards`
+/// feature as Unicode playing card glyphs.
+///
+pub struct CardSet<'a>(pub &'a [Option<PokerCard>]);
+
+#[cfg(not(feature = "fancy_cards"))]
+impl std::fmt::Display for CardSet<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self
+            .0
+            .iter()
+            .map(|card| card.map_or("_!".to_string(), |card| card.to_string()))
+            .join(", ");
+        f.write_str(&s)
+    }
+}
+
+#[cfg(feature = "fancy_cards")]
+impl std::fmt::Display for CardSet<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self
+            .0
+            .iter()
+            .map(|card| card.map_or(HIDDEN_CARD_GLYPH, |card| card.to_glyph()))
+            .join(", ");
+        f.write_str(&s)
     }
 }
 
 #[derive(Default, Clone, Debug)]
 pub struct PokerDeck {
-    poker_cards: Vec<PokerCard>,
-    cards_g1: Vec<G1Affine>,
+    pub(super) poker_cards: Vec<PokerCard>,
+    pub(super) cards_g1: Vec<G1Affine>,
+    /// Index of the card keyed by compressed base point
+    pub(super) card_index: HashMap<[u8; 48], usize>,
 }
 
 impl PokerDeck {
     pub fn new() -> Self {
-        let poker_cards: Vec<PokerCard> = b"23456789TJQKA"
+        Self::from_poker_cards(
+            Rank::ALL
+                .into_iter()
+                .flat_map(|rank| Suit::ALL.map(|suit| PokerCard::new(rank, suit)))
+                .collect(),
+        )
+    }
+
+    /// Create 36-card short deck (6+ Hold'em), with no cards below Six
+    pub fn new_short_deck() -> Self {
+        Self::from_poker_cards(
+            Rank::ALL
+                .into_iter()
+                .filter(|rank| rank.value() >= 6)
+                .flat_map(|rank| Suit::ALL.map(|suit| PokerCard::new(rank, suit)))
+                .collect(),
+        )
+    }
+
+    /// Create deck of given ranks and suits, e.g. b"6789TJQKA" and b"shdc"
+    /// for 36-card short deck (6+ Hold'em).
+    ///
+    /// Cards are laid out rank-major in order given. Each rank and suit may
+    /// be given only once, as deck must not hold the same card twice.
+    ///
+    pub fn new_with_ranks(ranks: &[u8], suits: &[u8]) -> Result<Self, ParseCardError> {
+        let ranks: Vec<Rank> = ranks
             .iter()
-            .flat_map(|rank| b"shdc".iter().map(move |suit| vec![*rank, *suit]))
-            .map(|v| PokerCard(v))
-            .collect();
+            .map(|&c| Rank::from_char(c as char).ok_or(ParseCardError::InvalidRank(c as char)))
+            .collect::<Result<_, _>>()?;
+        let suits: Vec<Suit> = suits
+            .iter()
+            .map(|&c| Suit::from_char(c as char).ok_or(ParseCardError::InvalidSuit(c as char)))
+            .collect::<Result<_, _>>()?;
+
+        if let Some(rank) = ranks.iter().duplicates().next() {
+            return Err(ParseCardError::DuplicateRank(rank.to_char()));
+        }
+        if let Some(suit) = suits.iter().duplicates().next() {
+            return Err(ParseCardError::DuplicateSuit(suit.to_char()));
+        }
+
+        Ok(Self::from_poker_cards(
+            ranks
+                .iter()
+                .flat_map(|&rank| suits.iter().map(move |&suit| PokerCard::new(rank, suit)))
+                .collect(),
+        ))
+    }
 
+    fn from_poker_cards(poker_cards: Vec<PokerCard>) -> Self {
         let cards_g1: Vec<G1Affine> = poker_cards
             .iter()
-            .map(|card| hash_to_curve(&card.0).to_affine())
+            .map(|card| hash_to_curve(&card.to_bytes()).to_affine())
+            .collect();
+
+        let card_index = cards_g1
+            .iter()
+            .enumerate()
+            .map(|(index, card_g1)| (card_g1.to_compressed(), index))
             .collect();
 
         Self {
             poker_cards,
             cards_g1,
+            card_index,
         }
     }
 
+    /// Check every base point is hashed from code of its card, so that player
+    /// handed tampered or reordered deck can refuse it before shuffling.
+    ///
+    /// Base points are recomputed, so this is as costly as creating the deck.
+    ///
+    pub fn verify(&self) -> bool {
+        let cards_match = self.poker_cards.len() == self.cards_g1.len()
+            && self
+                .poker_cards
+                .iter()
+                .zip(&self.cards_g1)
+                .all(|(card, card_g1)| hash_to_curve(&card.to_bytes()).to_affine() == *card_g1);
+
+        let index_matches = self.card_index.len() == self.cards_g1.len()
+            && self.cards_g1.iter().enumerate().all(|(index, card_g1)| {
+                self.card_index.get(&card_g1.to_compressed()) == Some(&index)
+            });
+
+        cards_match && index_matches
+    }
+
+    /// Tell which card has been revealed, or None if point is not a card
     pub fn find_card(&self, revealed_point: G1Affine) -> Option<PokerCard> {
-        let Some(card_index) = self.cards_g1.iter().position(|x| revealed_point.eq(x)) else {
-            return None;
-        };
+        let card_index = self.card_index.get(&revealed_point.to_compressed())?;
+
+        self.poker_cards.get(*card_index).cloned()
+    }
 
-        self.poker_cards.get(card_index).cloned()
+    /// Tell which card is at index in the deck, which for a full deck is
+    /// same as `PokerCard::to_index()`
+    pub fn card_at(&self, index: usize) -> Option<PokerCard> {
+        self.poker_cards.get(index).cloned()
     }
 
     pub fn cards(&self) -> Vec<G1Affine> {
         self.cards_g1.clone()
     }
 
+    /// Borrow card points without cloning them
+    pub fn as_slice(&self) -> &[G1Affine] {
+        &self.cards_g1
+    }
+
+    /// Poker cards in the same order as their base points in `cards()`
+    pub fn poker_cards(&self) -> &[PokerCard] {
+        &self.poker_cards
+    }
+
     pub fn len(&self) -> usize {
         self.cards_g1.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.cards_g1.is_empty()
+    }
+
     pub fn masked_cards(&self) -> MaskedCards {
         MaskedCards::new(self.cards())
     }
@@ -74,8 +422,10 @@ impl PokerDeck {
     }
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaskedCards {
+    #[cfg_attr(feature = "serde", serde(with = "serde_g1"))]
     cards_g1: Vec<G1Affine>,
 }
 
@@ -88,24 +438,43 @@ impl MaskedCards {
         self.cards_g1.clone()
     }
 
+    /// Borrow card points without cloning them
+    pub fn as_slice(&self) -> &[G1Affine] {
+        &self.cards_g1
+    }
+
     pub fn cards_n(&self, count: usize) -> Vec<G1Affine> {
-        self.cards_g1[0..count].iter().cloned().collect()
+        self.cards_g1[0..count].to_vec()
     }
 
     pub fn len(&self) -> usize {
         self.cards_g1.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.cards_g1.is_empty()
+    }
+
     pub fn mask(&mut self, sk: SigningKey) {
         self.cards_g1
             .iter_mut()
             .for_each(|card_g1| *card_g1 = sign::mask(*card_g1, sk));
     }
 
+    /// Mask cards without copying the secret
+    pub fn mask_with(&mut self, sk: &SecretKey) {
+        self.cards_g1
+            .iter_mut()
+            .for_each(|card_g1| *card_g1 = sk.mask(*card_g1));
+    }
+
     pub fn shuffle(&mut self, rng: &mut impl Rng) {
         self.cards_g1.shuffle(rng);
     }
 
+    /// Shuffles cards (Fisher–Yates) recording for each output slot which input
+    /// slot the card came from, so that shuffle can later be verified using
+    /// `verify::verify_shuffle_traced` once shuffler's public key is known.
     pub fn shuffle_traced(&mut self, rng: &mut impl Rng) -> Vec<verify::ShuffleTrace> {
         let mut cards_g1_indexed: Vec<_> = self.cards_g1.iter().cloned().enumerate().collect();
 
@@ -124,22 +493,46 @@ impl MaskedCards {
         res
     }
 
+    /// Shuffles cards in order reproducible from the seed.
+    ///
+    /// Only for tests, debugging and audits replaying a game. Must never be
+    /// used in real play, as anyone knowing the seed learns the permutation,
+    /// which destroys the hidden shuffle.
+    ///
+    pub fn shuffle_seeded(&mut self, seed: [u8; 32]) {
+        self.shuffle(&mut ChaCha20Rng::from_seed(seed));
+    }
+
+    /// Same as `shuffle_traced`, but in order reproducible from the seed.
+    ///
+    /// Must never be used in real play, see `shuffle_seeded`.
+    ///
+    pub fn shuffle_traced_seeded(&mut self, seed: [u8; 32]) -> Vec<verify::ShuffleTrace> {
+        self.shuffle_traced(&mut ChaCha20Rng::from_seed(seed))
+    }
+
     pub fn hash(&self) -> [u8; 32] {
         let mut hasher = Keccak256::new();
         for card in &self.cards_g1 {
-            hasher.update(&card.to_compressed());
+            hasher.update(card.to_compressed());
         }
         hasher.finalize().into()
     }
 
-    pub fn deal(&mut self, count: usize) -> UnmaskedCards {
+    /// Deal cards from the top of the deck
+    pub fn deal(&mut self, count: usize) -> Result<UnmaskedCards, PokerError> {
+        if self.cards_g1.len() < count {
+            return Err(PokerError::DeckExhausted);
+        }
         let dealt_cards = self.cards_g1.drain(..count).collect();
-        UnmaskedCards::new(dealt_cards)
+        Ok(UnmaskedCards::new(dealt_cards))
     }
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnmaskedCards {
+    #[cfg_attr(feature = "serde", serde(with = "serde_g1"))]
     cards_g1: Vec<G1Affine>,
 }
 
@@ -152,14 +545,76 @@ impl UnmaskedCards {
         self.cards_g1.clone()
     }
 
+    /// Borrow card points without cloning them
+    pub fn as_slice(&self) -> &[G1Affine] {
+        &self.cards_g1
+    }
+
     pub fn len(&self) -> usize {
         self.cards_g1.len()
     }
 
-    pub fn unmask(&mut self, sk: SigningKey) {
-        let sk_inv = sk.invert().expect("Invalid signing key");
+    pub fn is_empty(&self) -> bool {
+        self.cards_g1.is_empty()
+    }
+
+    /// Put other cards after these
+    pub fn append(&mut self, mut other: UnmaskedCards) {
+        self.cards_g1.append(&mut other.cards_g1);
+    }
+
+    /// Remove mask of the key from cards; zero key is rejected and cards are left as they were
+    pub fn unmask(&mut self, sk: SigningKey) -> Result<(), PokerError> {
+        let sk_inv = sk
+            .invert()
+            .into_option()
+            .ok_or(PokerError::InvalidSigningKey)?;
         self.cards_g1
             .iter_mut()
             .for_each(|card_g1| *card_g1 = sign::mask(*card_g1, sk_inv));
+        Ok(())
+    }
+
+    /// Unmask cards without copying the secret
+    pub fn unmask_with(&mut self, sk: &SecretKey) -> Result<(), PokerError> {
+        let sk_inv = sk.invert().map_err(|_| PokerError::InvalidSigningKey)?;
+        self.cards_g1
+            .iter_mut()
+            .for_each(|card_g1| *card_g1 = sk_inv.mask(*card_g1));
+        Ok(())
+    }
+}
+
+/// Serializes card points in their 48-byte compressed form
+#[cfg(feature = "serde")]
+mod serde_g1 {
+    use bls12_381::G1Affine;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(
+        cards_g1: &[G1Affine],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            cards_g1
+                .iter()
+                .map(|card_g1| card_g1.to_compressed().to_vec()),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<G1Affine>, D::Error> {
+        Vec::<Vec<u8>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|bytes| {
+                let bytes: [u8; 48] = bytes
+                    .try_into()
+                    .map_err(|_| D::Error::custom("Invalid compressed point length"))?;
+                G1Affine::from_compressed(&bytes)
+                    .into_option()
+                    .ok_or_else(|| D::Error::custom("Invalid compressed point"))
+            })
+            .collect()
     }
 }
@@ -0,0 +1,157 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use std::collections::HashMap;
+
+use crum_bls::{types::PublicKey, verify::ShuffleTrace};
+
+use crate::{
+    poker_bets::PokerAction,
+    poker_deck::{MaskedCards, UnmaskedCards},
+    poker_error::PokerError,
+    poker_hand::PokerHand,
+    poker_replay::{HandEvent, TableEvent},
+    poker_state::PokerHandStateEnum,
+    poker_table::PokerTable,
+};
+
+/// Player taking part in a hand run by `HandDriver`.
+///
+/// Agent holds its own secret key, so the driver only ever sees masked cards
+/// and the public key revealed at the end of the hand.
+///
+pub trait PlayerAgent {
+    /// Mask deck with own key and shuffle it, keeping the trace until the end
+    fn provide_shuffle(&mut self, deck: &mut MaskedCards);
+
+    /// Remove own mask from cards
+    fn unmask(&mut self, cards: &mut UnmaskedCards) -> Result<(), PokerError>;
+
+    /// Choose betting action when it is player's turn
+    fn choose_action(&mut self, hand: &PokerHand, player: usize) -> PokerAction;
+
+    /// Choose to concede at showdown without revealing, if policy allows it
+    fn muck(&mut self, _hand: &PokerHand, _player: usize) -> bool {
+        false
+    }
+
+    /// Public key revealed at the end of the hand
+    fn public_key(&self) -> PublicKey;
+
+    /// Trace of the shuffle made in this hand
+    fn shuffle_trace(&mut self) -> Vec<ShuffleTrace>;
+}
+
+/// Runs the current hand at the table by asking agent of each player in turn.
+pub struct HandDriver<A: PlayerAgent> {
+    agents: HashMap<u32, A>,
+}
+
+impl<A: PlayerAgent> HandDriver<A> {
+    /// Drive hand with agents of players seated at the table
+    pub fn new(agents: HashMap<u32, A>) -> Self {
+        Self { agents }
+    }
+
+    pub fn get_agent(&self, player_id: u32) -> Option<&A> {
+        self.agents.get(&player_id)
+    }
+
+    pub fn get_agent_mut(&mut self, player_id: u32) -> Option<&mut A> {
+        self.agents.get_mut(&player_id)
+    }
+
+    /// Let player whose turn it is make their next move.
+    ///
+    /// Returns true once the hand is finished. Move is applied as a table
+    /// event, so it is recorded when the table records events.
+    ///
+    pub fn step(&mut self, poker_table: &mut PokerTable) -> Result<bool, PokerError> {
+        let Some(hand) = poker_table.get_current_hand() else {
+            return Err(PokerError::NoActiveHand);
+        };
+
+        let state = hand.get_current_state();
+        if state.is_finished() {
+            return Ok(true);
+        }
+
+        let Some(agent) = poker_table
+            .get_player(state.get_current_player())
+            .and_then(|player_id| self.agents.get_mut(&player_id))
+        else {
+            return Err(PokerError::InvalidPlayer);
+        };
+
+        let event = match state.to_enum() {
+            PokerHandStateEnum::Shuffle { player, is_dealer } => {
+                let mut deck = if is_dealer {
+                    hand.get_poker_deck().masked_cards()
+                } else {
+                    hand.get_shuffled_deck().clone()
+                };
+                agent.provide_shuffle(&mut deck);
+                HandEvent::ShuffleDeck { player, deck }
+            }
+            PokerHandStateEnum::SmallBlind { player } => HandEvent::SmallBlind { player },
+            PokerHandStateEnum::BigBlind { player } => HandEvent::BigBlind { player },
+            PokerHandStateEnum::Bet { round: _, player } => HandEvent::Action {
+                player,
+                action: agent.choose_action(hand, player),
+            },
+            PokerHandStateEnum::UnmaskHoleCards { player } => {
+                let mut cards = hand.get_player_cards().clone();
+                for (i, c) in cards.iter_mut().enumerate() {
+                    if i != player {
+                        agent.unmask(c)?;
+                    }
+                }
+                HandEvent::UnmaskHoleCards { player, cards }
+            }
+            PokerHandStateEnum::UnmaskCommunityCards { round, player } => {
+                let Some(mut cards) = hand.get_community_cards(round).cloned() else {
+                    return Err(PokerError::MalformedCards);
+                };
+                agent.unmask(&mut cards)?;
+                HandEvent::UnmaskCommunityCards {
+                    player,
+                    round,
+                    cards,
+                }
+            }
+            PokerHandStateEnum::UnmaskShowdown { player } if agent.muck(hand, player) => {
+                HandEvent::Muck { player }
+            }
+            PokerHandStateEnum::UnmaskShowdown { player } => {
+                let mut cards = hand.get_player_cards().clone();
+                agent.unmask(&mut cards[player])?;
+                HandEvent::UnmaskShowdown { player, cards }
+            }
+            PokerHandStateEnum::SubmitPublicKey { player } => HandEvent::SubmitPublicKey {
+                player,
+                pk: agent.public_key(),
+                traces: agent.shuffle_trace(),
+            },
+            PokerHandStateEnum::Finished => return Ok(true),
+            PokerHandStateEnum::Cheated { .. } | PokerHandStateEnum::Invalid => {
+                return Err(PokerError::WrongState);
+            }
+        };
+
+        poker_table.apply_event(TableEvent::Hand(event))?;
+
+        Ok(poker_table
+            .get_current_hand()
+            .is_some_and(|hand| hand.get_current_state().is_finished()))
+    }
+
+    /// Run current hand at the table until it is finished
+    pub fn run(&mut self, poker_table: &mut PokerTable) -> Result<(), PokerError> {
+        while !self.step(poker_table)? {}
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use std::fmt;
+
+/// Errors reported by poker table, hand and betting state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PokerError {
+    /// Action is not allowed in current state of the hand
+    WrongState,
+    /// Action submitted by a player other than the one to act
+    NotYourTurn,
+    /// Player index is out of range
+    InvalidPlayer,
+    /// Player has already folded
+    PlayerFolded,
+    /// Amount is less than the required call amount
+    BetTooSmall,
+    /// Raise is smaller than the last bet or raise; carries minimum total bet
+    RaiseTooSmall { min: u64 },
+    /// Action is not allowed in current betting state, e.g. check facing a bet
+    IllegalAction,
+    /// Not enough chips in player's stack
+    InsufficientChips,
+    /// Submitted cards do not match cards dealt
+    MalformedCards,
+    /// Number of unmasked cards submitted for a player or round differs from cards dealt
+    MalformedUnmask,
+    /// Not enough cards left in the deck to deal
+    DeckExhausted,
+    /// Card code is not valid, e.g. "Xs"
+    InvalidCard,
+    /// Cards required have not been fully unmasked yet
+    CardsNotRevealed,
+    /// Mucking is not allowed by showdown policy or before any hand was shown
+    MuckNotAllowed,
+    /// No shuffle has been submitted yet
+    NoShuffleHistory,
+    /// Public key of the player has not been submitted yet
+    MissingPublicKey(usize),
+    /// Player cheated during shuffle
+    ShuffleForgery(usize),
+    /// Player cheated during unmasking
+    UnmaskCheat(usize),
+    /// Same card was revealed more than once
+    DuplicateCards,
+    /// There is no next round as hand has finished
+    HandFinished,
+    /// Hand is in progress
+    HandInProgress,
+    /// There is no active hand
+    NoActiveHand,
+    /// Deck cannot cover hole and community cards of all players
+    TooManyPlayers,
+    /// All seats at the table are taken
+    TableFull,
+    /// Player is already seated at the table
+    AlreadyJoined,
+    /// Hand needs at least two players with chips
+    NotEnoughPlayers,
+    /// Number of betting rounds does not match community card layout
+    RoundsMismatch,
+    /// Event log cannot be replayed
+    InvalidEventLog,
+    /// Snapshot does not describe a consistent hand
+    InvalidSnapshot,
+    /// Stack would grow beyond maximum buy-in of the table
+    ExceedsMaxBuyIn,
+    /// Signing key is zero, so it cannot unmask cards
+    InvalidSigningKey,
+    /// Player has already shuffled the deck in this hand
+    AlreadyShuffled,
+    /// Shuffled deck has different number of cards than deck it was made from
+    DeckSizeMismatch,
+    /// Blind schedule has no levels
+    EmptyBlindSchedule,
+    /// Hand has no betting round with community cards of that number
+    InvalidRound,
+    /// Number of community cards submitted differs from cards of the round
+    WrongCommunityCount { expected: usize },
+    /// Number of stacks differs from number of players dealt into the hand
+    StacksMismatch,
+}
+
+impl fmt::Display for PokerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PokerError::WrongState => write!(f, "Action not allowed in current state"),
+            PokerError::NotYourTurn => write!(f, "Not your turn"),
+            PokerError::InvalidPlayer => write!(f, "Invalid player"),
+            PokerError::PlayerFolded => write!(f, "Player has already folded"),
+            PokerError::BetTooSmall => write!(f, "Amount is less than the required call amount"),
+            PokerError::RaiseTooSmall { min } => {
+                write!(f, "Raise is too small, must raise to at least {}", min)
+            }
+            PokerError::IllegalAction => write!(f, "Action not allowed facing current bet"),
+            PokerError::InsufficientChips => write!(f, "Not enough chips in stack"),
+            PokerError::MalformedCards => write!(f, "Submitted cards do not match cards dealt"),
+            PokerError::MalformedUnmask => {
+                write!(f, "Number of unmasked cards does not match cards dealt")
+            }
+            PokerError::DeckExhausted => write!(f, "Not enough cards left in the deck"),
+            PokerError::InvalidCard => write!(f, "Invalid card code"),
+            PokerError::CardsNotRevealed => write!(f, "Cards not revealed"),
+            PokerError::MuckNotAllowed => write!(f, "Mucking is not allowed"),
+            PokerError::NoShuffleHistory => write!(f, "No shuffle history"),
+            PokerError::MissingPublicKey(player) => {
+                write!(f, "Missing public key of player {}", player)
+            }
+            PokerError::ShuffleForgery(player) => {
+                write!(f, "Player cheated during shuffle {}", player)
+            }
+            PokerError::UnmaskCheat(player) => {
+                write!(f, "Player cheated during unmasking {}", player)
+            }
+            PokerError::DuplicateCards => write!(f, "Same card revealed more than once"),
+            PokerError::HandFinished => write!(f, "No next round - Hand has finished"),
+            PokerError::HandInProgress => write!(f, "Hand in progress"),
+            PokerError::NoActiveHand => write!(f, "No active hand"),
+            PokerError::TooManyPlayers => write!(f, "Too many players for the deck"),
+            PokerError::TableFull => write!(f, "Table is full"),
+            PokerError::AlreadyJoined => write!(f, "Player already joined the table"),
+            PokerError::NotEnoughPlayers => write!(f, "Not enough players to start hand"),
+            PokerError::RoundsMismatch => {
+                write!(f, "Number of rounds does not match community card layout")
+            }
+            PokerError::InvalidEventLog => write!(f, "Event log cannot be replayed"),
+            PokerError::InvalidSnapshot => {
+                write!(f, "Snapshot does not describe a consistent hand")
+            }
+            PokerError::ExceedsMaxBuyIn => write!(f, "Stack would exceed maximum buy-in"),
+            PokerError::InvalidSigningKey => write!(f, "Invalid signing key"),
+            PokerError::AlreadyShuffled => write!(f, "Player has already shuffled the deck"),
+            PokerError::DeckSizeMismatch => {
+                write!(f, "Shuffled deck size does not match previous deck")
+            }
+            PokerError::EmptyBlindSchedule => write!(f, "Blind schedule has no levels"),
+            PokerError::InvalidRound => write!(f, "Invalid round"),
+            PokerError::WrongCommunityCount { expected } => {
+                write!(f, "Wrong number of community cards, expected {}", expected)
+            }
+            PokerError::StacksMismatch => {
+                write!(f, "Number of stacks does not match number of players")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PokerError {}
+
+/// Supports byte-string error interface (e.g. Stylus contracts)
+impl From<PokerError> for Vec<u8> {
+    fn from(err: PokerError) -> Self {
+        err.to_string().into_bytes()
+    }
+}
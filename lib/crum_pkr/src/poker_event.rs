@@ -0,0 +1,70 @@
+//! Crumble (CRyptographic gaMBLE)
+//! 
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//! 
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use crate::{poker_bets::PokerAction, poker_hand::PokerHand};
+
+/// Notification about progress of the hand.
+///
+/// UIs and on-chain bridges can react to these instead of polling the state
+/// of the hand after every call.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PokerEvent {
+    ShuffleSubmitted {
+        player: usize,
+    },
+    AntePosted {
+        player: usize,
+        amount: u64,
+    },
+    BlindPosted {
+        player: usize,
+        amount: u64,
+    },
+    /// Player unmasked cards; `state` is one of `POKER_HAND_STATE_UNMASK_*`
+    CardsUnmasked {
+        player: usize,
+        state: u8,
+        round: usize,
+    },
+    PlayerMucked {
+        player: usize,
+    },
+    BetPlaced {
+        player: usize,
+        action: PokerAction,
+    },
+    PublicKeySubmitted {
+        player: usize,
+    },
+    /// Chips won by each winner
+    PotsAwarded {
+        awards: Vec<(usize, u64)>,
+    },
+    HandFinished,
+    CheatDetected {
+        player: usize,
+    },
+    /// Same card was revealed more than once, which cannot be traced to
+    /// single player, so nobody is blamed
+    DuplicateCardsRevealed,
+    /// Player did not make their move in time
+    PlayerTimedOut {
+        player: usize,
+    },
+}
+
+impl PokerHand {
+    /// Take events emitted since last call
+    pub fn take_events(&mut self) -> Vec<PokerEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(super) fn emit(&mut self, event: PokerEvent) {
+        self.events.push(event);
+    }
+}
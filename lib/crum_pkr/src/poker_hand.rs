@@ -5,32 +5,123 @@
 //!
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
-use crum_bls::{types::PublicKey, verify};
+use std::cmp::Ordering;
+
+use bls12_381::{G1Affine, G2Prepared};
+use crum_bls::{proof::UnmaskProof, types::PublicKey, verify};
+use itertools::Itertools;
 
 use crate::{
-    poker_bets::PokerBettingState,
-    poker_deck::{MaskedCards, PokerDeck, UnmaskedCards},
+    poker_bets::{PokerAction, PokerBettingState},
+    poker_deck::{MaskedCards, PokerCard, PokerDeck, UnmaskedCards},
+    poker_error::PokerError,
+    poker_event::PokerEvent,
+    poker_hand_rank::{HandRank, rank_hand_with, rank_omaha_hand_with},
+    poker_replay::HandEvent,
     poker_state::{
         POKER_HAND_STATE_BET, POKER_HAND_STATE_BIG_BLIND, POKER_HAND_STATE_CHEATED,
-        POKER_HAND_STATE_FINISHED, POKER_HAND_STATE_SMALL_BLIND,
+        POKER_HAND_STATE_FINISHED, POKER_HAND_STATE_SHUFFLE, POKER_HAND_STATE_SMALL_BLIND,
         POKER_HAND_STATE_SUBMIT_PUBLIC_KEY, POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS,
-        POKER_HAND_STATE_UNMASK_HOLE_CARDS, POKER_HAND_STATE_UNMASK_SHOWDOWN, POKER_HOLDEM_PREFLOP,
-        PokerHandState, PokerHandStateEnum,
+        POKER_HAND_STATE_UNMASK_HOLE_CARDS, POKER_HAND_STATE_UNMASK_SHOWDOWN,
+        POKER_HOLDEM_COMMUNITY_LAYOUT, POKER_HOLDEM_HOLE_CARDS, POKER_HOLDEM_PREFLOP,
+        POKER_OMAHA_HOLE_CARDS, PokerHandState, PokerHandStateEnum, Street,
     },
 };
 
+/// Decides whether players called to showdown may concede without revealing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShowdownPolicy {
+    /// Every player called to showdown must peel their hole cards
+    #[default]
+    MustReveal,
+    /// Player may muck once another player has already shown their hand
+    MayMuck,
+}
+
+/// How the hand was decided
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandEnding {
+    /// Everyone else folded, and last player standing took the pot
+    Fold,
+    /// Pots were awarded to the best hands revealed at showdown
+    Showdown,
+}
+
+/// Result of a finished hand
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandOutcome {
+    /// Players who won chips, in the order pots were awarded
+    pub winners: Vec<usize>,
+    /// Chips won by each winner
+    pub awards: Vec<(usize, u64)>,
+    /// Rank of hand revealed by each player on the first run of the board,
+    /// None if folded or mucked
+    pub hand_ranks: Vec<Option<HandRank>>,
+    pub ending: HandEnding,
+}
+
+/// Betting decision made by a player during the hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionRecord {
+    pub player: usize,
+    pub street: Street,
+    pub action: PokerAction,
+    /// Chips put in by the action
+    pub amount: u64,
+}
+
 pub struct PokerHand {
     /// player_keys[public keys]
     pub(super) poker_deck: PokerDeck,
+    /// Short deck (6+) is used, under which flush beats full house
+    pub(super) short_deck: bool,
     pub(super) shuffled_deck: MaskedCards,
     pub(super) shuffle_history: Vec<MaskedCards>,
+    /// Players who have submitted their shuffle
+    pub(super) shuffled: Vec<bool>,
     pub(super) player_cards: Vec<UnmaskedCards>,
     pub(super) player_keys: Vec<Option<PublicKey>>,
+    /// Public keys prepared for pairing once submitted
+    pub(super) prepared_keys: Vec<Option<G2Prepared>>,
+    /// Public keys used by players to prove unmasking during the hand
+    pub(super) proof_keys: Vec<Option<PublicKey>>,
+    /// Traces players submitted to prove their shuffle
+    pub(super) shuffle_traces: Vec<Option<Vec<verify::ShuffleTrace>>>,
     pub(super) community_cards: Vec<UnmaskedCards>,
     pub(super) unmasking_sequence: Vec<(usize, u8, Vec<UnmaskedCards>)>,
     pub(super) current_state: PokerHandState,
     pub(super) betting_state: PokerBettingState,
     pub(super) small_blind: u64,
+    /// Posted by every player before the blinds
+    pub(super) ante: u64,
+    pub(super) hole_cards: usize,
+    /// Number of community cards dealt before each betting round
+    pub(super) community_layout: Vec<usize>,
+    /// Times to run the board once everyone is all-in
+    pub(super) run_it_times: usize,
+    /// Runs of the board dealt in this hand
+    pub(super) board_runs: usize,
+    /// Burn card before dealing each street of community cards
+    pub(super) burn_cards: bool,
+    /// Cards burned so far, never unmasked
+    pub(super) burned_cards: UnmaskedCards,
+    pub(super) showdown_policy: ShowdownPolicy,
+    pub(super) mucked_players: Vec<bool>,
+    /// Betting actions in the order they were made
+    pub(super) action_log: Vec<ActionRecord>,
+    /// Set once pots are awarded
+    pub(super) outcome: Option<HandOutcome>,
+    /// Time each player has to make their move
+    pub(super) action_timeout: Option<u64>,
+    /// Time by which current player must make their move
+    pub(super) deadline: Option<u64>,
+    /// Time of the last tick, from which clock of next player counts
+    pub(super) last_tick: Option<u64>,
+    pub(super) events: Vec<PokerEvent>,
+    /// Moves made in the hand, while recording for replay
+    pub(super) recorded_events: Option<Vec<HandEvent>>,
 }
 
 impl PokerHand {
@@ -40,21 +131,303 @@ impl PokerHand {
         dealer_button: usize,
         initial_chips: u64,
         small_blind: u64,
-    ) -> Self {
+        hole_cards: usize,
+    ) -> Result<Self, PokerError> {
+        Self::from_stacks(
+            vec![initial_chips; num_players],
+            max_rounds,
+            dealer_button,
+            small_blind,
+            hole_cards,
+        )
+    }
+
+    /// Start Omaha hand, where each player is dealt four hole cards
+    pub fn new_omaha(
+        num_players: usize,
+        max_rounds: usize,
+        dealer_button: usize,
+        initial_chips: u64,
+        small_blind: u64,
+    ) -> Result<Self, PokerError> {
+        Self::new(
+            num_players,
+            max_rounds,
+            dealer_button,
+            initial_chips,
+            small_blind,
+            POKER_OMAHA_HOLE_CARDS,
+        )
+    }
+
+    /// Start hand with chips each player brings from previous hands.
+    ///
+    /// Every betting round needs its entry in the Hold'em community layout,
+    /// and the deck must have enough cards for all players.
+    ///
+    pub fn from_stacks(
+        player_chips: Vec<u64>,
+        max_rounds: usize,
+        dealer_button: usize,
+        small_blind: u64,
+        hole_cards: usize,
+    ) -> Result<Self, PokerError> {
+        if max_rounds == 0 || max_rounds > POKER_HOLDEM_COMMUNITY_LAYOUT.len() {
+            return Err(PokerError::RoundsMismatch);
+        }
+        let num_players = player_chips.len();
         let poker_deck = PokerDeck::new();
         let shuffled_deck = poker_deck.masked_cards();
-        Self {
+        let mut betting_state = PokerBettingState::from_stacks(player_chips);
+        betting_state.set_big_blind(small_blind * 2);
+        let hand = Self {
             poker_deck,
+            short_deck: false,
             shuffled_deck,
             shuffle_history: vec![],
+            shuffled: vec![false; num_players],
             player_cards: (0..num_players).map(|_| UnmaskedCards::default()).collect(),
             player_keys: (0..num_players).map(|_| None).collect(),
+            prepared_keys: (0..num_players).map(|_| None).collect(),
+            proof_keys: vec![None; num_players],
+            shuffle_traces: vec![None; num_players],
             community_cards: (0..max_rounds).map(|_| UnmaskedCards::default()).collect(),
             unmasking_sequence: vec![],
             current_state: PokerHandState::new(num_players, max_rounds, dealer_button),
-            betting_state: PokerBettingState::new(num_players, initial_chips),
+            betting_state,
             small_blind,
+            ante: 0,
+            hole_cards,
+            community_layout: POKER_HOLDEM_COMMUNITY_LAYOUT[..max_rounds].to_vec(),
+            run_it_times: 1,
+            board_runs: 1,
+            burn_cards: false,
+            burned_cards: UnmaskedCards::default(),
+            showdown_policy: ShowdownPolicy::default(),
+            mucked_players: vec![false; num_players],
+            action_log: vec![],
+            outcome: None,
+            action_timeout: None,
+            deadline: None,
+            last_tick: None,
+            events: vec![],
+            recorded_events: None,
+        };
+        if hand.get_num_cards_needed() > hand.poker_deck.len() {
+            return Err(PokerError::TooManyPlayers);
         }
+        Ok(hand)
+    }
+
+    /// Start next hand between the same players once this one is finished.
+    ///
+    /// Deck base points are kept, so they need not be hashed to curve again,
+    /// and so are blinds, ante and other settings of the hand. Cards, keys,
+    /// betting and outcome of the previous hand are cleared, while events
+    /// not taken yet are kept.
+    ///
+    pub fn reset_for_next_hand(
+        &mut self,
+        new_dealer_button: usize,
+        stacks: &[u64],
+    ) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::Reset {
+            dealer_button: new_dealer_button,
+            stacks: stacks.to_vec(),
+        });
+        self.make_move(event, |hand| hand.reset(new_dealer_button, stacks))
+    }
+
+    fn reset(&mut self, new_dealer_button: usize, stacks: &[u64]) -> Result<(), PokerError> {
+        let num_players = self.current_state.num_players;
+        if !self.current_state.is_finished() {
+            return Err(PokerError::HandInProgress);
+        }
+        if stacks.len() != num_players {
+            return Err(PokerError::StacksMismatch);
+        }
+        if new_dealer_button >= num_players {
+            return Err(PokerError::InvalidPlayer);
+        }
+
+        let big_blind = self.betting_state.get_big_blind();
+        self.betting_state = PokerBettingState::from_stacks(stacks.to_vec());
+        self.betting_state.set_big_blind(big_blind);
+
+        self.shuffled_deck = self.poker_deck.masked_cards();
+        self.shuffle_history.clear();
+        self.shuffled.fill(false);
+        self.player_cards.fill_with(UnmaskedCards::default);
+        self.player_keys.fill(None);
+        self.prepared_keys.fill_with(|| None);
+        self.proof_keys.fill(None);
+        self.shuffle_traces.fill(None);
+        self.community_cards.fill_with(UnmaskedCards::default);
+        self.unmasking_sequence.clear();
+        self.current_state = PokerHandState::new(
+            num_players,
+            self.current_state.max_rounds,
+            new_dealer_button,
+        );
+        self.board_runs = 1;
+        self.burned_cards = UnmaskedCards::default();
+        self.mucked_players.fill(false);
+        self.action_log.clear();
+        self.outcome = None;
+        self.deadline = None;
+
+        Ok(())
+    }
+
+    /// Settings of the hand can only change before anyone has shuffled
+    fn check_not_started(&self) -> Result<(), PokerError> {
+        if self.current_state.current_state != POKER_HAND_STATE_SHUFFLE
+            || !self.shuffle_history.is_empty()
+        {
+            return Err(PokerError::WrongState);
+        }
+        Ok(())
+    }
+
+    /// Choose how many community cards are dealt before each betting round.
+    ///
+    /// Must be set before the hand starts; default is Hold'em `[0, 3, 1, 1]`.
+    /// Layout must have an entry for each of the hand's `max_rounds`.
+    ///
+    pub fn set_community_layout(&mut self, community_layout: Vec<usize>) -> Result<(), PokerError> {
+        self.check_not_started()?;
+        if community_layout.len() != self.current_state.max_rounds {
+            return Err(PokerError::RoundsMismatch);
+        }
+        self.community_layout = community_layout;
+        self.record_config();
+        Ok(())
+    }
+
+    /// Tell how many community cards are dealt before each betting round
+    pub fn get_community_layout(&self) -> &[usize] {
+        &self.community_layout
+    }
+
+    /// Run the rest of the board this many times once betting is closed
+    /// with players all-in, and award each run equal share of every pot.
+    ///
+    /// Must be set before the hand starts. Extra runs are dealt from what is
+    /// left of the deck, and if there are not enough cards the board is run
+    /// once.
+    ///
+    pub fn enable_run_it_twice(&mut self, times: usize) -> Result<(), PokerError> {
+        self.check_not_started()?;
+        self.run_it_times = times.max(1);
+        self.record_config();
+        Ok(())
+    }
+
+    /// Tell how many runs of the board were dealt in this hand
+    pub fn get_board_runs(&self) -> usize {
+        self.board_runs
+    }
+
+    /// Burn top card of the deck before dealing each street of community
+    /// cards, as in real Hold'em.
+    ///
+    /// Must be set before the hand starts; default is no burning.
+    ///
+    pub fn set_burn_cards(&mut self, burn_cards: bool) -> Result<(), PokerError> {
+        self.check_not_started()?;
+        self.burn_cards = burn_cards;
+        self.record_config();
+        Ok(())
+    }
+
+    pub fn get_burn_cards(&self) -> bool {
+        self.burn_cards
+    }
+
+    /// Play with 36-card short deck (6+ Hold'em), where cards below Six are
+    /// removed and hands are ranked by short-deck rules.
+    ///
+    /// Must be set before the hand starts; default is full deck. Fails if
+    /// short deck has not got enough cards for all players.
+    ///
+    pub fn set_short_deck(&mut self, short_deck: bool) -> Result<(), PokerError> {
+        self.check_not_started()?;
+        if self.short_deck != short_deck {
+            let poker_deck = if short_deck {
+                PokerDeck::new_short_deck()
+            } else {
+                PokerDeck::new()
+            };
+            if self.get_num_cards_needed() > poker_deck.len() {
+                return Err(PokerError::TooManyPlayers);
+            }
+            self.shuffled_deck = poker_deck.masked_cards();
+            self.poker_deck = poker_deck;
+            self.short_deck = short_deck;
+        }
+        self.record_config();
+        Ok(())
+    }
+
+    pub fn is_short_deck(&self) -> bool {
+        self.short_deck
+    }
+
+    /// Tell cards burned so far, in order of dealing
+    pub fn get_burned_cards(&self) -> &UnmaskedCards {
+        &self.burned_cards
+    }
+
+    /// Choose ante posted by every player before the blinds.
+    ///
+    /// Must be set before the hand starts; default is no ante.
+    ///
+    pub fn set_ante(&mut self, ante: u64) -> Result<(), PokerError> {
+        self.check_not_started()?;
+        self.ante = ante;
+        self.record_config();
+        Ok(())
+    }
+
+    /// Tell ante amount
+    pub fn get_ante(&self) -> u64 {
+        self.ante
+    }
+
+    /// Choose whether players may muck at showdown
+    pub fn set_showdown_policy(&mut self, policy: ShowdownPolicy) {
+        self.showdown_policy = policy;
+        self.record_config();
+    }
+
+    /// Tell whether players may muck at showdown
+    pub const fn get_showdown_policy(&self) -> ShowdownPolicy {
+        self.showdown_policy
+    }
+
+    /// Tell whether player mucked their hand at showdown
+    pub fn is_player_mucked(&self, player: usize) -> bool {
+        self.mucked_players.get(player).copied().unwrap_or(false)
+    }
+
+    /// Betting actions made so far, in order
+    pub fn action_log(&self) -> &[ActionRecord] {
+        &self.action_log
+    }
+
+    /// Tell who won and how, once the hand is finished
+    pub fn outcome(&self) -> Option<&HandOutcome> {
+        self.outcome.as_ref()
+    }
+
+    /// Tell whether player folded during betting.
+    ///
+    /// Folded player is skipped in betting and at showdown, but must still
+    /// take their mask off community cards, as every card carries masks of
+    /// all players.
+    ///
+    pub fn player_folded(&self, player: usize) -> bool {
+        player < self.current_state.num_players && !self.betting_state.is_active(player)
     }
 
     /// On event acting player checks the current round to follow the rules
@@ -84,23 +457,217 @@ impl PokerHand {
     }
 
     /// Supports community cards unmask
+    ///
+    /// All community cards are dealt together with hole cards, so cards of
+    /// later streets are there, but stay masked until their street.
+    ///
     pub fn get_community_cards(&self, round: usize) -> Option<&UnmaskedCards> {
-        if round == POKER_HOLDEM_PREFLOP {
-            return None;
-        }
-        self.community_cards.get(round - 1)
+        self.community_cards.get(self.community_index(round).ok()?)
+    }
+
+    /// Community cards dealt before betting round are kept at `round - 1`,
+    /// and there are none before preflop
+    fn community_index(&self, round: usize) -> Result<usize, PokerError> {
+        round
+            .checked_sub(1)
+            .filter(|&index| index < self.community_cards.len())
+            .ok_or(PokerError::InvalidRound)
+    }
+
+    /// Tell hole cards of the player followed by community cards of the first
+    /// run of the board, provided they have all been fully unmasked
+    pub fn get_revealed_cards(&self, player: usize) -> Result<Vec<PokerCard>, PokerError> {
+        self.get_revealed_cards_in_run(player, 0)
+    }
+
+    /// Same as `get_revealed_cards`, but with community cards of given run
+    /// of the board
+    pub fn get_revealed_cards_in_run(
+        &self,
+        player: usize,
+        run: usize,
+    ) -> Result<Vec<PokerCard>, PokerError> {
+        let hole_cards = self
+            .player_cards
+            .get(player)
+            .ok_or(PokerError::InvalidPlayer)?;
+        let board = self.board(run);
+        std::iter::once(hole_cards)
+            .chain(board.iter())
+            .flat_map(|cards| self.poker_deck.unmasked_cards(cards))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(PokerError::CardsNotRevealed)
+    }
+
+    /// Community cards of each street on given run of the board.
+    ///
+    /// Streets dealt before the board was run more than once are shared by
+    /// all runs, and cards of other runs follow the first run on each street.
+    ///
+    fn board(&self, run: usize) -> Vec<UnmaskedCards> {
+        self.community_cards
+            .iter()
+            .enumerate()
+            .map(|(index, cards)| {
+                let count = self.community_count(index);
+                if count == 0 {
+                    return cards.clone();
+                }
+                let runs = cards.as_slice().chunks(count).collect_vec();
+                let run_cards = runs.get(run).or(runs.first()).copied();
+                UnmaskedCards::new(run_cards.unwrap_or_default().to_vec())
+            })
+            .collect()
+    }
+
+    /// Number of community cards on single run of the board, for the street
+    /// dealt after betting round at `index`
+    pub(super) fn community_count(&self, index: usize) -> usize {
+        self.community_layout.get(index + 1).copied().unwrap_or(0)
+    }
+
+    /// Tell best five-card hand the player has got from revealed cards
+    ///
+    /// Omaha hands must use exactly two hole cards and three community cards.
+    ///
+    pub fn get_hand_rank(&self, player: usize) -> Result<HandRank, PokerError> {
+        self.get_hand_rank_in_run(player, 0)
+    }
+
+    /// Same as `get_hand_rank`, but on given run of the board
+    pub fn get_hand_rank_in_run(&self, player: usize, run: usize) -> Result<HandRank, PokerError> {
+        let cards = self.get_revealed_cards_in_run(player, run)?;
+        let rank = if self.hole_cards > POKER_HOLDEM_HOLE_CARDS {
+            let (hole_cards, community_cards) = cards.split_at(self.hole_cards);
+            rank_omaha_hand_with(hole_cards, community_cards, self.short_deck)
+        } else {
+            rank_hand_with(&cards, self.short_deck)
+        };
+        rank.ok_or(PokerError::CardsNotRevealed)
+    }
+
+    /// Referee checks that hand claimed by the player matches revealed cards
+    pub fn verify_claim(&self, player: usize, claimed: HandRank) -> Result<bool, PokerError> {
+        Ok(self.get_hand_rank(player)? == claimed)
+    }
+
+    /// Enumerate all two-card holdings opponent could still have.
+    ///
+    /// Cards already fully unmasked in this hand (community cards and hole
+    /// cards of other players) are excluded, and so are `hero_cards`.
+    ///
+    /// Hero's hole cards are taken as argument, because they cannot be told
+    /// from the hand itself: they stay masked with hero's key until showdown,
+    /// and the hand never holds anyone's key. Hero gets them from
+    /// `visible_cards_for()`, while spectator passes no cards.
+    ///
+    pub fn possible_opponent_holdings(
+        &self,
+        opponent: usize,
+        hero_cards: &[PokerCard],
+    ) -> Vec<[PokerCard; 2]> {
+        let known_cards: Vec<_> = self
+            .player_cards
+            .iter()
+            .enumerate()
+            .filter(|(player, _)| *player != opponent)
+            .map(|(_, cards)| cards)
+            .chain(self.community_cards.iter())
+            .flat_map(|cards| cards.as_slice())
+            .collect();
+
+        self.poker_deck
+            .as_slice()
+            .iter()
+            .zip(self.poker_deck.poker_cards())
+            .filter(|(card_g1, card)| !known_cards.contains(card_g1) && !hero_cards.contains(card))
+            .map(|(_, card)| *card)
+            .tuple_combinations()
+            .map(|(a, b)| [a, b])
+            .collect()
     }
 
     /// Tell amount required to call (minimum bet)
-    pub fn get_call_amount_required(&self, player: usize) -> Result<u64, Vec<u8>> {
+    pub fn get_call_amount_required(&self, player: usize) -> Result<u64, PokerError> {
         self.betting_state.call_amount_required(player)
     }
 
+    /// Tell smallest total bet this street that player may raise to
+    pub fn get_min_raise_to(&self, player: usize) -> u64 {
+        self.betting_state.min_raise_to(player)
+    }
+
+    /// Tell amount of chips player must put in to make the smallest raise
+    pub fn get_min_raise_amount(&self, player: usize) -> u64 {
+        self.betting_state.min_raise_amount(player)
+    }
+
+    /// Tell number of players dealt into the hand
+    pub fn get_num_players(&self) -> usize {
+        self.current_state.num_players
+    }
+
     /// Tell amount of chips remaining
     pub fn get_chips_remaining(&self, player: usize) -> u64 {
         self.betting_state.chips_remaining(player)
     }
 
+    /// Tell total chips in the pot
+    pub fn get_pot(&self) -> u64 {
+        self.betting_state.pot()
+    }
+
+    /// Tell highest total bet on current street
+    pub fn get_current_highest_bet(&self) -> u64 {
+        self.betting_state.current_highest_bet()
+    }
+
+    /// Tell total chips player has bet on current street
+    pub fn get_player_bet(&self, player: usize) -> u64 {
+        self.betting_state.round_bet(player)
+    }
+
+    /// Tell whether player is still in the hand
+    pub fn is_player_active(&self, player: usize) -> bool {
+        self.betting_state.is_active(player)
+    }
+
+    /// Tell whether player has put their whole stack in
+    pub fn is_player_all_in(&self, player: usize) -> bool {
+        self.betting_state.is_all_in(player)
+    }
+
+    /// Tell name of the current betting round
+    pub fn current_street(&self) -> Street {
+        Street::from_round(self.current_state.current_round)
+    }
+
+    /// Tell players still in the hand who must act before the street is over
+    pub fn players_to_act(&self) -> Vec<usize> {
+        self.betting_state.players_to_act()
+    }
+
+    /// Tell number of cards dealt in the whole hand, hole, community and
+    /// burned
+    pub fn get_num_cards_needed(&self) -> usize {
+        self.current_state.num_players * self.hole_cards
+            + self
+                .community_layout
+                .iter()
+                .map(|&count| self.burn_count(count) + count)
+                .sum::<usize>()
+    }
+
+    /// Tell number of cards burned before street of `count` community cards
+    pub(super) fn burn_count(&self, count: usize) -> usize {
+        usize::from(self.burn_cards && count > 0)
+    }
+
+    /// Tell number of hole cards dealt to each player
+    pub fn get_hole_cards_count(&self) -> usize {
+        self.hole_cards
+    }
+
     /// Tell small blind amount
     pub fn get_small_blind(&self) -> u64 {
         self.small_blind
@@ -108,7 +675,28 @@ impl PokerHand {
 
     /// Tell big blind amount
     pub fn get_big_blind(&self) -> u64 {
-        self.small_blind * 2
+        self.betting_state.get_big_blind()
+    }
+
+    /// Choose big blind other than twice the small blind.
+    ///
+    /// Must be set before the hand starts.
+    ///
+    pub fn set_big_blind(&mut self, big_blind: u64) -> Result<(), PokerError> {
+        self.check_not_started()?;
+        self.betting_state.set_big_blind(big_blind);
+        self.record_config();
+        Ok(())
+    }
+
+    /// Tell which player posts small blind
+    pub fn get_small_blind_player(&self) -> usize {
+        self.current_state.small_blind_player()
+    }
+
+    /// Tell which player posts big blind
+    pub fn get_big_blind_player(&self) -> usize {
+        self.current_state.big_blind_player()
     }
 
     /// Called by each player to submit shuffled and masked deck
@@ -116,7 +704,15 @@ impl PokerHand {
         &mut self,
         player: usize,
         deck: MaskedCards,
-    ) -> Result<(), Vec<u8>> {
+    ) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::ShuffleDeck {
+            player,
+            deck: deck.clone(),
+        });
+        self.make_move(event, |hand| hand.shuffle_deck(player, deck))
+    }
+
+    fn shuffle_deck(&mut self, player: usize, deck: MaskedCards) -> Result<(), PokerError> {
         // check current player is submitter
 
         let PokerHandStateEnum::Shuffle {
@@ -124,58 +720,161 @@ impl PokerHand {
             is_dealer: _,
         } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in shuffle state")?;
+            return Err(PokerError::WrongState);
         };
 
+        // Shuffle history is indexed by player, so each shuffles only once
+        if self.shuffled.get(player).copied().unwrap_or(false) {
+            return Err(PokerError::AlreadyShuffled);
+        }
+
         if p != player {
-            return Err(b"Not your turn to shuffle")?;
+            return Err(PokerError::NotYourTurn);
+        }
+
+        // Dropped or added cards are caught now rather than by verifying the
+        // shuffle at the end of the hand
+        if deck.len() != self.shuffled_deck.len() {
+            return Err(PokerError::DeckSizeMismatch);
         }
 
+        self.shuffled[player] = true;
         self.shuffle_history.push(deck.clone());
         self.shuffled_deck = deck;
 
-        // emit shuffle submitted
+        self.emit(PokerEvent::ShuffleSubmitted { player });
 
         if self.current_state.next_player() {
+            self.current_state.current_player = self.current_state.small_blind_player();
             self.current_state.current_state = POKER_HAND_STATE_SMALL_BLIND;
         }
 
         Ok(())
     }
 
-    pub fn submit_small_blind(&mut self, player: usize) -> Result<(), Vec<u8>> {
+    /// Same as `submit_shuffled_deck`, but with public key and traces of the
+    /// shuffle, so it can be verified straight away instead of at the end of
+    /// the hand. Player must later reveal that same key.
+    pub fn submit_shuffled_deck_with_proof(
+        &mut self,
+        player: usize,
+        deck: MaskedCards,
+        pk: PublicKey,
+        traces: Vec<verify::ShuffleTrace>,
+    ) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::ShuffleDeckWithProof {
+            player,
+            deck: deck.clone(),
+            pk,
+            traces: traces.clone(),
+        });
+        self.make_move(event, |hand| {
+            hand.shuffle_deck_with_proof(player, deck, pk, traces)
+        })
+    }
+
+    fn shuffle_deck_with_proof(
+        &mut self,
+        player: usize,
+        deck: MaskedCards,
+        pk: PublicKey,
+        traces: Vec<verify::ShuffleTrace>,
+    ) -> Result<(), PokerError> {
+        let PokerHandStateEnum::Shuffle {
+            player: p,
+            is_dealer: _,
+        } = self.get_current_state().to_enum()
+        else {
+            return Err(PokerError::WrongState);
+        };
+
+        if self.shuffled.get(player).copied().unwrap_or(false) {
+            return Err(PokerError::AlreadyShuffled);
+        }
+
+        if p != player {
+            return Err(PokerError::NotYourTurn);
+        }
+
+        if deck.len() != self.shuffled_deck.len() {
+            return Err(PokerError::DeckSizeMismatch);
+        }
+
+        let before = self.shuffled_deck.cards();
+        self.verify_shuffle_proof(player, pk, &before, deck.as_slice(), &traces)?;
+
+        self.shuffle_deck(player, deck)?;
+        self.shuffle_traces[player] = Some(traces);
+        Ok(())
+    }
+
+    pub fn submit_small_blind(&mut self, player: usize) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::SmallBlind { player });
+        self.make_move(event, |hand| hand.post_small_blind(player))
+    }
+
+    fn post_small_blind(&mut self, player: usize) -> Result<(), PokerError> {
         let PokerHandStateEnum::SmallBlind { player: p } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in small blind state")?;
+            return Err(PokerError::WrongState);
         };
 
         if p != player {
-            return Err(b"Not your turn to post small blind")?;
+            return Err(PokerError::NotYourTurn);
         }
 
-        self.betting_state
-            .process_action(player, self.get_small_blind())?;
+        // Antes go in with the small blind, as part of posting blinds
+        if self.ante > 0 {
+            for (player, amount) in self.betting_state.post_antes(self.ante) {
+                self.emit(PokerEvent::AntePosted { player, amount });
+            }
+        }
+
+        let amount = self
+            .betting_state
+            .post_blind(player, self.get_small_blind())?;
+
+        self.emit(PokerEvent::BlindPosted { player, amount });
 
-        self.current_state.next_player();
+        self.current_state.current_player = self.current_state.big_blind_player();
         self.current_state.current_state = POKER_HAND_STATE_BIG_BLIND;
 
         Ok(())
     }
 
-    pub fn submit_big_blind(&mut self, player: usize) -> Result<(), Vec<u8>> {
+    pub fn submit_big_blind(&mut self, player: usize) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::BigBlind { player });
+        self.make_move(event, |hand| hand.post_big_blind(player))
+    }
+
+    fn post_big_blind(&mut self, player: usize) -> Result<(), PokerError> {
         let PokerHandStateEnum::BigBlind { player: p } = self.get_current_state().to_enum() else {
-            return Err(b"Not in big blind state")?;
+            return Err(PokerError::WrongState);
         };
 
         if p != player {
-            return Err(b"Not your turn to post big blind")?;
+            return Err(PokerError::NotYourTurn);
+        }
+
+        // Make sure there are enough cards for the whole hand before dealing
+        if self.shuffled_deck.len() < self.get_num_cards_needed() {
+            return Err(PokerError::DeckExhausted);
         }
 
-        self.betting_state
-            .process_action(player, self.get_big_blind())?;
+        let amount = self
+            .betting_state
+            .post_big_blind(player, self.get_big_blind())?;
+
+        self.emit(PokerEvent::BlindPosted { player, amount });
 
         for cards in self.player_cards.iter_mut() {
-            *cards = self.shuffled_deck.deal(2);
+            *cards = self.shuffled_deck.deal(self.hole_cards)?;
+        }
+
+        // Community cards are fixed now, before anyone bets, and only get
+        // unmasked street by street
+        for index in 0..self.community_cards.len() {
+            self.community_cards[index] = self.deal_street(self.community_count(index))?;
         }
 
         self.current_state.next_dealer();
@@ -189,21 +888,35 @@ impl PokerHand {
         &mut self,
         player: usize,
         player_cards: Vec<UnmaskedCards>,
-    ) -> Result<bool, Vec<u8>> {
+    ) -> Result<bool, PokerError> {
+        let event = self.recording(|| HandEvent::UnmaskHoleCards {
+            player,
+            cards: player_cards.clone(),
+        });
+        self.make_move(event, |hand| hand.unmask_player_cards(player, player_cards))
+    }
+
+    fn unmask_player_cards(
+        &mut self,
+        player: usize,
+        player_cards: Vec<UnmaskedCards>,
+    ) -> Result<bool, PokerError> {
         // check current player is submitter
         let PokerHandStateEnum::UnmaskHoleCards { player: p } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in unmask hole cards state")?;
+            return Err(PokerError::WrongState);
         };
 
         if p != player {
-            return Err(b"Not your turn to unmask hole cards")?;
+            return Err(PokerError::NotYourTurn);
         }
 
         if player_cards.len() != self.player_cards.len() {
-            return Err(b"Incorrect length of player cards")?;
+            return Err(PokerError::MalformedCards);
         }
 
+        self.check_unmasked_player_cards(&player_cards)?;
+
         self.unmasking_sequence.push((
             player,
             POKER_HAND_STATE_UNMASK_HOLE_CARDS,
@@ -211,41 +924,117 @@ impl PokerHand {
         ));
         self.player_cards = player_cards;
 
-        // emit player cards unmasked by player
+        self.emit(PokerEvent::CardsUnmasked {
+            player,
+            state: POKER_HAND_STATE_UNMASK_HOLE_CARDS,
+            round: POKER_HOLDEM_PREFLOP,
+        });
 
         if self.current_state.next_player() {
+            // Blinds are the opening bets of preflop round
             self.current_state
-                .next_player_masked(self.betting_state.get_active_players(), true);
-            self.betting_state.next_street();
+                .start_betting_round(&self.betting_state.get_acting_players());
             self.current_state.current_state = POKER_HAND_STATE_BET;
 
             self.check_betting_round_complete()?;
-            return Ok(true)
+            return Ok(true);
         }
 
         Ok(false)
     }
 
+    /// Same as `submit_player_cards`, but with proofs of unmasking of cards
+    /// of every other player, so they can be audited before keys are revealed.
+    pub fn submit_player_cards_with_proofs(
+        &mut self,
+        player: usize,
+        player_cards: Vec<UnmaskedCards>,
+        pk: PublicKey,
+        proofs: Vec<Vec<UnmaskProof>>,
+    ) -> Result<bool, PokerError> {
+        let event = self.recording(|| HandEvent::UnmaskHoleCardsWithProofs {
+            player,
+            cards: player_cards.clone(),
+            pk,
+            proofs: proofs.clone(),
+        });
+        self.make_move(event, |hand| {
+            hand.unmask_player_cards_with_proofs(player, player_cards, pk, proofs)
+        })
+    }
+
+    fn unmask_player_cards_with_proofs(
+        &mut self,
+        player: usize,
+        player_cards: Vec<UnmaskedCards>,
+        pk: PublicKey,
+        proofs: Vec<Vec<UnmaskProof>>,
+    ) -> Result<bool, PokerError> {
+        let PokerHandStateEnum::UnmaskHoleCards { player: p } = self.get_current_state().to_enum()
+        else {
+            return Err(PokerError::WrongState);
+        };
+
+        if p != player {
+            return Err(PokerError::NotYourTurn);
+        }
+
+        if player_cards.len() != self.player_cards.len() || proofs.len() != self.player_cards.len()
+        {
+            return Err(PokerError::MalformedCards);
+        }
+
+        for target_player in 0..player_cards.len() {
+            if target_player == player {
+                continue;
+            }
+            let before = self.player_cards[target_player].cards();
+            self.verify_unmask_proofs(
+                player,
+                pk,
+                &before,
+                player_cards[target_player].as_slice(),
+                &proofs[target_player],
+            )?;
+        }
+
+        self.unmask_player_cards(player, player_cards)
+    }
+
     /// Called by each player to unmask player hand
     pub fn submit_player_cards_showdown(
         &mut self,
         player: usize,
         player_cards: Vec<UnmaskedCards>,
-    ) -> Result<bool, Vec<u8>> {
+    ) -> Result<bool, PokerError> {
+        let event = self.recording(|| HandEvent::UnmaskShowdown {
+            player,
+            cards: player_cards.clone(),
+        });
+        self.make_move(event, |hand| hand.unmask_showdown(player, player_cards))
+    }
+
+    fn unmask_showdown(
+        &mut self,
+        player: usize,
+        player_cards: Vec<UnmaskedCards>,
+    ) -> Result<bool, PokerError> {
         // check current player is submitter
         let PokerHandStateEnum::UnmaskShowdown { player: p } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in unmask hole cards state")?;
+            return Err(PokerError::WrongState);
         };
 
         if p != player {
-            return Err(b"Not your turn to unmask hole cards")?;
+            return Err(PokerError::NotYourTurn);
         }
 
         if player_cards.len() != self.player_cards.len() {
-            return Err(b"Incorrect length of player cards")?;
+            return Err(PokerError::MalformedCards);
         }
 
+        self.check_unmasked_player_cards(&player_cards)?;
+
         self.unmasking_sequence.push((
             player,
             POKER_HAND_STATE_UNMASK_SHOWDOWN,
@@ -253,9 +1042,57 @@ impl PokerHand {
         ));
         self.player_cards = player_cards;
 
-        // emit player cards unmasked by player
+        self.emit(PokerEvent::CardsUnmasked {
+            player,
+            state: POKER_HAND_STATE_UNMASK_SHOWDOWN,
+            round: self.current_state.current_round,
+        });
 
-        if self.current_state.next_player() {
+        // Player peeled last mask off own cards
+        self.check_revealed_cards(player, self.player_cards[player].cards())?;
+
+        if self.next_showdown_player() {
+            self.current_state.current_state = POKER_HAND_STATE_SUBMIT_PUBLIC_KEY;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Called by player to concede at showdown without revealing hole cards
+    pub fn submit_muck(&mut self, player: usize) -> Result<bool, PokerError> {
+        let event = self.recording(|| HandEvent::Muck { player });
+        self.make_move(event, |hand| hand.muck(player))
+    }
+
+    fn muck(&mut self, player: usize) -> Result<bool, PokerError> {
+        let PokerHandStateEnum::UnmaskShowdown { player: p } = self.get_current_state().to_enum()
+        else {
+            return Err(PokerError::WrongState);
+        };
+
+        if p != player {
+            return Err(PokerError::NotYourTurn);
+        }
+
+        if self.showdown_policy != ShowdownPolicy::MayMuck {
+            return Err(PokerError::MuckNotAllowed);
+        }
+
+        let has_shown = self
+            .unmasking_sequence
+            .iter()
+            .any(|(_, state_type, _)| *state_type == POKER_HAND_STATE_UNMASK_SHOWDOWN);
+
+        if !has_shown {
+            return Err(PokerError::MuckNotAllowed);
+        }
+
+        self.mucked_players[player] = true;
+
+        self.emit(PokerEvent::PlayerMucked { player });
+
+        if self.next_showdown_player() {
             self.current_state.current_state = POKER_HAND_STATE_SUBMIT_PUBLIC_KEY;
             return Ok(true);
         }
@@ -269,28 +1106,44 @@ impl PokerHand {
         player: usize,
         round: usize,
         cards: UnmaskedCards,
-    ) -> Result<bool, Vec<u8>> {
+    ) -> Result<bool, PokerError> {
+        let event = self.recording(|| HandEvent::UnmaskCommunityCards {
+            player,
+            round,
+            cards: cards.clone(),
+        });
+        self.make_move(event, |hand| {
+            hand.unmask_community_cards(player, round, cards)
+        })
+    }
+
+    fn unmask_community_cards(
+        &mut self,
+        player: usize,
+        round: usize,
+        cards: UnmaskedCards,
+    ) -> Result<bool, PokerError> {
+        let index = self.community_index(round)?;
+
         // check current player is submitter
         let PokerHandStateEnum::UnmaskCommunityCards {
             round: r,
             player: p,
         } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in bet state")?;
+            return Err(PokerError::WrongState);
         };
 
         if r != round {
-            return Err(b"Not this round to unmask cards")?;
+            return Err(PokerError::WrongState);
         }
 
         if p != player {
-            return Err(b"Not your turn to bet")?;
+            return Err(PokerError::NotYourTurn);
         }
 
-        let round_cards = self
-            .community_cards
-            .get_mut(round - 1)
-            .expect("No round cards");
+        self.check_community_count(index, &cards)?;
+        let round_cards = &mut self.community_cards[index];
 
         self.unmasking_sequence.push((
             player,
@@ -299,12 +1152,20 @@ impl PokerHand {
         ));
         *round_cards = cards;
 
-        // emit community cards for round unmasked by player
+        self.emit(PokerEvent::CardsUnmasked {
+            player,
+            state: POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS,
+            round,
+        });
+
+        if self.current_state.is_last_player() {
+            self.check_revealed_cards(player, self.community_cards[index].cards())?;
+        }
 
         if self.current_state.next_player() {
-            self.current_state
-                .next_player_masked(self.betting_state.get_active_players(), true);
             self.betting_state.next_street();
+            self.current_state
+                .start_betting_round(&self.betting_state.get_acting_players());
             self.current_state.current_state = POKER_HAND_STATE_BET;
 
             self.check_betting_round_complete()?;
@@ -314,30 +1175,170 @@ impl PokerHand {
         Ok(false)
     }
 
+    /// Move to next player who has not folded; true once back at dealer
+    fn next_showdown_player(&mut self) -> bool {
+        loop {
+            if self.current_state.next_player() {
+                return true;
+            }
+            if !self.player_folded(self.current_state.current_player) {
+                return false;
+            }
+        }
+    }
+
+    /// Every player must still have as many cards as they were dealt
+    fn check_unmasked_player_cards(
+        &self,
+        player_cards: &[UnmaskedCards],
+    ) -> Result<(), PokerError> {
+        if player_cards
+            .iter()
+            .zip(&self.player_cards)
+            .any(|(submitted, dealt)| submitted.len() != dealt.len())
+        {
+            return Err(PokerError::MalformedUnmask);
+        }
+        Ok(())
+    }
+
+    /// Fully unmasked cards must all be cards of the deck, otherwise player
+    /// who peeled last mask is flagged as cheater.
+    /// Every card of the round must be submitted, as unmasking history
+    /// replay relies on each submission holding all cards of the round
+    fn check_community_count(&self, index: usize, cards: &UnmaskedCards) -> Result<(), PokerError> {
+        let expected = self.community_cards[index].len();
+        if cards.len() != expected {
+            return Err(PokerError::WrongCommunityCount { expected });
+        }
+        Ok(())
+    }
+
+    fn check_revealed_cards(
+        &mut self,
+        player: usize,
+        cards: Vec<G1Affine>,
+    ) -> Result<(), PokerError> {
+        if cards
+            .into_iter()
+            .all(|card| self.poker_deck.find_card(card).is_some())
+        {
+            return Ok(());
+        }
+
+        self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+        self.emit(PokerEvent::CheatDetected { player });
+        Err(PokerError::UnmaskCheat(player))
+    }
+
+    /// Same as `submit_community_cards`, but with proofs of unmasking, so
+    /// they can be audited before keys are revealed.
+    pub fn submit_community_cards_with_proofs(
+        &mut self,
+        player: usize,
+        round: usize,
+        cards: UnmaskedCards,
+        pk: PublicKey,
+        proofs: Vec<UnmaskProof>,
+    ) -> Result<bool, PokerError> {
+        let event = self.recording(|| HandEvent::UnmaskCommunityCardsWithProofs {
+            player,
+            round,
+            cards: cards.clone(),
+            pk,
+            proofs: proofs.clone(),
+        });
+        self.make_move(event, |hand| {
+            hand.unmask_community_cards_with_proofs(player, round, cards, pk, proofs)
+        })
+    }
+
+    fn unmask_community_cards_with_proofs(
+        &mut self,
+        player: usize,
+        round: usize,
+        cards: UnmaskedCards,
+        pk: PublicKey,
+        proofs: Vec<UnmaskProof>,
+    ) -> Result<bool, PokerError> {
+        let index = self.community_index(round)?;
+
+        let PokerHandStateEnum::UnmaskCommunityCards {
+            round: r,
+            player: p,
+        } = self.get_current_state().to_enum()
+        else {
+            return Err(PokerError::WrongState);
+        };
+
+        if r != round {
+            return Err(PokerError::WrongState);
+        }
+
+        if p != player {
+            return Err(PokerError::NotYourTurn);
+        }
+
+        self.check_community_count(index, &cards)?;
+        let before = self.community_cards[index].cards();
+        self.verify_unmask_proofs(player, pk, &before, cards.as_slice(), &proofs)?;
+
+        self.unmask_community_cards(player, round, cards)
+    }
+
     /// Called at the end of hand to verify faierness of gameplay
     pub fn submit_public_key(
         &mut self,
         player: usize,
         pk: PublicKey,
         traces: Vec<verify::ShuffleTrace>,
-    ) -> Result<(), Vec<u8>> {
+    ) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::SubmitPublicKey {
+            player,
+            pk,
+            traces: traces.clone(),
+        });
+        self.make_move(event, |hand| hand.reveal_public_key(player, pk, traces))
+    }
+
+    fn reveal_public_key(
+        &mut self,
+        player: usize,
+        pk: PublicKey,
+        traces: Vec<verify::ShuffleTrace>,
+    ) -> Result<(), PokerError> {
         let PokerHandStateEnum::SubmitPublicKey { player: p } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in submit public key state")?;
+            return Err(PokerError::WrongState);
         };
 
         if p != player {
-            return Err(b"Not your turn to submit public key")?;
+            return Err(PokerError::NotYourTurn);
+        }
+
+        // Identity key would make every unmasking pairing check pass, and
+        // key revealed must be the one player used to prove unmasking
+        if bool::from(pk.is_identity())
+            || self.proof_keys[player].is_some_and(|proof_key| proof_key != pk)
+        {
+            self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+            self.emit(PokerEvent::CheatDetected { player });
+            return Err(PokerError::UnmaskCheat(player));
         }
 
         let player_key = self.player_keys.get_mut(player).expect("No player key");
         *player_key = Some(pk);
+        self.prepared_keys[player] = Some(G2Prepared::from(pk));
 
-        // emit (ephemeral) public key submitted
+        self.emit(PokerEvent::PublicKeySubmitted { player });
 
-        if !self.verify_shuffle(player, pk, traces) {
+        // Traces are kept even if invalid, so that audit can tell why
+        let is_valid = self.verify_shuffle(player, &traces)?;
+        self.shuffle_traces[player] = Some(traces);
+        if !is_valid {
             self.current_state.current_state = POKER_HAND_STATE_CHEATED;
-            return Err("Player cheated during shuffle")?;
+            self.emit(PokerEvent::CheatDetected { player });
+            return Err(PokerError::ShuffleForgery(player));
         }
 
         if self.current_state.next_player() {
@@ -345,74 +1346,314 @@ impl PokerHand {
                 Ok(None) => (),
                 Ok(Some(cheater)) => {
                     self.current_state.current_state = POKER_HAND_STATE_CHEATED;
-                    return Err(
-                        format!("Player cheated during unmasking {}", cheater).into_bytes()
-                    )?;
+                    self.emit(PokerEvent::CheatDetected { player: cheater });
+                    return Err(PokerError::UnmaskCheat(cheater));
                 }
-                Err(err) => Err(err)?,
+                Err(err) => return Err(err),
+            }
+            if self.has_duplicate_cards() {
+                self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+                self.emit(PokerEvent::DuplicateCardsRevealed);
+                return Err(PokerError::DuplicateCards);
             }
-            // TODO
-            // compute score of each hand
-            // select winner
+            self.award_pot_to_best_hands()?;
             self.current_state.current_state = POKER_HAND_STATE_FINISHED;
+            self.emit(PokerEvent::HandFinished);
         }
 
         Ok(())
     }
 
+    /// Computes score of each hand still contending the pot and awards the
+    /// main pot and each side pot to the best hand(s) eligible for it.
+    ///
+    /// Winners of a tie split the pot evenly, with odd chips going to the
+    /// first winner left of the button. When the board was run more than
+    /// once, each run awards its share of every pot.
+    ///
+    pub(super) fn award_pot_to_best_hands(&mut self) -> Result<Vec<(usize, u64)>, PokerError> {
+        let num_players = self.current_state.num_players;
+        let dealer = self.current_state.dealer_button;
+
+        // Seats in order starting left of the button
+        let seats: Vec<usize> = (1..=num_players)
+            .map(|seat| (dealer + seat) % num_players)
+            .collect();
+
+        let mut runs = Vec::with_capacity(self.board_runs);
+        let mut first_run_ranks = None;
+
+        for run in 0..self.board_runs {
+            let (ranks, winners) = self.best_hands(&seats, run)?;
+            first_run_ranks.get_or_insert(ranks);
+            runs.push(winners);
+        }
+
+        let awards = self.betting_state.award_pots_in_runs(&runs);
+
+        self.outcome = Some(HandOutcome {
+            winners: awards
+                .iter()
+                .filter(|(_, amount)| *amount > 0)
+                .map(|(player, _)| *player)
+                .collect(),
+            awards: awards.clone(),
+            hand_ranks: first_run_ranks.unwrap_or_else(|| vec![None; num_players]),
+            ending: HandEnding::Showdown,
+        });
+
+        self.emit(PokerEvent::PotsAwarded {
+            awards: awards.clone(),
+        });
+
+        Ok(awards)
+    }
+
+    /// Rank of each hand still contending the pot on given run of the board,
+    /// and best hand(s) eligible for each pot
+    #[allow(clippy::type_complexity)]
+    fn best_hands(
+        &self,
+        seats: &[usize],
+        run: usize,
+    ) -> Result<(Vec<Option<HandRank>>, Vec<Vec<usize>>), PokerError> {
+        let mut ranks = vec![None; self.current_state.num_players];
+        for &player in seats {
+            if self.betting_state.get_active_players()[player] && !self.mucked_players[player] {
+                ranks[player] = Some(self.get_hand_rank_in_run(player, run)?);
+            }
+        }
+
+        let winners: Vec<Vec<usize>> = self
+            .betting_state
+            .pots()
+            .iter()
+            .map(|pot| {
+                let contenders: Vec<usize> = seats
+                    .iter()
+                    .copied()
+                    .filter(|p| pot.eligible_players.contains(p) && ranks[*p].is_some())
+                    .collect();
+
+                let best_rank = contenders
+                    .iter()
+                    .filter_map(|p| ranks[*p].as_ref())
+                    .max_by(|a, b| self.compare_hand_ranks(a, b));
+
+                if best_rank.is_none() {
+                    // Everyone eligible mucked, so they share the pot
+                    return seats
+                        .iter()
+                        .copied()
+                        .filter(|p| pot.eligible_players.contains(p))
+                        .collect();
+                }
+
+                contenders
+                    .iter()
+                    .copied()
+                    .filter(|p| ranks[*p].as_ref() == best_rank)
+                    .collect()
+            })
+            .collect();
+
+        Ok((ranks, winners))
+    }
+
+    /// Compare hands by rules of the deck used
+    fn compare_hand_ranks(&self, a: &HandRank, b: &HandRank) -> Ordering {
+        if self.short_deck {
+            a.cmp_short_deck(b)
+        } else {
+            a.cmp(b)
+        }
+    }
+
+    /// Verify shuffle of the player using their submitted public key
     pub fn verify_shuffle(
-        &mut self,
+        &self,
         player: usize,
-        pk: PublicKey,
-        traces: Vec<verify::ShuffleTrace>,
-    ) -> bool {
+        traces: &[verify::ShuffleTrace],
+    ) -> Result<bool, PokerError> {
+        let pk_prepared = self.prepared_keys[player]
+            .as_ref()
+            .ok_or(PokerError::MissingPublicKey(player))?;
+
         let num_players = self.current_state.num_players;
         let dealer = self.current_state.dealer_button;
 
         let step_index = (player + num_players - dealer) % num_players;
 
-        let next_cards = self.shuffle_history[step_index].cards();
+        let next_cards = self.shuffle_history[step_index].as_slice();
         let prev_cards = if step_index == 0 {
-            self.poker_deck.cards()
+            self.poker_deck.as_slice()
         } else {
-            self.shuffle_history[step_index - 1].cards()
+            self.shuffle_history[step_index - 1].as_slice()
         };
 
-        verify::verify_shuffle_traced(&prev_cards, &next_cards, &pk, &traces).is_ok()
+        Ok(
+            verify::verify_shuffle_traced_prepared(prev_cards, next_cards, pk_prepared, traces)
+                .is_ok(),
+        )
+    }
+
+    /// Submit bet as amount of chips put in; 0 means check, or fold when facing a bet
+    pub fn submit_bet(&mut self, player: usize, amount: u64) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::Bet { player, amount });
+        self.make_move(event, |hand| hand.bet(player, amount))
+    }
+
+    fn bet(&mut self, player: usize, amount: u64) -> Result<(), PokerError> {
+        self.check_bet_turn(player)?;
+
+        let action = self.betting_state.action_for_amount(player, amount)?;
+
+        self.act(player, action)
+    }
+
+    /// Put in exactly the chips needed to call, or whole stack when player
+    /// cannot cover the call; checks when there is nothing to call
+    pub fn submit_call(&mut self, player: usize) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::Call { player });
+        self.make_move(event, |hand| {
+            hand.check_bet_turn(player)?;
+
+            let amount = hand
+                .get_call_amount_required(player)?
+                .min(hand.get_chips_remaining(player));
+
+            hand.bet(player, amount)
+        })
+    }
+
+    /// Submit betting decision of the player
+    pub fn submit_action(&mut self, player: usize, action: PokerAction) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::Action { player, action });
+        self.make_move(event, |hand| hand.act(player, action))
+    }
+
+    pub(super) fn act(&mut self, player: usize, action: PokerAction) -> Result<(), PokerError> {
+        self.check_bet_turn(player)?;
+
+        let street = self.current_street();
+        let chips_before = self.betting_state.chips_remaining(player);
+
+        self.betting_state.apply_action(player, action)?;
+
+        self.action_log.push(ActionRecord {
+            player,
+            street,
+            action,
+            amount: chips_before - self.betting_state.chips_remaining(player),
+        });
+
+        self.emit(PokerEvent::BetPlaced { player, action });
+        self.current_state
+            .next_player_masked(&self.betting_state.get_acting_players(), false);
+
+        self.check_betting_round_complete()?;
+
+        Ok(())
     }
 
-    pub fn submit_bet(&mut self, player: usize, amount: u64) -> Result<(), Vec<u8>> {
+    fn check_bet_turn(&self, player: usize) -> Result<(), PokerError> {
         let PokerHandStateEnum::Bet {
             round: _,
             player: p,
         } = self.get_current_state().to_enum()
         else {
-            return Err(b"Not in bet state")?;
+            return Err(PokerError::WrongState);
         };
 
         if p != player {
-            return Err(b"Not your turn to bet")?;
+            return Err(PokerError::NotYourTurn);
         }
 
-        self.betting_state.process_action(player, amount)?;
-        self.current_state
-            .next_player_masked(self.betting_state.get_active_players(), false);
+        Ok(())
+    }
 
-        self.check_betting_round_complete()?;
+    /// Deal extra runs of streets after betting round at `first`, each run
+    /// street by street, putting them after the first run of each street
+    fn deal_board_runs(&mut self, first: usize) -> Result<(), PokerError> {
+        let counts = (first..self.community_cards.len())
+            .map(|index| self.community_count(index))
+            .collect_vec();
+        let run_cards: usize = counts
+            .iter()
+            .map(|&count| self.burn_count(count) + count)
+            .sum();
+
+        if run_cards == 0 || self.shuffled_deck.len() < run_cards * (self.run_it_times - 1) {
+            return Ok(());
+        }
 
+        for _ in 1..self.run_it_times {
+            for (index, &count) in (first..).zip(&counts) {
+                let cards = self.deal_street(count)?;
+                self.community_cards[index].append(cards);
+            }
+        }
+
+        self.board_runs = self.run_it_times;
         Ok(())
     }
 
-    fn check_betting_round_complete(&mut self) -> Result<(), Vec<u8>> {
+    /// Deal street of community cards, burning card first if enabled
+    fn deal_street(&mut self, count: usize) -> Result<UnmaskedCards, PokerError> {
+        if self.burn_count(count) > 0 {
+            let burned = self.shuffled_deck.deal(1)?;
+            self.burned_cards.append(burned);
+        }
+        self.shuffled_deck.deal(count)
+    }
+
+    fn check_betting_round_complete(&mut self) -> Result<(), PokerError> {
+        if let Ok(winner) = self
+            .betting_state
+            .get_active_players()
+            .iter()
+            .positions(|&active| active)
+            .exactly_one()
+        {
+            // Everyone else folded, so last player standing takes the pot
+            // without showdown
+            let awards = self.betting_state.award_pot(&[winner]);
+            self.current_state.current_state = POKER_HAND_STATE_FINISHED;
+
+            self.outcome = Some(HandOutcome {
+                winners: vec![winner],
+                awards: awards.clone(),
+                hand_ranks: vec![None; self.current_state.num_players],
+                ending: HandEnding::Fold,
+            });
+
+            self.emit(PokerEvent::PotsAwarded { awards });
+            self.emit(PokerEvent::HandFinished);
+
+            return Ok(());
+        }
+
         if self.betting_state.is_betting_round_complete() {
             self.current_state.next_dealer();
             let round = self.current_state.current_round;
 
             if self.current_state.next_round()? {
                 self.current_state.current_state = POKER_HAND_STATE_UNMASK_SHOWDOWN;
+                if self.player_folded(self.current_state.current_player) {
+                    self.next_showdown_player();
+                }
             } else {
-                let num_cards_deal = if round == POKER_HOLDEM_PREFLOP { 3 } else { 1 };
-                self.community_cards[round] = self.shuffled_deck.deal(num_cards_deal);
+                // Nobody can bet any more, so rest of the board may be run
+                // more than once
+                let num_acting = self
+                    .betting_state
+                    .get_acting_players()
+                    .iter()
+                    .filter(|&&acting| acting)
+                    .count();
+                if self.run_it_times > 1 && self.board_runs == 1 && num_acting <= 1 {
+                    self.deal_board_runs(round)?;
+                }
                 self.current_state.current_state = POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS;
             }
         }
@@ -0,0 +1,234 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use std::cmp::{Ordering, Reverse};
+
+use itertools::Itertools;
+
+use crate::poker_deck::{PokerCard, Suit};
+
+const ACE: u8 = 14;
+const FIVE: u8 = 5;
+const NINE: u8 = 9;
+
+/// Ranking of best five-card poker hand.
+///
+/// Variants are ordered from weakest to strongest, and card values (2..=14,
+/// Ace high) carried by each variant break ties within same category, so
+/// that comparing two `HandRank` values tells which hand wins.
+///
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandRank {
+    HighCard([u8; 5]),
+    Pair { pair: u8, kickers: [u8; 3] },
+    TwoPair { high: u8, low: u8, kicker: u8 },
+    ThreeOfAKind { three: u8, kickers: [u8; 2] },
+    Straight { high: u8 },
+    Flush([u8; 5]),
+    FullHouse { three: u8, pair: u8 },
+    FourOfAKind { four: u8, kicker: u8 },
+    StraightFlush { high: u8 },
+    RoyalFlush,
+}
+
+impl HandRank {
+    /// Compare hands by short-deck (6+) rules, where flush beats full house
+    pub fn cmp_short_deck(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (HandRank::Flush(_), HandRank::FullHouse { .. }) => Ordering::Greater,
+            (HandRank::FullHouse { .. }, HandRank::Flush(_)) => Ordering::Less,
+            _ => self.cmp(other),
+        }
+    }
+}
+
+/// Tell rank value of the card (2..=14, Ace high)
+pub fn card_value(card: &PokerCard) -> u8 {
+    card.rank().value()
+}
+
+/// Rank exactly five cards, with Ace playing low in A-6-7-8-9 straight if
+/// short deck is used
+fn rank_five(cards: &[&PokerCard], short_deck: bool) -> Option<HandRank> {
+    let mut values: Vec<u8> = cards.iter().map(|c| card_value(c)).collect();
+    let suits: Vec<Suit> = cards.iter().map(|c| c.suit()).collect();
+
+    values.sort_unstable_by(|a, b| b.cmp(a));
+
+    let is_flush = suits.iter().all_equal();
+    let is_distinct = values.iter().all_unique();
+
+    let straight_high = if !is_distinct {
+        None
+    } else if values[0] - values[4] == 4 {
+        Some(values[0])
+    } else if values == [ACE, 5, 4, 3, 2] {
+        // The wheel: Ace plays low
+        Some(FIVE)
+    } else if short_deck && values == [ACE, 9, 8, 7, 6] {
+        // Short deck has no cards below Six, so Ace plays low below Six
+        Some(NINE)
+    } else {
+        None
+    };
+
+    // Group by value, largest groups first, then highest value first
+    let groups: Vec<(usize, u8)> = values
+        .iter()
+        .dedup_with_count()
+        .map(|(count, value)| (count, *value))
+        .sorted_by(|a, b| b.cmp(a))
+        .collect();
+
+    let kickers = |skip: usize| -> Vec<u8> { groups.iter().skip(skip).map(|g| g.1).collect() };
+
+    let rank = match (straight_high, is_flush) {
+        (Some(ACE), true) => HandRank::RoyalFlush,
+        (Some(high), true) => HandRank::StraightFlush { high },
+        _ if groups[0].0 == 4 => HandRank::FourOfAKind {
+            four: groups[0].1,
+            kicker: groups[1].1,
+        },
+        _ if groups[0].0 == 3 && groups[1].0 == 2 => HandRank::FullHouse {
+            three: groups[0].1,
+            pair: groups[1].1,
+        },
+        (_, true) => HandRank::Flush(values.try_into().ok()?),
+        (Some(high), false) => HandRank::Straight { high },
+        _ if groups[0].0 == 3 => HandRank::ThreeOfAKind {
+            three: groups[0].1,
+            kickers: kickers(1).try_into().ok()?,
+        },
+        _ if groups[0].0 == 2 && groups[1].0 == 2 => HandRank::TwoPair {
+            high: groups[0].1,
+            low: groups[1].1,
+            kicker: groups[2].1,
+        },
+        _ if groups[0].0 == 2 => HandRank::Pair {
+            pair: groups[0].1,
+            kickers: kickers(1).try_into().ok()?,
+        },
+        _ => HandRank::HighCard(values.try_into().ok()?),
+    };
+
+    Some(rank)
+}
+
+/// Rank best five-card hand out of given cards (typically 2 hole cards and
+/// 5 community cards).
+///
+/// Returns None when fewer than five cards were given.
+///
+pub fn rank_hand(cards: &[PokerCard]) -> Option<HandRank> {
+    rank_hand_with(cards, false)
+}
+
+/// Rank best five-card hand out of given cards, optionally by short-deck
+/// (6+) rules.
+///
+/// Hands ranked by short-deck rules must be compared using
+/// `HandRank::cmp_short_deck()`.
+///
+pub fn rank_hand_with(cards: &[PokerCard], short_deck: bool) -> Option<HandRank> {
+    if cards.len() < 5 {
+        return None;
+    }
+    let mut best = None;
+    for five in cards.iter().combinations(5) {
+        let rank = rank_five(&five, short_deck)?;
+        let is_better = |b: &HandRank| {
+            if short_deck {
+                rank.cmp_short_deck(b).is_gt()
+            } else {
+                rank > *b
+            }
+        };
+        if best.as_ref().is_none_or(is_better) {
+            best = Some(rank);
+        }
+    }
+    best
+}
+
+/// Pick best five cards out of seven (2 hole cards and 5 community cards)
+/// together with their rank.
+///
+/// Cards are ordered the way the hand is read: largest groups first, then
+/// higher values first, with Ace played low placed last in the wheel
+/// (A-2-3-4-5), e.g. 5-4-3-2-A.
+///
+pub fn best_hand(cards: &[PokerCard; 7]) -> (HandRank, [PokerCard; 5]) {
+    let mut best: Option<(HandRank, Vec<&PokerCard>)> = None;
+    for five in cards.iter().combinations(5) {
+        let Some(rank) = rank_five(&five, false) else {
+            continue;
+        };
+        if best.as_ref().is_none_or(|(b, _)| rank > *b) {
+            best = Some((rank, five));
+        }
+    }
+    let (rank, five) = best.expect("Seven cards always make a hand");
+
+    let count = |value: u8| five.iter().filter(|c| card_value(c) == value).count();
+    let mut ordered: Vec<PokerCard> = five
+        .iter()
+        .map(|c| **c)
+        .sorted_by_key(|c| Reverse((count(card_value(c)), card_value(c))))
+        .collect();
+
+    if matches!(
+        rank,
+        HandRank::Straight { high: FIVE } | HandRank::StraightFlush { high: FIVE }
+    ) {
+        ordered.rotate_left(1);
+    }
+
+    let ordered = ordered.try_into().expect("Exactly five cards");
+    (rank, ordered)
+}
+
+/// Rank best Omaha hand, which must use exactly two of the hole cards and
+/// three of the community cards.
+///
+/// Returns None when there are not enough cards.
+///
+pub fn rank_omaha_hand(
+    hole_cards: &[PokerCard],
+    community_cards: &[PokerCard],
+) -> Option<HandRank> {
+    rank_omaha_hand_with(hole_cards, community_cards, false)
+}
+
+/// Rank best Omaha hand, optionally by short-deck (6+) rules.
+///
+/// Hands ranked by short-deck rules must be compared using
+/// `HandRank::cmp_short_deck()`.
+///
+pub fn rank_omaha_hand_with(
+    hole_cards: &[PokerCard],
+    community_cards: &[PokerCard],
+    short_deck: bool,
+) -> Option<HandRank> {
+    let mut best = None;
+    for hole in hole_cards.iter().combinations(2) {
+        for community in community_cards.iter().combinations(3) {
+            let five: Vec<_> = hole.iter().chain(community.iter()).copied().collect();
+            let rank = rank_five(&five, short_deck)?;
+            let is_better = |b: &HandRank| {
+                if short_deck {
+                    rank.cmp_short_deck(b).is_gt()
+                } else {
+                    rank > *b
+                }
+            };
+            if best.as_ref().is_none_or(is_better) {
+                best = Some(rank);
+            }
+        }
+    }
+    best
+}
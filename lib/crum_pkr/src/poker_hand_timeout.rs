@@ -0,0 +1,105 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use crate::{
+    poker_bets::PokerAction,
+    poker_error::PokerError,
+    poker_event::PokerEvent,
+    poker_hand::PokerHand,
+    poker_replay::HandEvent,
+    poker_state::{POKER_HAND_STATE_CHEATED, PokerHandStateEnum},
+};
+
+impl PokerHand {
+    /// Give each player `timeout` units of time to make their move.
+    ///
+    /// Units are whatever the caller passes to `tick`, e.g. seconds or block
+    /// numbers.
+    ///
+    pub fn set_action_timeout(&mut self, timeout: u64) {
+        self.action_timeout = Some(timeout);
+        self.record_config();
+    }
+
+    pub fn get_action_timeout(&self) -> Option<u64> {
+        self.action_timeout
+    }
+
+    /// Time left for current player to make their move, if the clock is running
+    pub fn time_remaining(&self, now: u64) -> Option<u64> {
+        self.deadline.map(|deadline| deadline.saturating_sub(now))
+    }
+
+    /// Let time pass, and act on behalf of the player who let it run out.
+    ///
+    /// Clock of each player starts as soon as their turn begins, counting
+    /// from the last tick, or from the first tick if there was none yet.
+    /// Player who runs out of time while betting is folded. Any other move is
+    /// mandatory for the hand to carry on, so the hand is aborted with that
+    /// player marked as cheater.
+    ///
+    /// Returns player who timed out, if any.
+    ///
+    pub fn tick(&mut self, now: u64) -> Result<Option<usize>, PokerError> {
+        let event = self.recording(|| HandEvent::Tick { now });
+        self.make_move(event, |hand| hand.run_clock(now))
+    }
+
+    fn run_clock(&mut self, now: u64) -> Result<Option<usize>, PokerError> {
+        self.last_tick = Some(now);
+
+        let Some(timeout) = self.action_timeout else {
+            return Ok(None);
+        };
+
+        if !self.is_clock_running() {
+            self.deadline = None;
+            return Ok(None);
+        }
+
+        let Some(deadline) = self.deadline else {
+            self.deadline = Some(now.saturating_add(timeout));
+            return Ok(None);
+        };
+
+        if now < deadline {
+            return Ok(None);
+        }
+
+        let player = self.current_state.current_player;
+        self.emit(PokerEvent::PlayerTimedOut { player });
+
+        if let PokerHandStateEnum::Bet { .. } = self.current_state.to_enum() {
+            self.act(player, PokerAction::Fold)?;
+        } else {
+            self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+            self.emit(PokerEvent::CheatDetected { player });
+        }
+
+        Ok(Some(player))
+    }
+
+    /// Start clock of the player whose turn just began
+    pub(super) fn start_clock(&mut self) {
+        self.deadline = match (self.action_timeout, self.last_tick) {
+            (Some(timeout), Some(now)) if self.is_clock_running() => {
+                Some(now.saturating_add(timeout))
+            }
+            _ => None,
+        };
+    }
+
+    /// Clock runs while there is someone to make a move
+    fn is_clock_running(&self) -> bool {
+        !matches!(
+            self.current_state.to_enum(),
+            PokerHandStateEnum::Finished
+                | PokerHandStateEnum::Cheated { .. }
+                | PokerHandStateEnum::Invalid
+        )
+    }
+}
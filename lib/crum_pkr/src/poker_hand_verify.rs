@@ -1,58 +1,395 @@
 use super::poker_hand::PokerHand;
-use pairing::{MultiMillerLoop, group::Group};
+use bls12_381::{G1Affine, G2Prepared};
+use crum_bls::{
+    proof::UnmaskProof,
+    referee::{AUDIT_ENTRY_LEN, CARD_COMPRESSED_LEN, COMMUNITY_CARD, UNDEALT_CARD},
+    types::PublicKey,
+    verify,
+};
+use itertools::Itertools;
+use std::ops::Range;
 
-use crate::
+use crate::{
+    poker_error::PokerError,
+    poker_event::PokerEvent,
+    poker_replay::HandEvent,
     poker_state::{
-        POKER_HAND_STATE_CHEATED,
-        POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS,
+        POKER_HAND_STATE_CHEATED, POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS,
         POKER_HAND_STATE_UNMASK_HOLE_CARDS, POKER_HAND_STATE_UNMASK_SHOWDOWN,
+    },
+};
+
+/// Single peel of the unmasking history.
+///
+/// Player removed their mask from `masked` card, resulting in `unmasked` card.
+/// Points are kept in their 48-byte compressed form.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuditEntry {
+    pub player: usize,
+    pub unmasked: Vec<u8>,
+    pub masked: Vec<u8>,
+}
+
+impl AuditEntry {
+    /// Decode (unmasked, masked) points, or None if either is not a valid point
+    pub fn to_points(&self) -> Option<(G1Affine, G1Affine)> {
+        let decode = |bytes: &[u8]| {
+            let bytes: [u8; 48] = bytes.try_into().ok()?;
+            G1Affine::from_compressed(&bytes).into_option()
+        };
+        Some((decode(&self.unmasked)?, decode(&self.masked)?))
+    }
+
+    /// Encode entry as taken by `referee::verify_hand`, or None if player
+    /// index does not fit in a byte or points are not compressed
+    pub fn to_bytes(&self) -> Option<[u8; AUDIT_ENTRY_LEN]> {
+        if self.unmasked.len() != CARD_COMPRESSED_LEN || self.masked.len() != CARD_COMPRESSED_LEN {
+            return None;
+        }
+        let mut bytes = [0u8; AUDIT_ENTRY_LEN];
+        bytes[0] = u8::try_from(self.player).ok()?;
+        bytes[1..1 + CARD_COMPRESSED_LEN].copy_from_slice(&self.unmasked);
+        bytes[1 + CARD_COMPRESSED_LEN..].copy_from_slice(&self.masked);
+        Some(bytes)
+    }
+}
+
+/// Where cards of the hand were dealt from in the final shuffled deck
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DealtLayout {
+    /// Positions of hole cards of each player
+    pub hole_cards: Vec<Range<usize>>,
+    /// Positions of community cards of each round from the flop on, with
+    /// one range for each run of the board
+    pub community_cards: Vec<Vec<Range<usize>>>,
+    /// Positions of burned cards
+    pub burned_cards: Vec<usize>,
+    /// Masked hole cards of each player as dealt
+    pub hole_points: Vec<Vec<G1Affine>>,
+    /// Masked community cards of each round as dealt, runs one after another
+    pub community_points: Vec<Vec<G1Affine>>,
+}
+
+/// Outcome of auditing whole hand, e.g. by third party resolving dispute
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuditReport {
+    /// Players in order they shuffled, and whether their shuffle was proven
+    pub shuffles: Vec<(usize, bool)>,
+    /// Number of peels of unmasking history verified
+    pub peels: usize,
+    /// Player caught cheating, with shuffles checked before unmasking
+    pub cheater: Option<usize>,
+}
+
+impl AuditReport {
+    /// Tell whether every shuffle and unmasking step has been proven
+    pub fn is_fair(&self) -> bool {
+        self.cheater.is_none()
     }
-;
+}
 
 impl PokerHand {
-    /// Replay and verify whole unmasking history.
-    /// 
-    /// This is efficient algorithm using only single Final Exponentiation call.
-    /// 
-    pub fn verify_unmasking(&mut self) -> Result<Option<usize>, Vec<u8>> {
+    /// Number of pairing terms the batched unmasking audit runs for a hand
+    /// played all the way to showdown.
+    ///
+    /// Each peel contributes two terms: e(unmasked, PK) and e(masked, -G2).
+    /// Once the hand is finished, community cards are counted as dealt,
+    /// including extra runs of the board; before that, as many as the hand
+    /// may deal if the board is run every time allowed. Burned cards are
+    /// never unmasked, so they add no terms.
+    ///
+    /// Only players who show their cards at showdown peel their own hole
+    /// cards. Before the hand is finished, every player still in the hand and
+    /// not mucked is counted, as any of them may yet show.
+    ///
+    pub fn audit_pairing_count(&self) -> usize {
+        let num_players = self.current_state.num_players;
+
+        let community_cards = match self.dealt_layout() {
+            Ok(layout) if self.current_state.is_finished() => layout
+                .community_cards
+                .iter()
+                .flatten()
+                .map(Range::len)
+                .sum(),
+            _ => self.community_layout.iter().sum::<usize>() * self.run_it_times,
+        };
+
+        // Every player peels hole cards of every other player
+        let hole_peels = num_players * (num_players - 1) * self.hole_cards;
+        // Every player peels every community card
+        let community_peels = num_players * community_cards;
+        // Players who show peel own hole cards at showdown
+        let showdown_players = if self.current_state.is_finished() {
+            self.unmasking_sequence
+                .iter()
+                .filter(|(_, state_type, _)| *state_type == POKER_HAND_STATE_UNMASK_SHOWDOWN)
+                .count()
+        } else {
+            (0..num_players)
+                .filter(|&player| {
+                    self.betting_state.get_active_players()[player] && !self.mucked_players[player]
+                })
+                .count()
+        };
+        let showdown_peels = showdown_players * self.hole_cards;
+
+        2 * (hole_peels + community_peels + showdown_peels)
+    }
+
+    /// Number of pairing terms needed to verify traced shuffles of all players.
+    ///
+    /// Each trace contributes two terms: e(after, -G2) and e(before, PK).
+    ///
+    pub fn shuffle_pairing_count(&self) -> usize {
+        2 * self.current_state.num_players * self.poker_deck.len()
+    }
+
+    /// Estimated gas required by on-chain referee to verify whole hand.
+    ///
+    /// Table operator can use this to reject configurations (too many players
+    /// or rounds) that would not fit within the block gas limit.
+    ///
+    pub fn estimated_verification_gas(&self, gas_per_pairing: u64) -> u64 {
+        let pairings = self.audit_pairing_count() + self.shuffle_pairing_count();
+        pairings as u64 * gas_per_pairing
+    }
+
+    /// Keccak256 hash of the final shuffled deck, as committed on-chain.
+    ///
+    /// This is the deck before any cards were dealt, so the commitment stays
+    /// the same throughout the hand.
+    ///
+    pub fn deck_commitment(&self) -> [u8; 32] {
+        self.shuffle_history
+            .last()
+            .unwrap_or(&self.shuffled_deck)
+            .hash()
+    }
+
+    /// Tell whether final shuffled deck matches commitment stored on-chain
+    pub fn verify_deck_commitment(&self, expected: [u8; 32]) -> bool {
+        self.deck_commitment() == expected
+    }
+
+    /// Verify proofs of unmasking submitted by player during the hand.
+    ///
+    /// Player must use same public key for all proofs, and must later reveal
+    /// that same key. Failed proof marks player as cheater.
+    ///
+    pub(super) fn verify_unmask_proofs(
+        &mut self,
+        player: usize,
+        pk: PublicKey,
+        before: &[G1Affine],
+        after: &[G1Affine],
+        proofs: &[UnmaskProof],
+    ) -> Result<(), PokerError> {
+        if before.len() != after.len() || proofs.len() != after.len() {
+            return Err(PokerError::MalformedCards);
+        }
+
+        let is_same_key = self.proof_keys[player].is_none_or(|proof_key| proof_key == pk);
+
+        let is_valid = is_same_key
+            && before
+                .iter()
+                .zip(after)
+                .zip(proofs)
+                .all(|((masked, unmasked), proof)| {
+                    verify::verify_unmask_proof(masked, unmasked, &pk, proof)
+                });
+
+        if !is_valid {
+            self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+            self.emit(PokerEvent::CheatDetected { player });
+            return Err(PokerError::UnmaskCheat(player));
+        }
+
+        self.proof_keys[player] = Some(pk);
+        Ok(())
+    }
+
+    /// Verify shuffle step of the player as soon as it is submitted.
+    ///
+    /// Public key becomes key player must use to prove unmasking, and must
+    /// later reveal. Failed verification marks player as cheater.
+    ///
+    pub(super) fn verify_shuffle_proof(
+        &mut self,
+        player: usize,
+        pk: PublicKey,
+        before: &[G1Affine],
+        after: &[G1Affine],
+        traces: &[verify::ShuffleTrace],
+    ) -> Result<(), PokerError> {
+        let is_same_key = self.proof_keys[player].is_none_or(|proof_key| proof_key == pk);
+
+        let is_valid = is_same_key
+            && !bool::from(pk.is_identity())
+            && verify::verify_shuffle_traced_prepared(before, after, &G2Prepared::from(pk), traces)
+                .is_ok();
+
+        if !is_valid {
+            self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+            self.emit(PokerEvent::CheatDetected { player });
+            return Err(PokerError::ShuffleForgery(player));
+        }
+
+        self.proof_keys[player] = Some(pk);
+        Ok(())
+    }
+
+    /// Tell whether any card was revealed more than once.
+    ///
+    /// Only fully unmasked cards are checked, i.e. community cards and hole
+    /// cards of players who revealed them at showdown.
+    ///
+    pub fn has_duplicate_cards(&self) -> bool {
+        !self
+            .player_cards
+            .iter()
+            .chain(self.community_cards.iter())
+            .flat_map(|cards| cards.as_slice())
+            .filter_map(|card| self.poker_deck.find_card(*card))
+            .all_unique()
+    }
+
+    /// Public keys of all players
+    pub fn public_keys(&self) -> Result<Vec<&PublicKey>, PokerError> {
+        self.player_keys
+            .iter()
+            .enumerate()
+            .map(|(player, pk_opt)| pk_opt.as_ref().ok_or(PokerError::MissingPublicKey(player)))
+            .collect()
+    }
+
+    /// Public keys of all players prepared for pairing
+    pub fn prepared_public_keys(&self) -> Result<Vec<&G2Prepared>, PokerError> {
+        self.prepared_keys
+            .iter()
+            .enumerate()
+            .map(|(player, pk_opt)| pk_opt.as_ref().ok_or(PokerError::MissingPublicKey(player)))
+            .collect()
+    }
+
+    /// Positions and masked points of cards dealt from the final shuffled
+    /// deck, i.e. where replay of unmasking history starts from.
+    ///
+    /// Cards of rounds not dealt yet have empty ranges.
+    ///
+    pub fn dealt_layout(&self) -> Result<DealtLayout, PokerError> {
         let final_shuffled_deck = self
             .shuffle_history
             .last()
-            .ok_or_else(|| b"No shuffle history")?
-            .cards();
+            .ok_or(PokerError::NoShuffleHistory)?
+            .as_slice();
 
-        let num_players = self.current_state.num_players;
         let mut deck_idx = 0;
+        let mut take = |count: usize| {
+            deck_idx += count;
+            deck_idx - count..deck_idx
+        };
+
+        let hole_cards = (0..self.current_state.num_players)
+            .map(|_| take(self.hole_cards))
+            .collect_vec();
+
+        // Community cards of all streets are dealt at once, straight after
+        // hole cards, each street after its burned card
+        let mut burned_cards = Vec::new();
+        let mut community_cards = Vec::with_capacity(self.community_cards.len());
+        for (index, cards) in self.community_cards.iter().enumerate() {
+            let count = self.community_count(index).min(cards.len());
+            if self.burn_count(count) > 0 {
+                burned_cards.push(take(1).start);
+            }
+            community_cards.push(vec![take(count)]);
+        }
 
-        let mut tracked_hole_cards: Vec<Vec<bls12_381::G1Affine>> = Vec::new();
-        for _ in 0..num_players {
-            tracked_hole_cards.push(final_shuffled_deck[deck_idx..deck_idx + 2].to_vec());
-            deck_idx += 2;
+        // Extra runs of the board come next, each run street by street
+        for _ in 1..self.board_runs {
+            for (index, ranges) in community_cards.iter_mut().enumerate() {
+                let count = self.community_count(index);
+                if self.community_cards[index].len() > count {
+                    if self.burn_count(count) > 0 {
+                        burned_cards.push(take(1).start);
+                    }
+                    ranges.push(take(count));
+                }
+            }
         }
 
-        let mut tracked_community_cards: Vec<Vec<bls12_381::G1Affine>> = vec![
-            final_shuffled_deck[deck_idx..deck_idx + 3].to_vec(),
-            final_shuffled_deck[deck_idx + 3..deck_idx + 4].to_vec(),
-            final_shuffled_deck[deck_idx + 4..deck_idx + 5].to_vec(),
-        ];
+        let points = |range: &Range<usize>| {
+            final_shuffled_deck
+                .get(range.clone())
+                .ok_or(PokerError::DeckExhausted)
+        };
 
-        let mut comm_round_idx = 0;
-        let mut comm_unmask_count = 0;
+        let hole_points = hole_cards
+            .iter()
+            .map(|range| points(range).map(<[G1Affine]>::to_vec))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let community_points = community_cards
+            .iter()
+            .map(|ranges| {
+                ranges.iter().try_fold(Vec::new(), |mut cards, range| {
+                    cards.extend_from_slice(points(range)?);
+                    Ok(cards)
+                })
+            })
+            .collect::<Result<Vec<_>, PokerError>>()?;
 
-        // 1. Prepare G2 points once for the entire batch to save CPU cycles
-        let neg_g2_gen = -bls12_381::G2Affine::generator();
-        let neg_g2_prepared = bls12_381::G2Prepared::from(neg_g2_gen);
+        Ok(DealtLayout {
+            hole_cards,
+            community_cards,
+            burned_cards,
+            hole_points,
+            community_points,
+        })
+    }
 
-        let mut prepared_pks = Vec::new();
-        for pk_opt in &self.player_keys {
-            let pk = pk_opt.ok_or_else(|| b"Missing PK for unmask audit")?;
-            prepared_pks.push(bls12_381::G2Prepared::from(pk));
+    /// Where each card of the final shuffled deck went, as taken by
+    /// `referee::verify_hand`: seat of the player holding it,
+    /// `COMMUNITY_CARD`, or `UNDEALT_CARD` for burned and undealt cards.
+    ///
+    pub fn dealt_cards(&self) -> Result<Vec<u8>, PokerError> {
+        let layout = self.dealt_layout()?;
+        let mut dealt_cards = vec![UNDEALT_CARD; self.poker_deck.len()];
+
+        for (player, range) in layout.hole_cards.into_iter().enumerate() {
+            dealt_cards[range].fill(player as u8);
+        }
+        for range in layout.community_cards.into_iter().flatten() {
+            dealt_cards[range].fill(COMMUNITY_CARD);
         }
+        Ok(dealt_cards)
+    }
+
+    /// Replay unmasking history and collect every peel as (unmasked, masked, player).
+    ///
+    /// Peels of many hands can be verified together using
+    /// `verify::verify_unmasking_batch`.
+    ///
+    pub fn unmasking_peels(&self) -> Result<Vec<(G1Affine, G1Affine, usize)>, PokerError> {
+        let num_players = self.current_state.num_players;
+        let DealtLayout {
+            hole_points: mut tracked_hole_cards,
+            community_points: mut tracked_community_cards,
+            ..
+        } = self.dealt_layout()?;
+
+        let mut comm_round_idx = 0;
+        let mut comm_unmask_count = 0;
 
         // We will collect all peeling actions here: (unmasked, masked, action_player)
         let mut audit_trail = Vec::new();
 
-        // 2. Replay history and collect the trace instead of verifying immediately
+        // Replay history and collect the trace instead of verifying immediately
         for (action_player, state_type, submitted_cards) in &self.unmasking_sequence {
             match *state_type {
                 POKER_HAND_STATE_UNMASK_HOLE_CARDS => {
@@ -61,22 +398,22 @@ impl PokerHand {
                             continue;
                         }
                         let before = &tracked_hole_cards[target_player];
-                        let after = submitted_cards[target_player].cards();
+                        let after = submitted_cards[target_player].as_slice();
 
                         for (b, a) in before.iter().zip(after.iter()) {
                             audit_trail.push((*a, *b, *action_player));
                         }
-                        tracked_hole_cards[target_player] = after;
+                        tracked_hole_cards[target_player] = after.to_vec();
                     }
                 }
                 POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS => {
                     let before = &tracked_community_cards[comm_round_idx];
-                    let after = submitted_cards[0].cards();
+                    let after = submitted_cards[0].as_slice();
 
                     for (b, a) in before.iter().zip(after.iter()) {
                         audit_trail.push((*a, *b, *action_player));
                     }
-                    tracked_community_cards[comm_round_idx] = after;
+                    tracked_community_cards[comm_round_idx] = after.to_vec();
 
                     comm_unmask_count += 1;
                     if comm_unmask_count == num_players {
@@ -87,48 +424,118 @@ impl PokerHand {
                 POKER_HAND_STATE_UNMASK_SHOWDOWN => {
                     let target_player = *action_player;
                     let before = &tracked_hole_cards[target_player];
-                    let after = submitted_cards[target_player].cards();
+                    let after = submitted_cards[target_player].as_slice();
 
                     for (b, a) in before.iter().zip(after.iter()) {
                         audit_trail.push((*a, *b, *action_player));
                     }
-                    tracked_hole_cards[target_player] = after;
+                    tracked_hole_cards[target_player] = after.to_vec();
                 }
                 _ => {}
             }
         }
 
-        // 3. Build the giant batch for the Miller Loop
-        let mut miller_terms = Vec::with_capacity(audit_trail.len() * 2);
-        for (unmasked, masked, action_player) in &audit_trail {
-            miller_terms.push((unmasked, &prepared_pks[*action_player]));
-            miller_terms.push((masked, &neg_g2_prepared));
+        Ok(audit_trail)
+    }
+
+    /// Every peel of the unmasking history in serializable form.
+    ///
+    /// External verifier can decode the entries and re-run the batched check
+    /// without replaying the unmasking sequence. Trail is empty until the
+    /// deck is shuffled.
+    ///
+    pub fn unmasking_audit_trail(&self) -> Vec<AuditEntry> {
+        self.unmasking_peels()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(unmasked, masked, player)| AuditEntry {
+                player,
+                unmasked: unmasked.to_compressed().to_vec(),
+                masked: masked.to_compressed().to_vec(),
+            })
+            .collect()
+    }
+
+    /// Replay and verify whole unmasking history.
+    ///
+    /// This is efficient algorithm using only single Final Exponentiation call.
+    ///
+    pub fn verify_unmasking(&mut self) -> Result<Option<usize>, PokerError> {
+        let event = self.recording(|| HandEvent::VerifyUnmasking);
+        self.make_move(event, |hand| {
+            let cheater = hand.find_unmasking_cheater()?;
+            if cheater.is_some() {
+                hand.current_state.current_state = POKER_HAND_STATE_CHEATED;
+            }
+            Ok(cheater)
+        })
+    }
+
+    /// Verify every shuffle step and whole unmasking history without
+    /// changing state of the hand.
+    ///
+    /// Shuffle step is audited against traces kept by the hand, once its
+    /// player has revealed their public key together with them.
+    ///
+    pub fn audit_full(&self) -> Result<AuditReport, PokerError> {
+        let num_players = self.current_state.num_players;
+        let dealer = self.current_state.dealer_button;
+
+        let mut shuffles = Vec::with_capacity(self.shuffle_history.len());
+        for step in 0..self.shuffle_history.len() {
+            let player = (dealer + step) % num_players;
+            let (Some(traces), Some(_)) = (&self.shuffle_traces[player], &self.player_keys[player])
+            else {
+                continue;
+            };
+            shuffles.push((player, self.verify_shuffle(player, traces)?));
         }
 
-        // 4. The Optimistic Batch Execution (O(1) final exponentiation for the whole game)
-        let is_valid: bool = bls12_381::Bls12::multi_miller_loop(&miller_terms)
-            .final_exponentiation()
-            .is_identity()
-            .into();
+        let peels = self.unmasking_peels()?.len();
+        let cheater = shuffles
+            .iter()
+            .find(|(_, valid)| !valid)
+            .map(|(player, _)| *player);
+        let cheater = match cheater {
+            Some(player) => Some(player),
+            None => self.find_unmasking_cheater()?,
+        };
+
+        Ok(AuditReport {
+            shuffles,
+            peels,
+            cheater,
+        })
+    }
+
+    /// Find player who failed to prove their unmasking
+    fn find_unmasking_cheater(&self) -> Result<Option<usize>, PokerError> {
+        let pks = self.public_keys()?;
+        let prepared_pks = self.prepared_public_keys()?;
+        let audit_trail = self.unmasking_peels()?;
+
+        let batch: Vec<_> = audit_trail
+            .iter()
+            .map(|&(unmasked, masked, action_player)| {
+                (
+                    unmasked,
+                    masked,
+                    pks[action_player],
+                    prepared_pks[action_player],
+                )
+            })
+            .collect();
 
-        if is_valid {
+        // The Optimistic Batch Execution (O(1) final exponentiation for the whole game)
+        if verify::verify_unmasking_batch(&batch) {
             // The game was perfectly fair.
             return Ok(None);
         }
 
-        // 5. Fallback: The batch failed. Someone cheated.
+        // Fallback: The batch failed. Someone cheated.
         // We run the individual checks to find out exactly who it was.
-        for (unmasked, masked, action_player) in audit_trail {
-            let is_match: bool = bls12_381::Bls12::multi_miller_loop(&[
-                (&unmasked, &prepared_pks[action_player]),
-                (&masked, &neg_g2_prepared),
-            ])
-            .final_exponentiation()
-            .is_identity()
-            .into();
-
-            if !is_match {
-                self.current_state.current_state = POKER_HAND_STATE_CHEATED;
+        for (term, (_, _, action_player)) in batch.iter().zip(audit_trail) {
+            if !verify::verify_unmasking_batch(std::slice::from_ref(term)) {
                 return Ok(Some(action_player));
             }
         }
@@ -0,0 +1,84 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use itertools::Itertools;
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{
+    poker_deck::{PokerCard, Rank, Suit},
+    poker_hand_rank::rank_hand,
+    poker_state::{POKER_HOLDEM_COMMUNITY_CARDS, POKER_HOLDEM_HOLE_CARDS},
+};
+
+/// Estimate strength of Hold'em hole cards against given number of opponents.
+///
+/// Deals random opponent holes and the rest of the board `samples` times, and
+/// tells share of the pots won, from 0.0 (never wins) to 1.0 (always wins).
+/// Split pots count as a fraction of the win.
+///
+pub fn estimate_strength(
+    hole: &[PokerCard],
+    board: &[PokerCard],
+    opponents: usize,
+    samples: usize,
+) -> f64 {
+    estimate_strength_with_rng(hole, board, opponents, samples, &mut rand::thread_rng())
+}
+
+/// Estimate strength of hole cards drawing samples from given random source
+pub fn estimate_strength_with_rng(
+    hole: &[PokerCard],
+    board: &[PokerCard],
+    opponents: usize,
+    samples: usize,
+    rng: &mut impl Rng,
+) -> f64 {
+    let mut remaining: Vec<PokerCard> = Rank::ALL
+        .into_iter()
+        .cartesian_product(Suit::ALL)
+        .map(|(rank, suit)| PokerCard::new(rank, suit))
+        .filter(|card| !hole.contains(card) && !board.contains(card))
+        .collect();
+
+    let board_needed = POKER_HOLDEM_COMMUNITY_CARDS.saturating_sub(board.len());
+    let cards_needed = opponents * POKER_HOLDEM_HOLE_CARDS + board_needed;
+    if samples == 0 || cards_needed > remaining.len() {
+        return 0.0;
+    }
+
+    let mut won = 0.0;
+    for _ in 0..samples {
+        let (drawn, _) = remaining.partial_shuffle(rng, cards_needed);
+        let (board_rest, opponent_holes) = drawn.split_at(board_needed);
+
+        let full_board: Vec<PokerCard> = board.iter().chain(board_rest).copied().collect();
+        let rank_of = |cards: &[PokerCard]| {
+            let seven: Vec<PokerCard> = cards.iter().chain(&full_board).copied().collect();
+            rank_hand(&seven)
+        };
+
+        let own_rank = rank_of(hole);
+        let mut ties = 0;
+        let mut beaten = false;
+        for opponent_hole in opponent_holes.chunks(POKER_HOLDEM_HOLE_CARDS) {
+            let rank = rank_of(opponent_hole);
+            if rank > own_rank {
+                beaten = true;
+                break;
+            }
+            if rank == own_rank {
+                ties += 1;
+            }
+        }
+
+        if !beaten {
+            won += 1.0 / (ties + 1) as f64;
+        }
+    }
+
+    won / samples as f64
+}
@@ -0,0 +1,461 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use crum_bls::{proof::UnmaskProof, types::PublicKey, verify::ShuffleTrace};
+
+use crate::{
+    poker_bets::PokerAction,
+    poker_blinds::{BlindLevel, BlindSchedule},
+    poker_deck::{MaskedCards, UnmaskedCards},
+    poker_error::PokerError,
+    poker_hand::{PokerHand, ShowdownPolicy},
+    poker_state::POKER_HAND_STATE_CHEATED,
+    poker_table::PokerTable,
+};
+
+/// Settings of the hand, chosen before it starts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandConfig {
+    pub community_layout: Vec<usize>,
+    pub short_deck: bool,
+    pub run_it_times: usize,
+    pub burn_cards: bool,
+    pub ante: u64,
+    pub big_blind: u64,
+    pub showdown_policy: ShowdownPolicy,
+    pub action_timeout: Option<u64>,
+}
+
+/// Action submitted by a player during the hand.
+///
+/// Cryptographic steps carry submitted points and keys, so that replaying
+/// them reproduces exactly the same hand.
+///
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum HandEvent {
+    ShuffleDeck {
+        player: usize,
+        deck: MaskedCards,
+    },
+    ShuffleDeckWithProof {
+        player: usize,
+        deck: MaskedCards,
+        pk: PublicKey,
+        traces: Vec<ShuffleTrace>,
+    },
+    SmallBlind {
+        player: usize,
+    },
+    BigBlind {
+        player: usize,
+    },
+    UnmaskHoleCards {
+        player: usize,
+        cards: Vec<UnmaskedCards>,
+    },
+    UnmaskHoleCardsWithProofs {
+        player: usize,
+        cards: Vec<UnmaskedCards>,
+        pk: PublicKey,
+        proofs: Vec<Vec<UnmaskProof>>,
+    },
+    UnmaskCommunityCards {
+        player: usize,
+        round: usize,
+        cards: UnmaskedCards,
+    },
+    UnmaskCommunityCardsWithProofs {
+        player: usize,
+        round: usize,
+        cards: UnmaskedCards,
+        pk: PublicKey,
+        proofs: Vec<UnmaskProof>,
+    },
+    UnmaskShowdown {
+        player: usize,
+        cards: Vec<UnmaskedCards>,
+    },
+    Muck {
+        player: usize,
+    },
+    Bet {
+        player: usize,
+        amount: u64,
+    },
+    Call {
+        player: usize,
+    },
+    Action {
+        player: usize,
+        action: PokerAction,
+    },
+    SubmitPublicKey {
+        player: usize,
+        pk: PublicKey,
+        traces: Vec<ShuffleTrace>,
+    },
+    /// Unmasking verified before all keys are revealed
+    VerifyUnmasking,
+    /// Time passed, possibly running out player's clock
+    Tick {
+        now: u64,
+    },
+    /// Settings of the hand changed
+    Configure(HandConfig),
+    /// Hand reset for the next one between the same players
+    Reset {
+        dealer_button: usize,
+        stacks: Vec<u64>,
+    },
+}
+
+/// Table-level call recorded for deterministic replay
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum TableEvent {
+    Created {
+        max_players: usize,
+        max_rounds: usize,
+        hole_cards: usize,
+    },
+    Join {
+        player: u32,
+    },
+    Leave {
+        player: u32,
+    },
+    Rebuy {
+        player: u32,
+        amount: u64,
+    },
+    StartHand {
+        initial_chips: u64,
+        small_blind: u64,
+    },
+    /// Hand started with blinds of the level
+    StartHandAtLevel {
+        initial_chips: u64,
+        level: BlindLevel,
+    },
+    /// Buy-in limit or blind schedule of the table changed
+    Configure {
+        max_buy_in: Option<u64>,
+        blind_schedule: Option<BlindSchedule>,
+    },
+    AdvanceLevel,
+    TickBlinds {
+        now: u64,
+    },
+    EndHand,
+    Hand(HandEvent),
+}
+
+impl PokerHand {
+    /// Submit player action described by the event
+    pub fn apply_event(&mut self, event: HandEvent) -> Result<(), PokerError> {
+        match event {
+            HandEvent::ShuffleDeck { player, deck } => self.submit_shuffled_deck(player, deck),
+            HandEvent::ShuffleDeckWithProof {
+                player,
+                deck,
+                pk,
+                traces,
+            } => self.submit_shuffled_deck_with_proof(player, deck, pk, traces),
+            HandEvent::SmallBlind { player } => self.submit_small_blind(player),
+            HandEvent::BigBlind { player } => self.submit_big_blind(player),
+            HandEvent::UnmaskHoleCards { player, cards } => {
+                self.submit_player_cards(player, cards).map(|_| ())
+            }
+            HandEvent::UnmaskHoleCardsWithProofs {
+                player,
+                cards,
+                pk,
+                proofs,
+            } => self
+                .submit_player_cards_with_proofs(player, cards, pk, proofs)
+                .map(|_| ()),
+            HandEvent::UnmaskCommunityCards {
+                player,
+                round,
+                cards,
+            } => self
+                .submit_community_cards(player, round, cards)
+                .map(|_| ()),
+            HandEvent::UnmaskCommunityCardsWithProofs {
+                player,
+                round,
+                cards,
+                pk,
+                proofs,
+            } => self
+                .submit_community_cards_with_proofs(player, round, cards, pk, proofs)
+                .map(|_| ()),
+            HandEvent::UnmaskShowdown { player, cards } => {
+                self.submit_player_cards_showdown(player, cards).map(|_| ())
+            }
+            HandEvent::Muck { player } => self.submit_muck(player).map(|_| ()),
+            HandEvent::Bet { player, amount } => self.submit_bet(player, amount),
+            HandEvent::Call { player } => self.submit_call(player),
+            HandEvent::Action { player, action } => self.submit_action(player, action),
+            HandEvent::SubmitPublicKey { player, pk, traces } => {
+                self.submit_public_key(player, pk, traces)
+            }
+            HandEvent::VerifyUnmasking => self.verify_unmasking().map(|_| ()),
+            HandEvent::Tick { now } => self.tick(now).map(|_| ()),
+            HandEvent::Configure(config) => self.configure(config),
+            HandEvent::Reset {
+                dealer_button,
+                stacks,
+            } => self.reset_for_next_hand(dealer_button, &stacks),
+        }
+    }
+
+    /// Start or stop recording moves made in the hand
+    pub fn record_events(&mut self, enabled: bool) {
+        if !enabled {
+            self.recorded_events = None;
+        } else if self.recorded_events.is_none() {
+            self.recorded_events = Some(vec![]);
+        }
+    }
+
+    /// Take moves recorded since last call
+    pub fn take_recorded_events(&mut self) -> Vec<HandEvent> {
+        self.recorded_events
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Tell settings of the hand
+    pub fn get_config(&self) -> HandConfig {
+        HandConfig {
+            community_layout: self.community_layout.clone(),
+            short_deck: self.short_deck,
+            run_it_times: self.run_it_times,
+            burn_cards: self.burn_cards,
+            ante: self.ante,
+            big_blind: self.get_big_blind(),
+            showdown_policy: self.showdown_policy,
+            action_timeout: self.action_timeout,
+        }
+    }
+
+    /// Change all settings of the hand at once.
+    ///
+    /// Must be done before the hand starts, as with each of the setters.
+    ///
+    pub fn configure(&mut self, config: HandConfig) -> Result<(), PokerError> {
+        let event = self.recording(|| HandEvent::Configure(config.clone()));
+        self.make_move(event, |hand| {
+            hand.set_community_layout(config.community_layout)?;
+            hand.set_short_deck(config.short_deck)?;
+            hand.enable_run_it_twice(config.run_it_times)?;
+            hand.set_burn_cards(config.burn_cards)?;
+            hand.set_ante(config.ante)?;
+            hand.set_big_blind(config.big_blind)?;
+            hand.set_showdown_policy(config.showdown_policy);
+            hand.action_timeout = config.action_timeout;
+            Ok(())
+        })
+    }
+
+    /// Event to record for the move about to be made, if hand is recording
+    pub(super) fn recording(&self, event: impl FnOnce() -> HandEvent) -> Option<HandEvent> {
+        self.recorded_events.as_ref().map(|_| event())
+    }
+
+    /// Make the move, record it once it is accepted, and start the clock of
+    /// whoever is to move next.
+    ///
+    /// Move exposing a cheater is recorded too, as it changes the state of
+    /// the hand. Moves made from within the move are not recorded, so that
+    /// replaying the move makes them again.
+    ///
+    pub(super) fn make_move<T>(
+        &mut self,
+        event: Option<HandEvent>,
+        submit: impl FnOnce(&mut Self) -> Result<T, PokerError>,
+    ) -> Result<T, PokerError> {
+        let before = self.current_state.to_tuple();
+        let recording = event.and_then(|event| Some((event, self.recorded_events.take()?)));
+
+        let result = submit(self);
+
+        if let Some((event, mut events)) = recording {
+            let (_, _, state_before) = before;
+            if result.is_ok()
+                || (state_before != POKER_HAND_STATE_CHEATED
+                    && self.current_state.current_state == POKER_HAND_STATE_CHEATED)
+            {
+                events.push(event);
+            }
+            self.recorded_events = Some(events);
+        }
+
+        if self.current_state.to_tuple() != before {
+            self.start_clock();
+        }
+
+        result
+    }
+
+    /// Record settings changed by a setter
+    pub(super) fn record_config(&mut self) {
+        let event = self.recording(|| HandEvent::Configure(self.get_config()));
+        self.make_move(event, |_| Ok(())).ok();
+    }
+}
+
+impl PokerTable {
+    /// Start or stop recording table events.
+    ///
+    /// Recording starts with the table configuration and players already
+    /// seated, so it should be enabled before the hand starts. Moves made in
+    /// the hand are recorded by the hand itself, whether made through the
+    /// table or directly on the current hand.
+    ///
+    pub fn record_events(&mut self, enabled: bool) {
+        if let Some(hand) = self.current_hand.as_mut() {
+            hand.record_events(enabled);
+        }
+        if !enabled {
+            self.recorded_events = None;
+            return;
+        }
+        if self.recorded_events.is_some() {
+            return;
+        }
+        let mut events = vec![TableEvent::Created {
+            max_players: self.max_players,
+            max_rounds: self.max_rounds,
+            hole_cards: self.hole_cards,
+        }];
+        if self.max_buy_in.is_some() || self.blind_schedule.is_some() {
+            events.push(TableEvent::Configure {
+                max_buy_in: self.max_buy_in,
+                blind_schedule: self.blind_schedule.clone(),
+            });
+        }
+        events.extend(
+            self.current_players
+                .iter()
+                .map(|&player| TableEvent::Join { player }),
+        );
+        self.recorded_events.replace(events);
+    }
+
+    /// Tell events recorded so far, including moves made in current hand
+    pub fn get_recorded_events(&self) -> Vec<TableEvent> {
+        let Some(events) = self.recorded_events.as_ref() else {
+            return vec![];
+        };
+        let hand_events = self
+            .current_hand
+            .iter()
+            .flat_map(|hand| hand.recorded_events.iter().flatten())
+            .cloned()
+            .map(TableEvent::Hand);
+        events.iter().cloned().chain(hand_events).collect()
+    }
+
+    pub(super) fn record_event(&mut self, event: impl FnOnce() -> TableEvent) {
+        let hand_events = self
+            .current_hand
+            .as_mut()
+            .map(PokerHand::take_recorded_events)
+            .unwrap_or_default();
+        self.record_hand_events(hand_events);
+        if let Some(events) = self.recorded_events.as_mut() {
+            events.push(event());
+        }
+    }
+
+    /// Move events recorded by the hand into table log
+    pub(super) fn record_hand_events(&mut self, hand_events: Vec<HandEvent>) {
+        if let Some(events) = self.recorded_events.as_mut() {
+            events.extend(hand_events.into_iter().map(TableEvent::Hand));
+        }
+    }
+
+    /// Apply table event, recording it if recording is enabled
+    pub fn apply_event(&mut self, event: TableEvent) -> Result<(), PokerError> {
+        match event {
+            TableEvent::Created { .. } => Err(PokerError::InvalidEventLog),
+            TableEvent::Join { player } => self.join(player),
+            TableEvent::Leave { player } => self.leave(player),
+            TableEvent::Rebuy { player, amount } => self.rebuy(player, amount),
+            TableEvent::StartHand {
+                initial_chips,
+                small_blind,
+            } => self.start_hand(initial_chips, small_blind),
+            TableEvent::StartHandAtLevel {
+                initial_chips,
+                level,
+            } => self.start_hand_at_level(initial_chips, &level),
+            TableEvent::Configure {
+                max_buy_in,
+                blind_schedule,
+            } => {
+                self.configure(max_buy_in, blind_schedule);
+                Ok(())
+            }
+            TableEvent::AdvanceLevel => {
+                self.advance_level();
+                Ok(())
+            }
+            TableEvent::TickBlinds { now } => {
+                self.tick_blinds(now);
+                Ok(())
+            }
+            TableEvent::EndHand => self.end_hand(),
+            TableEvent::Hand(hand_event) => {
+                let Some(hand) = self.current_hand.as_mut() else {
+                    return Err(PokerError::NoActiveHand);
+                };
+                hand.apply_event(hand_event)
+            }
+        }
+    }
+
+    /// Reconstruct table by replaying recorded events.
+    ///
+    /// First event must be `TableEvent::Created`, as produced by `record_events()`.
+    /// Move that exposed a cheater is replayed with the same outcome.
+    ///
+    pub fn replay_events(events: &[TableEvent]) -> Result<PokerTable, PokerError> {
+        let Some((
+            TableEvent::Created {
+                max_players,
+                max_rounds,
+                hole_cards,
+            },
+            rest,
+        )) = events.split_first()
+        else {
+            return Err(PokerError::InvalidEventLog);
+        };
+
+        let mut table = PokerTable::with_hole_cards(*max_players, *max_rounds, *hole_cards);
+        table.record_events(true);
+
+        for event in rest {
+            if let Err(err) = table.apply_event(event.clone()) {
+                let is_cheated = matches!(event, TableEvent::Hand(_))
+                    && table.current_hand.as_ref().is_some_and(|hand| {
+                        hand.current_state.current_state == POKER_HAND_STATE_CHEATED
+                    });
+                if !is_cheated {
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(table)
+    }
+}
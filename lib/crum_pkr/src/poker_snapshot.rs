@@ -0,0 +1,299 @@
+//! Crumble (CRyptographic gaMBLE)
+//! 
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//! 
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use bls12_381::{G2Affine, G2Prepared};
+use crum_bls::{types::PublicKey, verify};
+
+use crate::{
+    poker_bets::PokerBettingState,
+    poker_deck::{MaskedCards, PokerDeck, UnmaskedCards},
+    poker_error::PokerError,
+    poker_hand::{ActionRecord, PokerHand, ShowdownPolicy},
+    poker_state::{
+        POKER_HAND_STATE_CHEATED, POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS,
+        POKER_HAND_STATE_UNMASK_HOLE_CARDS, POKER_HAND_STATE_UNMASK_SHOWDOWN, PokerHandState,
+    },
+};
+
+/// Checkpoint of everything needed to carry on with the hand.
+///
+/// Public keys are kept in their 96-byte compressed form. Events not yet
+/// taken and outcome of finished hand are not part of the snapshot, and the
+/// clock of the player to act restarts on restore.
+///
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandSnapshot {
+    pub short_deck: bool,
+    pub shuffled_deck: MaskedCards,
+    pub shuffle_history: Vec<MaskedCards>,
+    pub shuffled: Vec<bool>,
+    pub player_cards: Vec<UnmaskedCards>,
+    pub player_keys: Vec<Option<Vec<u8>>>,
+    pub proof_keys: Vec<Option<Vec<u8>>>,
+    /// Shuffle traces of each player as (after index, claimed before index)
+    pub shuffle_traces: Vec<Option<Vec<(usize, usize)>>>,
+    pub community_cards: Vec<UnmaskedCards>,
+    pub unmasking_sequence: Vec<(usize, u8, Vec<UnmaskedCards>)>,
+    pub current_state: PokerHandState,
+    pub betting_state: PokerBettingState,
+    pub small_blind: u64,
+    pub ante: u64,
+    pub action_timeout: Option<u64>,
+    pub hole_cards: usize,
+    pub community_layout: Vec<usize>,
+    pub run_it_times: usize,
+    pub board_runs: usize,
+    pub burn_cards: bool,
+    pub burned_cards: UnmaskedCards,
+    pub showdown_policy: ShowdownPolicy,
+    pub mucked_players: Vec<bool>,
+    pub action_log: Vec<ActionRecord>,
+}
+
+fn key_to_bytes(key: &Option<PublicKey>) -> Option<Vec<u8>> {
+    key.map(|pk| pk.to_compressed().to_vec())
+}
+
+fn key_from_bytes(bytes: Option<Vec<u8>>) -> Result<Option<PublicKey>, PokerError> {
+    let Some(bytes) = bytes else {
+        return Ok(None);
+    };
+    let bytes: [u8; 96] = bytes.try_into().map_err(|_| PokerError::InvalidSnapshot)?;
+    G2Affine::from_compressed(&bytes)
+        .into_option()
+        .map(Some)
+        .ok_or(PokerError::InvalidSnapshot)
+}
+
+impl PokerHand {
+    /// Capture state of the hand, so that it can be restored later
+    pub fn snapshot(&self) -> HandSnapshot {
+        HandSnapshot {
+            short_deck: self.short_deck,
+            shuffled_deck: self.shuffled_deck.clone(),
+            shuffle_history: self.shuffle_history.clone(),
+            shuffled: self.shuffled.clone(),
+            player_cards: self.player_cards.clone(),
+            player_keys: self.player_keys.iter().map(key_to_bytes).collect(),
+            proof_keys: self.proof_keys.iter().map(key_to_bytes).collect(),
+            shuffle_traces: self
+                .shuffle_traces
+                .iter()
+                .map(|traces| {
+                    traces.as_ref().map(|traces| {
+                        traces
+                            .iter()
+                            .map(|trace| (trace.after_index, trace.claimed_before_index))
+                            .collect()
+                    })
+                })
+                .collect(),
+            community_cards: self.community_cards.clone(),
+            unmasking_sequence: self.unmasking_sequence.clone(),
+            current_state: self.current_state.clone(),
+            betting_state: self.betting_state.clone(),
+            small_blind: self.small_blind,
+            ante: self.ante,
+            action_timeout: self.action_timeout,
+            hole_cards: self.hole_cards,
+            community_layout: self.community_layout.clone(),
+            run_it_times: self.run_it_times,
+            board_runs: self.board_runs,
+            burn_cards: self.burn_cards,
+            burned_cards: self.burned_cards.clone(),
+            showdown_policy: self.showdown_policy,
+            mucked_players: self.mucked_players.clone(),
+            action_log: self.action_log.clone(),
+        }
+    }
+
+    /// Carry on with the hand from snapshot.
+    ///
+    /// Snapshot may come from untrusted storage, so it is checked to describe
+    /// a hand this engine could have reached.
+    ///
+    pub fn restore(snapshot: HandSnapshot) -> Result<Self, PokerError> {
+        let state = &snapshot.current_state;
+        let num_players = state.num_players;
+        let max_rounds = state.max_rounds;
+
+        let state_is_valid = num_players > 0
+            && state.dealer_button < num_players
+            && state.current_player < num_players
+            && state.current_round <= max_rounds
+            && state.current_state <= POKER_HAND_STATE_CHEATED
+            && snapshot.run_it_times > 0
+            && (snapshot.board_runs == 1 || snapshot.board_runs == snapshot.run_it_times);
+
+        let lengths_agree = snapshot.shuffled.len() == num_players
+            && snapshot.player_cards.len() == num_players
+            && snapshot.player_keys.len() == num_players
+            && snapshot.proof_keys.len() == num_players
+            && snapshot.shuffle_traces.len() == num_players
+            && snapshot.mucked_players.len() == num_players
+            && snapshot.community_cards.len() == max_rounds
+            && snapshot.community_layout.len() == max_rounds
+            && snapshot.betting_state.is_consistent(num_players);
+
+        let is_unmask_state = |state_type: u8| {
+            matches!(
+                state_type,
+                POKER_HAND_STATE_UNMASK_HOLE_CARDS
+                    | POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS
+                    | POKER_HAND_STATE_UNMASK_SHOWDOWN
+            )
+        };
+        let hole_cards = snapshot.hole_cards;
+        let is_hole_cards = |cards: &UnmaskedCards| cards.is_empty() || cards.len() == hole_cards;
+        let entry_is_valid =
+            |(player, state_type, submitted_cards): &(usize, u8, Vec<UnmaskedCards>)| {
+                let cards_are_valid = if *state_type == POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS {
+                    submitted_cards.len() == 1
+                } else {
+                    submitted_cards.len() == num_players
+                        && submitted_cards
+                            .iter()
+                            .all(|cards| cards.len() == hole_cards)
+                };
+                *player < num_players && is_unmask_state(*state_type) && cards_are_valid
+            };
+        let sequence_is_valid = snapshot.unmasking_sequence.iter().all(entry_is_valid);
+        let community_unmasks = snapshot
+            .unmasking_sequence
+            .iter()
+            .filter(|(_, state_type, _)| *state_type == POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS)
+            .count();
+
+        if !state_is_valid
+            || !lengths_agree
+            || !sequence_is_valid
+            || community_unmasks > num_players * max_rounds
+        {
+            return Err(PokerError::InvalidSnapshot);
+        }
+
+        // Cards must fit the deck the hand is played with
+        let poker_deck = if snapshot.short_deck {
+            PokerDeck::new_short_deck()
+        } else {
+            PokerDeck::new()
+        };
+        let deck_len = poker_deck.len();
+        let num_shuffled = snapshot
+            .shuffled
+            .iter()
+            .filter(|&&shuffled| shuffled)
+            .count();
+
+        let deck_is_valid = snapshot.shuffled_deck.len() <= deck_len
+            && snapshot.shuffle_history.len() == num_shuffled
+            && snapshot
+                .shuffle_history
+                .iter()
+                .all(|cards| cards.len() == deck_len);
+
+        let cards_are_valid = (1..=deck_len).contains(&hole_cards)
+            && snapshot.player_cards.iter().all(is_hole_cards)
+            && snapshot
+                .community_layout
+                .iter()
+                .all(|&count| count <= deck_len);
+
+        if !deck_is_valid || !cards_are_valid {
+            return Err(PokerError::InvalidSnapshot);
+        }
+
+        let player_keys = snapshot
+            .player_keys
+            .into_iter()
+            .map(key_from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let proof_keys = snapshot
+            .proof_keys
+            .into_iter()
+            .map(key_from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Traces out of range only fail verification, so they are kept as is
+        let shuffle_traces = snapshot
+            .shuffle_traces
+            .into_iter()
+            .map(|traces| {
+                traces.map(|traces| {
+                    traces
+                        .into_iter()
+                        .map(|(after_index, claimed_before_index)| verify::ShuffleTrace {
+                            after_index,
+                            claimed_before_index,
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+
+        let prepared_keys = player_keys
+            .iter()
+            .map(|pk| pk.map(G2Prepared::from))
+            .collect();
+
+        let hand = Self {
+            poker_deck,
+            short_deck: snapshot.short_deck,
+            shuffled_deck: snapshot.shuffled_deck,
+            shuffle_history: snapshot.shuffle_history,
+            shuffled: snapshot.shuffled,
+            player_cards: snapshot.player_cards,
+            player_keys,
+            prepared_keys,
+            proof_keys,
+            shuffle_traces,
+            community_cards: snapshot.community_cards,
+            unmasking_sequence: snapshot.unmasking_sequence,
+            current_state: snapshot.current_state,
+            betting_state: snapshot.betting_state,
+            small_blind: snapshot.small_blind,
+            ante: snapshot.ante,
+            action_timeout: snapshot.action_timeout,
+            hole_cards: snapshot.hole_cards,
+            community_layout: snapshot.community_layout,
+            run_it_times: snapshot.run_it_times,
+            board_runs: snapshot.board_runs,
+            burn_cards: snapshot.burn_cards,
+            burned_cards: snapshot.burned_cards,
+            showdown_policy: snapshot.showdown_policy,
+            mucked_players: snapshot.mucked_players,
+            action_log: snapshot.action_log,
+            outcome: None,
+            deadline: None,
+            last_tick: None,
+            events: vec![],
+            recorded_events: None,
+        };
+
+        // Community cards of each street must fit their place in the layout
+        let community_is_valid = hand
+            .community_cards
+            .iter()
+            .enumerate()
+            .all(|(index, cards)| {
+                let count = hand.community_count(index);
+                if count == 0 {
+                    cards.is_empty()
+                } else {
+                    cards.len() % count == 0 && cards.len() <= count * hand.board_runs
+                }
+            });
+
+        if !community_is_valid || hand.get_num_cards_needed() > deck_len {
+            return Err(PokerError::InvalidSnapshot);
+        }
+
+        Ok(hand)
+    }
+}
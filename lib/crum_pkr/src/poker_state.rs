@@ -5,6 +5,8 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
+use crate::poker_error::PokerError;
+
 pub const POKER_HAND_STATE_SHUFFLE: u8 = 0;
 pub const POKER_HAND_STATE_SMALL_BLIND: u8 = 1;
 pub const POKER_HAND_STATE_BIG_BLIND: u8 = 2;
@@ -21,6 +23,34 @@ pub const POKER_HOLDEM_FLOP: usize = 1;
 pub const POKER_HOLDEM_TURN: usize = 2;
 pub const POKER_HOLDEM_RIVER: usize = 3;
 pub const POKER_HOLDEM_ROUNDS: usize = 4;
+pub const POKER_HOLDEM_HOLE_CARDS: usize = 2;
+pub const POKER_HOLDEM_COMMUNITY_CARDS: usize = 5;
+pub const POKER_OMAHA_HOLE_CARDS: usize = 4;
+
+/// Community cards dealt before each betting round: Flop, Turn and River
+pub const POKER_HOLDEM_COMMUNITY_LAYOUT: [usize; POKER_HOLDEM_ROUNDS] = [0, 3, 1, 1];
+
+/// Name of the betting round in Texas Hold'em
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// Tell street of given betting round, rounds past the river count as river
+    pub const fn from_round(round: usize) -> Self {
+        match round {
+            POKER_HOLDEM_PREFLOP => Street::Preflop,
+            POKER_HOLDEM_FLOP => Street::Flop,
+            POKER_HOLDEM_TURN => Street::Turn,
+            _ => Street::River,
+        }
+    }
+}
 
 pub enum PokerHandStateEnum {
     Shuffle { player: usize, is_dealer: bool },
@@ -36,6 +66,47 @@ pub enum PokerHandStateEnum {
     Invalid,
 }
 
+impl std::fmt::Display for PokerHandStateEnum {
+    /// Compact form for logs, e.g. "Bet(round=1, player=0)"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PokerHandStateEnum::Shuffle { player, is_dealer } => {
+                if *is_dealer {
+                    write!(f, "Shuffle(player={}, dealer)", player)
+                } else {
+                    write!(f, "Shuffle(player={})", player)
+                }
+            }
+            PokerHandStateEnum::SmallBlind { player } => write!(f, "SmallBlind(player={})", player),
+            PokerHandStateEnum::BigBlind { player } => write!(f, "BigBlind(player={})", player),
+            PokerHandStateEnum::Bet { round, player } => {
+                write!(f, "Bet(round={}, player={})", round, player)
+            }
+            PokerHandStateEnum::UnmaskHoleCards { player } => {
+                write!(f, "UnmaskHoleCards(player={})", player)
+            }
+            PokerHandStateEnum::UnmaskCommunityCards { round, player } => {
+                write!(
+                    f,
+                    "UnmaskCommunityCards(round={}, player={})",
+                    round, player
+                )
+            }
+            PokerHandStateEnum::UnmaskShowdown { player } => {
+                write!(f, "UnmaskShowdown(player={})", player)
+            }
+            PokerHandStateEnum::SubmitPublicKey { player } => {
+                write!(f, "SubmitPublicKey(player={})", player)
+            }
+            PokerHandStateEnum::Cheated { player } => write!(f, "Cheated(player={})", player),
+            PokerHandStateEnum::Finished => write!(f, "Finished"),
+            PokerHandStateEnum::Invalid => write!(f, "Invalid"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PokerHandState {
     pub(super) dealer_button: usize,
     pub(super) num_players: usize,
@@ -73,6 +144,41 @@ impl PokerHandState {
         self.current_player
     }
 
+    /// Tell who posts small blind; in heads-up it is the dealer
+    pub const fn small_blind_player(&self) -> usize {
+        if self.num_players == 2 {
+            self.dealer_button
+        } else {
+            (self.dealer_button + 1) % self.num_players
+        }
+    }
+
+    /// Tell who posts big blind
+    pub const fn big_blind_player(&self) -> usize {
+        (self.small_blind_player() + 1) % self.num_players
+    }
+
+    /// Tell who acts first in betting round
+    ///
+    /// Preflop action starts left of the big blind (UTG), which in heads-up is
+    /// the dealer. On later streets action starts left of the button.
+    pub const fn first_to_act(&self, round: usize) -> usize {
+        if round == POKER_HOLDEM_PREFLOP {
+            (self.big_blind_player() + 1) % self.num_players
+        } else {
+            (self.dealer_button + 1) % self.num_players
+        }
+    }
+
+    /// Move to the first player still in the hand to act in current round
+    pub fn start_betting_round(&mut self, mask: &[bool]) -> bool {
+        self.current_player = self.first_to_act(self.current_round);
+        if mask[self.current_player] {
+            return false;
+        }
+        self.next_player_masked(mask, false)
+    }
+
     pub fn next_dealer(&mut self) {
         self.current_player = self.dealer_button;
     }
@@ -82,7 +188,12 @@ impl PokerHandState {
         self.current_player == self.dealer_button
     }
 
-    pub fn next_player_masked(&mut self, mask: &Vec<bool>, from_dealer: bool) -> bool {
+    /// Tell whether current player is the last one before dealer's turn again
+    pub fn is_last_player(&self) -> bool {
+        (self.current_player + 1) % self.num_players == self.dealer_button
+    }
+
+    pub fn next_player_masked(&mut self, mask: &[bool], from_dealer: bool) -> bool {
         if from_dealer {
             self.next_dealer();
             if mask[self.current_player] {
@@ -101,11 +212,11 @@ impl PokerHandState {
         }
     }
 
-    pub fn next_round(&mut self) -> Result<bool, Vec<u8>> {
+    pub fn next_round(&mut self) -> Result<bool, PokerError> {
         let next_round = self.current_round + 1;
 
         if next_round > self.max_rounds {
-            return Err(b"No next round - Hand has finished")?;
+            return Err(PokerError::HandFinished);
         }
 
         self.current_round = next_round;
@@ -121,6 +232,12 @@ impl PokerHandState {
         (self.current_round, self.current_player, self.current_state)
     }
 
+    /// State type, round and player, for compact logging
+    pub const fn status_code(&self) -> (u8, usize, usize) {
+        let (round, player, state) = self.to_tuple();
+        (state, round, player)
+    }
+
     pub const fn to_enum(&self) -> PokerHandStateEnum {
         match self.current_state {
             POKER_HAND_STATE_SHUFFLE => PokerHandStateEnum::Shuffle {
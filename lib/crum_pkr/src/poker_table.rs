@@ -5,37 +5,169 @@
 //! 
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
-use crate::poker_hand::PokerHand;
+use crate::{
+    poker_blinds::{BlindLevel, BlindSchedule, LevelLength},
+    poker_error::PokerError,
+    poker_hand::{HandOutcome, PokerHand},
+    poker_replay::TableEvent,
+    poker_state::POKER_HOLDEM_HOLE_CARDS,
+};
 
 pub struct PokerTable {
-    max_players: usize,
-    max_rounds: usize,
-    current_players: Vec<u32>,
-    dealer_button: usize,
-    current_hand: Option<PokerHand>,
+    pub(super) max_players: usize,
+    pub(super) max_rounds: usize,
+    pub(super) hole_cards: usize,
+    pub(super) current_players: Vec<u32>,
+    /// Chips carried over between hands, none until player is dealt in
+    pub(super) player_stacks: Vec<Option<u64>>,
+    /// Chips bought after hand finished, added to stack once hand is settled
+    pub(super) pending_rebuys: Vec<u64>,
+    /// Largest stack player may top up to by re-buying
+    pub(super) max_buy_in: Option<u64>,
+    /// Players who lost all their chips, in order of elimination
+    pub(super) eliminated_players: Vec<u32>,
+    pub(super) dealer_button: usize,
+    /// Blinds rising over hands played at the table
+    pub(super) blind_schedule: Option<BlindSchedule>,
+    pub(super) current_hand: Option<PokerHand>,
+    /// Outcome of the last hand settled, kept once next hand starts
+    pub(super) last_hand_outcome: Option<HandOutcome>,
+    pub(super) recorded_events: Option<Vec<TableEvent>>,
 }
 
 impl PokerTable {
     /// Player 1 creates a table
     pub fn new(max_players: usize, max_rounds: usize) -> Self {
+        Self::with_hole_cards(max_players, max_rounds, POKER_HOLDEM_HOLE_CARDS)
+    }
+
+    /// Player 1 creates a table dealing given number of hole cards, e.g. Omaha
+    pub fn with_hole_cards(max_players: usize, max_rounds: usize, hole_cards: usize) -> Self {
         Self {
             max_players,
             max_rounds,
+            hole_cards,
             current_players: vec![],
+            player_stacks: vec![],
+            pending_rebuys: vec![],
+            max_buy_in: None,
+            eliminated_players: vec![],
             dealer_button: 0,
+            blind_schedule: None,
             current_hand: None,
+            last_hand_outcome: None,
+            recorded_events: None,
         }
     }
 
     /// Player 1, 2 (3,4,...) joins a table
-    pub fn join(&mut self, player: u32) {
-        // check player already joined
+    pub fn join(&mut self, player: u32) -> Result<(), PokerError> {
+        if self.current_players.len() >= self.max_players {
+            return Err(PokerError::TableFull);
+        }
+
+        if self.current_players.contains(&player) {
+            return Err(PokerError::AlreadyJoined);
+        }
+
         self.current_players.push(player);
+        self.player_stacks.push(None);
+        self.pending_rebuys.push(0);
         // emit player joined
+
+        self.record_event(|| TableEvent::Join { player });
+
+        Ok(())
+    }
+
+    /// Player leaves the table between hands
+    pub fn leave(&mut self, player: u32) -> Result<(), PokerError> {
+        let Some(seat) = self.current_players.iter().position(|&p| p == player) else {
+            return Err(PokerError::InvalidPlayer);
+        };
+
+        if let Some(hand) = &self.current_hand {
+            if !hand.get_current_state().is_finished() {
+                if seat < hand.get_num_players() {
+                    return Err(PokerError::HandInProgress);
+                }
+            } else if let Some(hand) = self.current_hand.take() {
+                // Settle chips before seats shift
+                self.settle_hand(hand);
+            }
+        }
+
+        let Some(seat) = self.current_players.iter().position(|&p| p == player) else {
+            // Player was eliminated in the hand just settled
+            return Ok(());
+        };
+
+        self.current_players.remove(seat);
+        self.player_stacks.remove(seat);
+        self.pending_rebuys.remove(seat);
+
+        // Keep the button on the same player
+        if seat < self.dealer_button {
+            self.dealer_button -= 1;
+        }
+        if self.dealer_button >= self.current_players.len() {
+            self.dealer_button = 0;
+        }
+
+        // emit player left
+
+        self.record_event(|| TableEvent::Leave { player });
+
+        Ok(())
     }
 
     /// Player 1 starts new hand (at their discretion) with players at the table
-    pub fn start_hand(&mut self, initial_chips: u64, small_blind: u64) -> Result<(), Vec<u8>> {
+    ///
+    /// Players keep their chips from previous hands, and the button moves on
+    /// to the next player with chips. Players joining buy in with `initial_chips`.
+    /// When the table has blind schedule, its blinds are used instead of
+    /// `small_blind`.
+    ///
+    pub fn start_hand(&mut self, initial_chips: u64, small_blind: u64) -> Result<(), PokerError> {
+        let level = match self.blind_schedule.as_mut() {
+            Some(schedule) => *schedule.next_hand_level(),
+            None => BlindLevel::new(small_blind, small_blind * 2, 0, LevelLength::Hands(1)),
+        };
+        self.deal_hand(initial_chips, &level)?;
+
+        if let Some(schedule) = self.blind_schedule.as_mut() {
+            schedule.hand_started();
+        }
+
+        // emit hand started
+
+        self.record_event(|| TableEvent::StartHand {
+            initial_chips,
+            small_blind,
+        });
+
+        Ok(())
+    }
+
+    /// Same as `start_hand`, but with blinds and ante of given level
+    pub fn start_hand_at_level(
+        &mut self,
+        initial_chips: u64,
+        level: &BlindLevel,
+    ) -> Result<(), PokerError> {
+        self.deal_hand(initial_chips, level)?;
+
+        // emit hand started
+
+        self.record_event(|| TableEvent::StartHandAtLevel {
+            initial_chips,
+            level: *level,
+        });
+
+        Ok(())
+    }
+
+    fn deal_hand(&mut self, initial_chips: u64, level: &BlindLevel) -> Result<(), PokerError> {
         // check player 1 is submitter
         // check hand in progress
 
@@ -44,27 +176,250 @@ impl PokerTable {
             .as_ref()
             .is_none_or(|h| h.get_current_state().is_finished())
         {
-            return Err(b"Hand in progress")?;
+            return Err(PokerError::HandInProgress);
+        }
+
+        if let Some(hand) = self.current_hand.take() {
+            self.settle_hand(hand);
         }
 
-        self.current_hand.replace(PokerHand::new(
-            self.current_players.len(),
+        if self.current_players.len() < 2 {
+            return Err(PokerError::NotEnoughPlayers);
+        }
+
+        let player_chips = self
+            .player_stacks
+            .iter_mut()
+            .map(|stack| *stack.get_or_insert(initial_chips))
+            .collect();
+
+        let mut hand = PokerHand::from_stacks(
+            player_chips,
             self.max_rounds,
             self.dealer_button,
-            initial_chips,
-            small_blind,
-        ));
+            level.small_blind,
+            self.hole_cards,
+        )?;
+        hand.set_big_blind(level.big_blind)?;
+        hand.set_ante(level.ante)?;
 
-        // emit hand started
+        hand.record_events(self.recorded_events.is_some());
+        self.current_hand.replace(hand);
+
+        Ok(())
+    }
+
+    /// Raise blinds over hands played at the table following the schedule
+    pub fn set_blind_schedule(&mut self, blind_schedule: BlindSchedule) {
+        self.configure(self.max_buy_in, Some(blind_schedule));
+    }
+
+    pub const fn get_blind_schedule(&self) -> Option<&BlindSchedule> {
+        self.blind_schedule.as_ref()
+    }
+
+    /// Tell blinds of the level being played, if the table has blind schedule
+    pub fn current_level(&self) -> Option<&BlindLevel> {
+        self.blind_schedule
+            .as_ref()
+            .map(BlindSchedule::current_level)
+    }
+
+    /// Move on to next blind level; false when there is no level left
+    pub fn advance_level(&mut self) -> bool {
+        let Some(schedule) = self.blind_schedule.as_mut() else {
+            return false;
+        };
+        let advanced = schedule.advance_level();
+
+        self.record_event(|| TableEvent::AdvanceLevel);
+
+        advanced
+    }
+
+    /// Let time pass for levels lasting given duration; true if level advanced
+    pub fn tick_blinds(&mut self, now: u64) -> bool {
+        let Some(schedule) = self.blind_schedule.as_mut() else {
+            return false;
+        };
+        let advanced = schedule.tick(now);
+
+        self.record_event(|| TableEvent::TickBlinds { now });
+
+        advanced
+    }
+
+    /// Settle finished hand without starting the next one, so that players
+    /// who lost all their chips are gone from the table
+    pub fn end_hand(&mut self) -> Result<(), PokerError> {
+        if self
+            .current_hand
+            .as_ref()
+            .is_some_and(|hand| !hand.get_current_state().is_finished())
+        {
+            return Err(PokerError::HandInProgress);
+        }
+
+        if let Some(hand) = self.current_hand.take() {
+            self.settle_hand(hand);
+        }
+
+        self.record_event(|| TableEvent::EndHand);
+
+        Ok(())
+    }
+
+    /// Carry chips over from finished hand, move the button, and remove
+    /// players who lost all their chips.
+    fn settle_hand(&mut self, mut hand: PokerHand) {
+        self.record_hand_events(hand.take_recorded_events());
+
+        if let Some(outcome) = hand.outcome() {
+            self.last_hand_outcome = Some(outcome.clone());
+        }
+
+        for player in 0..hand.get_num_players() {
+            let rebuy = std::mem::take(&mut self.pending_rebuys[player]);
+            self.player_stacks[player] = Some(hand.get_chips_remaining(player) + rebuy);
+        }
+
+        let num_players = self.current_players.len();
+        if let Some(dealer_button) = (1..=num_players)
+            .map(|seat| (self.dealer_button + seat) % num_players)
+            .find(|&seat| self.player_stacks[seat] != Some(0))
+        {
+            self.dealer_button = dealer_button;
+        }
+
+        let busted: Vec<usize> = (0..num_players)
+            .filter(|&seat| self.player_stacks[seat] == Some(0))
+            .collect();
+
+        // Keep the button on the same player once busted players are removed
+        self.dealer_button -= busted
+            .iter()
+            .filter(|&&seat| seat < self.dealer_button)
+            .count();
+
+        let mut eliminated = vec![];
+        for seat in busted.into_iter().rev() {
+            self.player_stacks.remove(seat);
+            self.pending_rebuys.remove(seat);
+            eliminated.push(self.current_players.remove(seat));
+        }
+        eliminated.reverse();
+
+        if self.dealer_button >= self.current_players.len() {
+            self.dealer_button = 0;
+        }
+
+        // emit players eliminated
+
+        self.eliminated_players.extend(eliminated);
+    }
+
+    /// Limit stack players may top up to by re-buying
+    pub fn set_max_buy_in(&mut self, max_buy_in: u64) {
+        self.configure(Some(max_buy_in), self.blind_schedule.clone());
+    }
+
+    /// Set buy-in limit and blind schedule of the table
+    pub(super) fn configure(
+        &mut self,
+        max_buy_in: Option<u64>,
+        blind_schedule: Option<BlindSchedule>,
+    ) {
+        self.max_buy_in = max_buy_in;
+        self.blind_schedule = blind_schedule.clone();
+
+        self.record_event(|| TableEvent::Configure {
+            max_buy_in,
+            blind_schedule,
+        });
+    }
+
+    pub const fn get_max_buy_in(&self) -> Option<u64> {
+        self.max_buy_in
+    }
+
+    /// Player tops up their stack between hands.
+    ///
+    /// Player who lost all their chips in the hand just finished stays at the
+    /// table if they re-buy before next hand starts. Player not yet dealt in
+    /// buys in with this amount instead of the table's initial chips.
+    ///
+    pub fn rebuy(&mut self, player: u32, amount: u64) -> Result<(), PokerError> {
+        let Some(seat) = self.current_players.iter().position(|&p| p == player) else {
+            return Err(PokerError::InvalidPlayer);
+        };
+
+        let is_settled = match &self.current_hand {
+            Some(hand) if !hand.get_current_state().is_finished() => {
+                return Err(PokerError::HandInProgress);
+            }
+            Some(hand) => seat >= hand.get_num_players(),
+            None => true,
+        };
+
+        let stack = self.seat_chips(seat) + self.pending_rebuys[seat] + amount;
+        if self.max_buy_in.is_some_and(|max_buy_in| stack > max_buy_in) {
+            return Err(PokerError::ExceedsMaxBuyIn);
+        }
+
+        if is_settled {
+            self.player_stacks[seat] = Some(stack);
+        } else {
+            self.pending_rebuys[seat] += amount;
+        }
+
+        // emit player re-bought
+
+        self.record_event(|| TableEvent::Rebuy { player, amount });
 
         Ok(())
     }
 
+    /// Tell players who lost all their chips, in order of elimination
+    pub fn get_eliminated_players(&self) -> &[u32] {
+        &self.eliminated_players
+    }
+
+    /// Tell whether player lost all their chips and left the table
+    pub fn is_player_eliminated(&self, player: u32) -> bool {
+        self.eliminated_players.contains(&player)
+    }
+
+    /// Tell amount of chips player has at the table, none if not seated
+    pub fn player_chips(&self, player_id: u32) -> Option<u64> {
+        let seat = self.current_players.iter().position(|&p| p == player_id)?;
+        Some(self.seat_chips(seat))
+    }
+
+    /// Tell amount of chips of the player seated at given seat
+    pub(super) fn seat_chips(&self, seat: usize) -> u64 {
+        match &self.current_hand {
+            Some(hand) if seat < hand.get_num_players() => hand.get_chips_remaining(seat),
+            _ => self.player_stacks.get(seat).copied().flatten().unwrap_or(0),
+        }
+    }
+
+    pub const fn get_dealer_button(&self) -> usize {
+        self.dealer_button
+    }
+
     /// Supports gameplay
     pub const fn get_current_hand(&self) -> Option<&PokerHand> {
         self.current_hand.as_ref()
     }
 
+    /// Tell outcome of the last finished hand, even once next hand started
+    pub fn last_outcome(&self) -> Option<&HandOutcome> {
+        self.current_hand
+            .as_ref()
+            .and_then(PokerHand::outcome)
+            .or(self.last_hand_outcome.as_ref())
+    }
+
     /// Supports gameplay
     pub const fn get_current_hand_mut(&mut self) -> Option<&mut PokerHand> {
         self.current_hand.as_mut()
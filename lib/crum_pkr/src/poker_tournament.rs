@@ -0,0 +1,262 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use std::collections::HashMap;
+
+use crate::{
+    poker_blinds::{BlindLevel, BlindSchedule},
+    poker_driver::{HandDriver, PlayerAgent},
+    poker_error::PokerError,
+    poker_state::POKER_HOLDEM_ROUNDS,
+    poker_table::PokerTable,
+};
+
+/// Most players seated at single tournament table
+pub const TOURNAMENT_TABLE_SIZE: usize = 9;
+
+/// Multi-table tournament played until one player has all the chips.
+///
+/// Tables play their hands in turn. Once a hand is finished, busted players
+/// are out, tables that are no longer needed are broken, and players are
+/// moved so that no table has more than one player more than another.
+///
+pub struct Tournament<A: PlayerAgent> {
+    tables: Vec<PokerTable>,
+    driver: HandDriver<A>,
+    starting_chips: u64,
+    /// Blinds rising over hands played at all tables
+    blind_schedule: BlindSchedule,
+    /// Table playing current hand
+    current_table: usize,
+    hands_played: usize,
+    /// Players who lost all their chips, in order of elimination
+    eliminated_players: Vec<u32>,
+}
+
+impl<A: PlayerAgent> Tournament<A> {
+    /// Seat players with their agents at as few tables as they fit, with
+    /// `starting_chips` each
+    pub fn new(
+        players: HashMap<u32, A>,
+        starting_chips: u64,
+        blind_schedule: BlindSchedule,
+    ) -> Result<Self, PokerError> {
+        if players.len() < 2 {
+            return Err(PokerError::NotEnoughPlayers);
+        }
+
+        let mut player_ids: Vec<u32> = players.keys().copied().collect();
+        player_ids.sort_unstable();
+
+        let num_tables = player_ids.len().div_ceil(TOURNAMENT_TABLE_SIZE);
+        let mut tables: Vec<PokerTable> = (0..num_tables)
+            .map(|_| PokerTable::new(TOURNAMENT_TABLE_SIZE, POKER_HOLDEM_ROUNDS))
+            .collect();
+
+        for (index, player) in player_ids.into_iter().enumerate() {
+            seat_player(&mut tables[index % num_tables], player, starting_chips)?;
+        }
+
+        Ok(Self {
+            tables,
+            driver: HandDriver::new(players),
+            starting_chips,
+            blind_schedule,
+            current_table: 0,
+            hands_played: 0,
+            eliminated_players: vec![],
+        })
+    }
+
+    /// Make next move at the table playing current hand, starting the hand
+    /// if it is not started yet.
+    ///
+    /// Returns true once the tournament is finished.
+    ///
+    pub fn step(&mut self) -> Result<bool, PokerError> {
+        if self.is_finished() {
+            return Ok(true);
+        }
+
+        let table = &mut self.tables[self.current_table];
+
+        if table.get_current_hand().is_none() {
+            table
+                .start_hand_at_level(self.starting_chips, self.blind_schedule.next_hand_level())?;
+            self.blind_schedule.hand_started();
+            return Ok(false);
+        }
+
+        if self.driver.step(table)? {
+            self.finish_hand()?;
+        }
+
+        Ok(self.is_finished())
+    }
+
+    /// Play until one player is left
+    pub fn run(&mut self) -> Result<(), PokerError> {
+        while !self.step()? {}
+        Ok(())
+    }
+
+    /// Settle finished hand, and get tables ready for next hand
+    fn finish_hand(&mut self) -> Result<(), PokerError> {
+        let table = &mut self.tables[self.current_table];
+        let num_eliminated = table.get_eliminated_players().len();
+        table.end_hand()?;
+
+        self.eliminated_players
+            .extend_from_slice(&table.get_eliminated_players()[num_eliminated..]);
+        self.hands_played += 1;
+
+        self.break_tables()?;
+        self.balance_tables()?;
+
+        self.current_table = (self.current_table + 1) % self.tables.len();
+        Ok(())
+    }
+
+    /// Break tables with fewest players while rest of players fit at fewer
+    /// tables
+    fn break_tables(&mut self) -> Result<(), PokerError> {
+        let tables_needed = self.players_left().div_ceil(TOURNAMENT_TABLE_SIZE).max(1);
+
+        while self.tables.len() > tables_needed {
+            let Some(index) = (0..self.tables.len())
+                .min_by_key(|&index| self.tables[index].get_current_player_count())
+            else {
+                break;
+            };
+
+            let table = self.tables.remove(index);
+            if index < self.current_table {
+                self.current_table -= 1;
+            }
+
+            for seat in 0..table.get_current_player_count() {
+                let Some(player) = table.get_player(seat) else {
+                    continue;
+                };
+                let target = self.smallest_table();
+                seat_player(&mut self.tables[target], player, table.seat_chips(seat))?;
+            }
+        }
+
+        if self.current_table >= self.tables.len() {
+            self.current_table = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Move players from the fullest table to the shortest one until they
+    /// differ by at most one player
+    fn balance_tables(&mut self) -> Result<(), PokerError> {
+        loop {
+            let source = self.largest_table();
+            let target = self.smallest_table();
+
+            let source_count = self.tables[source].get_current_player_count();
+            let target_count = self.tables[target].get_current_player_count();
+            if source_count <= target_count + 1 {
+                return Ok(());
+            }
+
+            // Player in the last seat moves
+            let table = &mut self.tables[source];
+            let seat = source_count - 1;
+            let Some(player) = table.get_player(seat) else {
+                return Err(PokerError::InvalidPlayer);
+            };
+            let chips = table.seat_chips(seat);
+            table.leave(player)?;
+
+            seat_player(&mut self.tables[target], player, chips)?;
+        }
+    }
+
+    fn smallest_table(&self) -> usize {
+        (0..self.tables.len())
+            .min_by_key(|&index| self.tables[index].get_current_player_count())
+            .unwrap_or(0)
+    }
+
+    fn largest_table(&self) -> usize {
+        (0..self.tables.len())
+            .max_by_key(|&index| self.tables[index].get_current_player_count())
+            .unwrap_or(0)
+    }
+
+    /// Tell blinds of the level being played
+    pub fn current_level(&self) -> &BlindLevel {
+        self.blind_schedule.current_level()
+    }
+
+    /// Move on to next blind level, e.g. once level time is over
+    pub fn advance_level(&mut self) -> bool {
+        self.blind_schedule.advance_level()
+    }
+
+    /// Tell number of players who still have chips
+    pub fn players_left(&self) -> usize {
+        self.tables
+            .iter()
+            .map(PokerTable::get_current_player_count)
+            .sum()
+    }
+
+    /// Tell whether single player is left with all the chips
+    pub fn is_finished(&self) -> bool {
+        self.players_left() <= 1
+    }
+
+    /// Tell number of hands finished at all tables
+    pub const fn get_hands_played(&self) -> usize {
+        self.hands_played
+    }
+
+    pub fn get_tables(&self) -> &[PokerTable] {
+        &self.tables
+    }
+
+    /// Tell players who lost all their chips, in order of elimination
+    pub fn get_eliminated_players(&self) -> &[u32] {
+        &self.eliminated_players
+    }
+
+    /// Players with their chips from first place down.
+    ///
+    /// Players still in are ordered by their chips, followed by eliminated
+    /// players, who finish in reverse order of elimination.
+    ///
+    pub fn standings(&self) -> Vec<(u32, u64)> {
+        let mut standings: Vec<(u32, u64)> = self
+            .tables
+            .iter()
+            .flat_map(|table| {
+                (0..table.get_current_player_count())
+                    .filter_map(move |seat| Some((table.get_player(seat)?, table.seat_chips(seat))))
+            })
+            .collect();
+        standings.sort_by_key(|&(player, chips)| (std::cmp::Reverse(chips), player));
+
+        standings.extend(
+            self.eliminated_players
+                .iter()
+                .rev()
+                .map(|&player| (player, 0)),
+        );
+        standings
+    }
+}
+
+/// Player takes seat at the table bringing their chips
+fn seat_player(table: &mut PokerTable, player: u32, chips: u64) -> Result<(), PokerError> {
+    table.join(player)?;
+    table.rebuy(player, chips)
+}
@@ -0,0 +1,126 @@
+//! Crumble (CRyptographic gaMBLE)
+//!
+//! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
+//! Designed by the Sonia Code & Gemini AI (2026)
+//!
+//! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
+
+use crum_bls::types::{SecretKey, SigningKey};
+
+use crate::{
+    poker_deck::{PokerCard, UnmaskedCards},
+    poker_error::PokerError,
+    poker_hand::PokerHand,
+    poker_state::PokerHandState,
+};
+
+/// What a spectator may see of the hand.
+///
+/// Cards are only shown once fully unmasked, and card points are never
+/// included, so that masked cards cannot be matched against the deck.
+///
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PublicHandView {
+    pub current_state: PokerHandState,
+    pub pot: u64,
+    /// Chips each player put in during current betting round
+    pub bets: Vec<u64>,
+    pub chips: Vec<u64>,
+    pub active_players: Vec<bool>,
+    /// Community cards revealed so far, in order of dealing
+    pub community_cards: Vec<PokerCard>,
+    /// Hole cards of each player, once shown at showdown
+    pub face_up_cards: Vec<Option<Vec<PokerCard>>>,
+}
+
+/// What a player may see of the cards, using their own key.
+///
+/// Cards still masked by anyone are None. Opponents' hole cards are never
+/// included, as player can only see them once shown at showdown, same as
+/// anyone else.
+///
+#[derive(Clone, Debug)]
+pub struct PlayerView {
+    pub player: usize,
+    /// Own hole cards, once every other player removed their mask
+    pub hole_cards: Vec<Option<PokerCard>>,
+    /// Community cards dealt so far, in order of dealing
+    pub community_cards: Vec<Option<PokerCard>>,
+}
+
+impl PokerHand {
+    /// Public state of the hand for observers
+    pub fn public_view(&self) -> PublicHandView {
+        let num_players = self.current_state.num_players;
+
+        PublicHandView {
+            current_state: self.current_state.clone(),
+            pot: self.get_pot(),
+            bets: (0..num_players)
+                .map(|player| self.get_player_bet(player))
+                .collect(),
+            chips: (0..num_players)
+                .map(|player| self.get_chips_remaining(player))
+                .collect(),
+            active_players: self.betting_state.get_active_players().clone(),
+            community_cards: self
+                .community_cards
+                .iter()
+                .filter_map(|cards| self.revealed(cards))
+                .flatten()
+                .collect(),
+            face_up_cards: self
+                .player_cards
+                .iter()
+                .map(|cards| self.revealed(cards))
+                .collect(),
+        }
+    }
+
+    /// Cards player can see using their own key
+    pub fn visible_cards_for(&self, player: usize, sk: SigningKey) -> PlayerView {
+        self.view_unmasked_by(player, |cards| cards.unmask(sk))
+    }
+
+    /// Same as `visible_cards_for`, but without copying the secret
+    pub fn visible_cards_for_with(&self, player: usize, sk: &SecretKey) -> PlayerView {
+        self.view_unmasked_by(player, |cards| cards.unmask_with(sk))
+    }
+
+    fn view_unmasked_by(
+        &self,
+        player: usize,
+        unmask: impl FnOnce(&mut UnmaskedCards) -> Result<(), PokerError>,
+    ) -> PlayerView {
+        let hole_cards = match self.player_cards.get(player) {
+            Some(cards) => {
+                let mut cards = cards.clone();
+                match unmask(&mut cards) {
+                    Ok(()) => self.poker_deck.unmasked_cards(&cards),
+                    Err(_) => vec![None; cards.len()],
+                }
+            }
+            None => vec![],
+        };
+
+        PlayerView {
+            player,
+            hole_cards,
+            community_cards: self
+                .community_cards
+                .iter()
+                .take(self.current_state.current_round)
+                .flat_map(|cards| self.poker_deck.unmasked_cards(cards))
+                .collect(),
+        }
+    }
+
+    /// Cards dealt, if they have all been fully unmasked
+    fn revealed(&self, cards: &UnmaskedCards) -> Option<Vec<PokerCard>> {
+        if cards.is_empty() {
+            return None;
+        }
+        self.poker_deck.unmasked_cards(cards).into_iter().collect()
+    }
+}
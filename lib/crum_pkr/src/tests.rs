@@ -1,232 +1,4450 @@
 //! Crumble (CRyptographic gaMBLE)
-//! 
+//!
 //! Mental Poker (1979) implemented using Boneh–Lynn–Shacham (BLS) cryptography.
 //! Designed by the Sonia Code & Gemini AI (2026)
-//! 
+//!
 //! Copyright (c) 2026 Sonia Code; See LICENSE file for license details.
 
-use crate::{poker_state::{POKER_HOLDEM_ROUNDS, PokerHandStateEnum}, poker_table::PokerTable};
+use crate::{
+    poker_bets::{PokerAction, PokerBettingState, Pot},
+    poker_blinds::{BlindLevel, BlindSchedule, LevelLength},
+    poker_deck::{MaskedCards, UnmaskedCards},
+    poker_driver::{HandDriver, PlayerAgent},
+    poker_error::PokerError,
+    poker_event::PokerEvent,
+    poker_hand::{ActionRecord, HandEnding, HandOutcome, PokerHand, ShowdownPolicy},
+    poker_hand_rank::{HandRank, best_hand, rank_hand, rank_hand_with, rank_omaha_hand},
+    poker_odds::{estimate_strength, estimate_strength_with_rng},
+    poker_replay::{HandConfig, HandEvent, TableEvent},
+    poker_snapshot::HandSnapshot,
+    poker_state::{
+        POKER_HAND_STATE_FINISHED, POKER_HAND_STATE_SHUFFLE,
+        POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS, POKER_HAND_STATE_UNMASK_HOLE_CARDS,
+        POKER_HAND_STATE_UNMASK_SHOWDOWN, POKER_HOLDEM_FLOP, POKER_HOLDEM_HOLE_CARDS,
+        POKER_HOLDEM_PREFLOP, POKER_HOLDEM_RIVER, POKER_HOLDEM_ROUNDS, POKER_HOLDEM_TURN,
+        POKER_OMAHA_HOLE_CARDS, PokerHandStateEnum, Street,
+    },
+    poker_table::PokerTable,
+    poker_tournament::{TOURNAMENT_TABLE_SIZE, Tournament},
+};
 
-use super::poker_deck::PokerDeck;
-use bls12_381::Scalar;
+use super::poker_deck::{CardSet, ParseCardError, PokerCard, PokerDeck, Rank, Suit};
+use bls12_381::{G1Affine, G2Affine, G2Prepared, G2Projective, Scalar};
 use crum_bls::{
-    hash_to_curve::hash_to_curve, lagrange, sign, util::make_public_key_from_signing_key, verify,
+    hash_to_curve::hash_to_curve,
+    lagrange,
+    proof::UnmaskProof,
+    referee::{self, RefereeVerdict},
+    sign,
+    types::{PublicKey, SecretKey},
+    util::{
+        PUBLIC_KEY_COMPRESSED_LEN, SIGNATURE_COMPRESSED_LEN, SIGNING_KEY_LEN,
+        make_public_key_from_compressed_slice, make_public_key_from_signing_key,
+        make_signature_from_compressed_slice, public_key_to_bytes, signature_to_bytes,
+        signing_key_from_bytes, signing_key_to_bytes, validate_signing_key,
+    },
+    verify,
 };
 use ff::Field;
 use itertools::Itertools;
 use pairing::group::Curve;
+use rand::{SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha20Rng;
+use std::cmp::Ordering;
 
-#[test]
-fn test_lifecycle() {
-    // --- 1. SETUP ---
+fn make_signing_keys(num_players: usize) -> Vec<Scalar> {
     let mut rng = rand::thread_rng();
+    (0..num_players).map(|_| Scalar::random(&mut rng)).collect()
+}
 
-    // Player A (Dealer) and Player B (Player)
-    let sk_a = Scalar::random(&mut rng);
-    let sk_b = Scalar::random(&mut rng);
+/// Shuffle traces of each player, kept until they reveal their key
+type ShuffleTraces = Vec<Option<Vec<verify::ShuffleTrace>>>;
+
+/// Keys of the players, their shuffle traces, and Hold'em hand between them
+/// with 100 chips each and small blind of 10
+fn holdem_hand(num_players: usize, dealer: usize) -> (Vec<Scalar>, ShuffleTraces, PokerHand) {
+    let hand = PokerHand::new(
+        num_players,
+        POKER_HOLDEM_ROUNDS,
+        dealer,
+        100,
+        10,
+        POKER_HOLDEM_HOLE_CARDS,
+    )
+    .unwrap();
+    (
+        make_signing_keys(num_players),
+        vec![None; num_players],
+        hand,
+    )
+}
 
-    // Public Keys in G2 for the Miller Loop "Audit"
-    let pk_a_g2 = make_public_key_from_signing_key(&sk_a);
-    let pk_b_g2 = make_public_key_from_signing_key(&sk_b);
+/// Makes action of the player whose turn it is.
+///
+/// Bets are always check or call, which keeps all players in the hand.
+fn next_hand_event(
+    hand: &PokerHand,
+    sks: &[Scalar],
+    traces: &mut [Option<Vec<verify::ShuffleTrace>>],
+) -> HandEvent {
+    let mut rng = rand::thread_rng();
 
-    // The "Ace of Spades" base point
-    let card_base = hash_to_curve(b"AS").to_affine();
+    match hand.get_current_state().to_enum() {
+        PokerHandStateEnum::Shuffle { player, is_dealer } => {
+            let mut deck = if is_dealer {
+                hand.get_poker_deck().masked_cards()
+            } else {
+                hand.get_shuffled_deck().clone()
+            };
+            deck.mask(sks[player]);
+            traces[player].replace(deck.shuffle_traced(&mut rng));
+            HandEvent::ShuffleDeck { player, deck }
+        }
+        PokerHandStateEnum::SmallBlind { player } => HandEvent::SmallBlind { player },
+        PokerHandStateEnum::BigBlind { player } => HandEvent::BigBlind { player },
+        PokerHandStateEnum::Bet { round: _, player } => {
+            let amount = hand.get_call_amount_required(player).unwrap();
+            HandEvent::Bet { player, amount }
+        }
+        PokerHandStateEnum::UnmaskHoleCards { player } => {
+            let mut cards = hand.get_player_cards().clone();
+            for (i, c) in cards.iter_mut().enumerate() {
+                if i != player {
+                    c.unmask(sks[player]).unwrap();
+                }
+            }
+            HandEvent::UnmaskHoleCards { player, cards }
+        }
+        PokerHandStateEnum::UnmaskCommunityCards { round, player } => {
+            let mut cards = hand.get_community_cards(round).unwrap().clone();
+            cards.unmask(sks[player]).unwrap();
+            HandEvent::UnmaskCommunityCards {
+                player,
+                round,
+                cards,
+            }
+        }
+        PokerHandStateEnum::UnmaskShowdown { player } => {
+            let mut cards = hand.get_player_cards().clone();
+            cards[player].unmask(sks[player]).unwrap();
+            HandEvent::UnmaskShowdown { player, cards }
+        }
+        PokerHandStateEnum::SubmitPublicKey { player } => HandEvent::SubmitPublicKey {
+            player,
+            pk: make_public_key_from_signing_key(&sks[player]),
+            traces: traces[player].take().unwrap(),
+        },
+        PokerHandStateEnum::Finished
+        | PokerHandStateEnum::Cheated { player: _ }
+        | PokerHandStateEnum::Invalid => panic!("No action possible"),
+    }
+}
 
-    // --- 2. SHUFFLE (Commutative Masking) ---
-    // Player A masks the card
-    let masked_a = sign::mask(card_base, sk_a);
+/// Performs single action on behalf of the player whose turn it is.
+fn step_hand(
+    hand: &mut PokerHand,
+    sks: &[Scalar],
+    traces: &mut [Option<Vec<verify::ShuffleTrace>>],
+) {
+    let event = next_hand_event(hand, sks, traces);
+    hand.apply_event(event).unwrap();
+}
 
-    // Player B masks Player A's result
-    let masked_b = sign::mask(masked_a, sk_b);
-    // At this point, masked_b = Card * sk_a * sk_b
+/// Steps through the hand until predicate is satisfied by current state.
+fn play_hand_until(
+    hand: &mut PokerHand,
+    sks: &[Scalar],
+    traces: &mut [Option<Vec<verify::ShuffleTrace>>],
+    until: impl Fn(&PokerHandStateEnum) -> bool,
+) {
+    while !until(&hand.get_current_state().to_enum()) {
+        step_hand(hand, sks, traces);
+    }
+}
 
-    // --- 3. BETTING (Threshold Consensus) ---
-    let bet_message = b"Player B bets 10 USDC";
+/// Steps through the hand until it is finished.
+fn play_hand_to_end(
+    hand: &mut PokerHand,
+    sks: &[Scalar],
+    traces: &mut [Option<Vec<verify::ShuffleTrace>>],
+) {
+    play_hand_until(hand, sks, traces, |s| {
+        matches!(s, PokerHandStateEnum::Finished)
+    });
+}
 
-    // Both players sign the bet to reach consensus
-    let sig_a = sign::sign(bet_message, sk_a);
-    let sig_b = sign::sign(bet_message, sk_b);
+/// Makes fully unmasked cards from codes such as "As"
+fn unmasked_cards_of(codes: &[&str]) -> UnmaskedCards {
+    UnmaskedCards::new(
+        codes
+            .iter()
+            .map(|code| hash_to_curve(code.as_bytes()).to_affine())
+            .collect(),
+    )
+}
 
-    // Combine into a threshold signature (2-of-2)
-    // For this test, we treat them as IDs 1 and 2
-    let shares = vec![(1, sig_a), (2, sig_b)];
-    let combined_bet_sig = lagrange::combine(&shares).expect("Failed to combine signatures");
+/// Makes a hand with hole and community cards already revealed
+fn revealed_hand(hole_cards: &[&[&str]], community_cards: &[&[&str]]) -> PokerHand {
+    let mut hand = PokerHand::new(
+        hole_cards.len(),
+        POKER_HOLDEM_ROUNDS,
+        0,
+        100,
+        10,
+        hole_cards[0].len(),
+    )
+    .unwrap();
+    hand.player_cards = hole_cards.iter().map(|c| unmasked_cards_of(c)).collect();
+    for (round, cards) in community_cards.iter().enumerate() {
+        hand.community_cards[round] = unmasked_cards_of(cards);
+    }
+    hand
+}
 
-    let pub_shares = vec![(1, pk_a_g2), (2, pk_b_g2)];
-    let master_pk = lagrange::recover(&pub_shares).expect("Failed to recover master public key");
+/// Unmasks cards with signing key and proves each peel
+fn unmask_with_proofs(sk: &Scalar, before: &UnmaskedCards) -> (UnmaskedCards, Vec<UnmaskProof>) {
+    let sk = SecretKey::new(*sk);
+    let mut after = before.clone();
+    after.unmask_with(&sk).unwrap();
 
-    // Verify that bet message was signed by all participants
+    let proofs = before
+        .as_slice()
+        .iter()
+        .zip(after.as_slice())
+        .map(|(masked, unmasked)| sk.prove_unmask(masked, unmasked, rand::thread_rng()))
+        .collect();
+
+    (after, proofs)
+}
+
+// Cards and deck
+
+#[test]
+fn test_cards_accessors() {
+    let poker_deck = PokerDeck::new();
+    let mut masked_deck = poker_deck.masked_cards();
+
+    assert_eq!(masked_deck.as_slice(), poker_deck.as_slice());
+    assert_eq!(masked_deck.as_slice(), masked_deck.cards().as_slice());
+
+    let dealt = masked_deck.deal(2).unwrap();
+
+    assert_eq!(dealt.as_slice(), &poker_deck.as_slice()[..2]);
+    assert_eq!(dealt.as_slice(), dealt.cards().as_slice());
+    assert_eq!(masked_deck.as_slice(), &poker_deck.as_slice()[2..]);
+}
+
+#[test]
+fn test_card_index() {
+    let poker_deck = PokerDeck::new();
+
+    for (index, card) in poker_deck.poker_cards().iter().enumerate() {
+        assert_eq!(card.to_index() as usize, index);
+        assert_eq!(PokerCard::from_index(index as u8), Some(*card));
+        assert_eq!(poker_deck.card_at(index), Some(*card));
+    }
+    assert_eq!(PokerCard::from_index(0), Some("2s".parse().unwrap()));
+    assert_eq!(PokerCard::from_index(51), Some("Ac".parse().unwrap()));
+    assert_eq!(PokerCard::from_index(52), None);
+    assert_eq!(PokerCard::from_index(u8::MAX), None);
+    assert_eq!(poker_deck.card_at(52), None);
+}
+
+#[test]
+fn test_deck_verify() {
+    let poker_deck = PokerDeck::new();
+    assert!(poker_deck.verify());
     assert!(
-        verify::verify(bet_message, &master_pk, &combined_bet_sig),
-        "Failed to verify bet message"
+        PokerDeck::new_with_ranks(b"6789TJQKA", b"shdc")
+            .unwrap()
+            .verify()
     );
 
-    // --- 4. DEALING (The Stateless Audit) ---
-    // To reveal the card to Player B, Player A must first "peel" their layer
-    let unmasked_by_a = sign::unmask(masked_b, sk_a);
+    // Base point of one card replaced with another point
+    let mut tampered = poker_deck.clone();
+    tampered.cards_g1[5] = hash_to_curve(b"Xx").to_affine();
+    assert!(!tampered.verify());
+
+    // Base points of two cards swapped
+    let mut tampered = poker_deck.clone();
+    tampered.cards_g1.swap(0, 1);
+    assert!(!tampered.verify());
+
+    // Cards reordered together with their base points, but not the index
+    let mut tampered = poker_deck.clone();
+    tampered.cards_g1.swap(0, 1);
+    tampered.poker_cards.swap(0, 1);
+    assert!(!tampered.verify());
+
+    let mut tampered = poker_deck;
+    tampered.cards_g1.pop();
+    assert!(!tampered.verify());
+}
 
-    // The Referee (Stylus Contract) verifies Player A was honest
-    // It checks: e(Unmasked, G2) == e(Masked, PK_A)
-    let audit_passed = verify::verify_unmasking(masked_b, unmasked_by_a, pk_a_g2);
-    assert!(audit_passed, "Player A's unmasking audit failed!");
+#[test]
+fn test_cards_equality() {
+    let poker_deck = PokerDeck::new();
+    let sk = make_signing_keys(1)[0];
+
+    let mut deck_a = poker_deck.masked_cards();
+    deck_a.mask(sk);
+    let mut deck_b = deck_a.clone();
+    assert_eq!(deck_a, deck_b);
+
+    // Same key, but different shuffles
+    deck_a.shuffle_seeded([1; 32]);
+    deck_b.shuffle_seeded([2; 32]);
+    assert_ne!(deck_a, deck_b);
+    assert_eq!(deck_a, deck_a.clone());
+
+    let mut dealt_a = deck_a.deal(5).unwrap();
+    let dealt_b = deck_b.deal(5).unwrap();
+    assert_ne!(dealt_a, dealt_b);
+    assert_eq!(dealt_a, dealt_a.clone());
+
+    // Unmasking changes the cards
+    let masked = dealt_a.clone();
+    dealt_a.unmask(sk).unwrap();
+    assert_ne!(dealt_a, masked);
+}
 
-    // Finally, Player B peels their own layer to see the card
-    let final_card = sign::unmask(unmasked_by_a, sk_b);
+#[cfg(not(feature = "fancy_cards"))]
+#[test]
+fn test_card_set_display() {
+    let cards = [
+        Some("As".parse().unwrap()),
+        None,
+        Some("Td".parse().unwrap()),
+    ];
+    assert_eq!(CardSet(&cards).to_string(), "As, _!, Td");
+    assert_eq!(CardSet(&[]).to_string(), "");
+}
 
-    // Verification: The final point should be the original Ace of Spades
-    assert_eq!(final_card, card_base, "The final card point is corrupted!");
+#[cfg(feature = "fancy_cards")]
+#[test]
+fn test_card_set_display_fancy() {
+    let cards = [
+        Some("As".parse().unwrap()),
+        None,
+        Some("Td".parse().unwrap()),
+    ];
+    assert_eq!(CardSet(&cards).to_string(), "🂡, 🂠, 🃊");
+
+    let glyphs: String = ["Ks", "Qh", "Jd", "2c", "7h", "Ac"]
+        .iter()
+        .map(|code| code.parse::<PokerCard>().unwrap().to_glyph())
+        .collect();
+    assert_eq!(glyphs, "🂮🂽🃋🃒🂷🃑");
+
+    // Every card has its own glyph
+    let deck = PokerDeck::new();
+    assert_eq!(
+        deck.poker_cards()
+            .iter()
+            .map(PokerCard::to_glyph)
+            .unique()
+            .count(),
+        52
+    );
+}
 
-    println!("Sovereign Deal Complete: Bet signed and card audited successfully.");
+#[test]
+fn test_poker_card_round_trip() {
+    let deck = PokerDeck::new();
+    assert_eq!(deck.poker_cards().len(), 52);
+
+    for (card, card_g1) in deck.poker_cards().iter().zip(deck.as_slice()) {
+        let code = card.to_string();
+        let parsed: PokerCard = code.parse().unwrap();
+
+        assert_eq!(parsed.rank(), card.rank());
+        assert_eq!(parsed.suit(), card.suit());
+        assert_eq!(parsed.to_string(), code);
+
+        // Base point is still hashed from the two-character code
+        assert_eq!(code.as_bytes(), parsed.to_bytes());
+        assert_eq!(hash_to_curve(code.as_bytes()).to_affine(), *card_g1);
+    }
+
+    let ace: PokerCard = "As".parse().unwrap();
+    assert_eq!(ace.rank(), Rank::Ace);
+    assert_eq!(ace.rank().value(), 14);
+    assert_eq!(ace.suit(), Suit::Spades);
+
+    assert!("Xs".parse::<PokerCard>().is_err());
 }
 
 #[test]
-fn test_poker() {
+fn test_poker_card_order() {
     let mut rng = rand::thread_rng();
+    let mut cards = PokerDeck::new().poker_cards().to_vec();
+    cards.shuffle(&mut rng);
+    cards.sort();
+
+    // Deuces first and Aces last, suits in deck order within each rank
+    assert_eq!(cards, PokerDeck::new().poker_cards());
+    assert_eq!(cards[0].to_string(), "2s");
+    assert_eq!(cards[51].to_string(), "Ac");
+    assert!(
+        cards
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.rank() <= b.rank())
+    );
 
-    let sk_1 = Scalar::random(&mut rng);
-    let sk_2 = Scalar::random(&mut rng);
+    let ace: PokerCard = "Ah".parse().unwrap();
+    let king: PokerCard = "Ks".parse().unwrap();
+    assert!(ace > king);
+    assert!(Rank::Ace > Rank::King);
+    assert!("Ts".parse::<PokerCard>().unwrap() < "Th".parse::<PokerCard>().unwrap());
+}
 
-    // Player 1 table create (Heads-up/Hold-em)
-    // Player 2 join
+#[test]
+fn test_parse_poker_card() {
+    for (code, rank, suit) in [
+        ("Td", Rank::Ten, Suit::Diamonds),
+        ("As", Rank::Ace, Suit::Spades),
+        ("2c", Rank::Two, Suit::Clubs),
+        ("Qh", Rank::Queen, Suit::Hearts),
+    ] {
+        assert_eq!(code.parse(), Ok(PokerCard::new(rank, suit)));
+    }
 
-    // Player 1 turn (shuffle)
+    for (code, err) in [
+        ("", ParseCardError::WrongLength),
+        ("A", ParseCardError::WrongLength),
+        ("Asd", ParseCardError::WrongLength),
+        ("10s", ParseCardError::WrongLength),
+        ("1s", ParseCardError::InvalidRank('1')),
+        ("as", ParseCardError::InvalidRank('a')),
+        ("Xs", ParseCardError::InvalidRank('X')),
+        ("AS", ParseCardError::InvalidSuit('S')),
+        ("Ax", ParseCardError::InvalidSuit('x')),
+        ("A♠", ParseCardError::InvalidSuit('♠')),
+    ] {
+        assert_eq!(code.parse::<PokerCard>(), Err(err), "{}", code);
+    }
 
-    // Player 1 starts with fresh deck of cards...
+    assert_eq!(
+        ParseCardError::InvalidRank('X').to_string(),
+        "Invalid card rank 'X'"
+    );
+    assert_eq!(
+        PokerError::from(ParseCardError::WrongLength),
+        PokerError::InvalidCard
+    );
+}
+
+#[test]
+fn test_short_deck() {
+    let poker_deck = PokerDeck::new_with_ranks(b"6789TJQKA", b"shdc").unwrap();
+    assert_eq!(poker_deck.len(), 36);
+    assert_eq!(poker_deck.poker_cards().iter().unique().count(), 36);
+    assert_eq!(poker_deck.card_at(0), Some("6s".parse().unwrap()));
+    assert_eq!(poker_deck.card_at(35), Some("Ac".parse().unwrap()));
+
+    // Same base points as in full deck
+    let full_deck = PokerDeck::new();
+    for (card, point) in poker_deck.poker_cards().iter().zip(poker_deck.as_slice()) {
+        assert!(card.rank() >= Rank::Six);
+        assert_eq!(full_deck.find_card(*point), Some(*card));
+        assert_eq!(poker_deck.find_card(*point), Some(*card));
+    }
+    assert_eq!(
+        poker_deck.find_card(full_deck.as_slice()[0]),
+        None,
+        "Deuce is not in short deck"
+    );
+
+    assert_eq!(
+        PokerDeck::new_with_ranks(b"6789TJQKA1", b"shdc").err(),
+        Some(ParseCardError::InvalidRank('1'))
+    );
+    assert_eq!(
+        PokerDeck::new_with_ranks(b"6789TJQKA", b"shdx").err(),
+        Some(ParseCardError::InvalidSuit('x'))
+    );
+    assert_eq!(
+        PokerDeck::new_with_ranks(b"6789TJQKA6", b"shdc").err(),
+        Some(ParseCardError::DuplicateRank('6'))
+    );
+    assert_eq!(
+        PokerDeck::new_with_ranks(b"6789TJQKA", b"shds").err(),
+        Some(ParseCardError::DuplicateSuit('s'))
+    );
+    assert_eq!(
+        PokerDeck::new_short_deck().poker_cards(),
+        poker_deck.poker_cards()
+    );
+
+    let cards = |codes: &[&str]| -> Vec<PokerCard> {
+        codes.iter().map(|code| code.parse().unwrap()).collect()
+    };
+
+    // Ace plays low below Six
+    let wheel = cards(&["As", "6h", "7d", "8c", "9s", "Kh", "Kd"]);
+    assert_eq!(
+        rank_hand_with(&wheel, true),
+        Some(HandRank::Straight { high: 9 })
+    );
+    assert_eq!(
+        rank_hand_with(&wheel, false),
+        Some(HandRank::Pair {
+            pair: 13,
+            kickers: [14, 9, 8]
+        })
+    );
+
+    // Flush beats full house
+    let flush = rank_hand_with(&cards(&["Ah", "7h", "8h", "Th", "Jh", "6c", "9d"]), true).unwrap();
+    let full_house =
+        rank_hand_with(&cards(&["Qs", "Qh", "Qd", "7c", "7s", "6c", "9d"]), true).unwrap();
+    assert!(flush < full_house);
+    assert!(flush.cmp_short_deck(&full_house).is_gt());
+    assert!(full_house.cmp_short_deck(&flush).is_lt());
+
+    // Flush is picked over full house out of same seven cards
+    let both = cards(&["Qh", "Qd", "Qs", "7h", "7s", "9h", "Th", "Kh"]);
+    assert!(matches!(rank_hand(&both), Some(HandRank::FullHouse { .. })));
+    assert!(matches!(
+        rank_hand_with(&both, true),
+        Some(HandRank::Flush(_))
+    ));
+}
+
+#[test]
+fn test_find_card_full_reveal() {
     let poker_deck = PokerDeck::new();
-    let mut masked_deck = poker_deck.masked_cards();
+    let all_cards = UnmaskedCards::new(poker_deck.cards());
 
-    // ...masks them and shuffles
-    masked_deck.mask(sk_1);
-    masked_deck.shuffle(&mut rng);
+    let rounds = 100;
+    let start = std::time::Instant::now();
+    for _ in 0..rounds {
+        let revealed = poker_deck.unmasked_cards(&all_cards);
+        assert!(revealed.iter().all(|c| c.is_some()));
+    }
+    println!(
+        "Revealed {} cards {} times in {:?}",
+        poker_deck.len(),
+        rounds,
+        start.elapsed()
+    );
 
-    // Player 1 commits onchain masked/shuffled deck
+    let revealed = poker_deck.unmasked_cards(&all_cards);
+    for (card, expected) in revealed.iter().zip(poker_deck.poker_cards()) {
+        assert_eq!(card.as_ref().unwrap().to_bytes(), expected.to_bytes());
+    }
 
-    // Player 2 turn (shuffle)
+    // Points that are not cards (e.g. still masked) are not found
+    let mut masked_cards = poker_deck.masked_cards();
+    masked_cards.mask(Scalar::random(&mut rand::thread_rng()));
+    assert!(
+        masked_cards
+            .as_slice()
+            .iter()
+            .all(|card_g1| poker_deck.find_card(*card_g1).is_none())
+    );
+}
 
-    // Player 2 repeats masking and shuffling...
-    masked_deck.mask(sk_2);
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_masked_cards() {
+    let mut rng = rand::thread_rng();
+
+    let mut masked_deck = PokerDeck::new().masked_cards();
+    masked_deck.mask(Scalar::random(&mut rng));
     masked_deck.shuffle(&mut rng);
 
-    // Player 2 commits onchain masked/shuffled deck
+    let json = serde_json::to_string(&masked_deck).unwrap();
+    let restored: crate::poker_deck::MaskedCards = serde_json::from_str(&json).unwrap();
 
-    // Now we have shuffled deck of cards that no one knows which card is which
-    // because when player 1 shuffled masked cards, player 2 wouldn't know which
-    // card ended up at which position, and then player 2 also masked and shuffled
-    // and player 1 can no longer tell which card is which, because all cards are
-    // doubly masked by signing key of player 1 and player 2. Cool thing about BLS
-    // is that signing is commutative, so we can unmask later on in any order, which
-    // is critical for Poker game.
-    let deck_hash = masked_deck.hash();
+    assert_eq!(restored.hash(), masked_deck.hash());
 
-    // Both players sign the exact same flat hash
-    let sig_1 = sign::sign(&deck_hash, sk_1);
-    let sig_2 = sign::sign(&deck_hash, sk_2);
+    let dealt = masked_deck.deal(2).unwrap();
+    let json = serde_json::to_string(&dealt).unwrap();
+    let restored: UnmaskedCards = serde_json::from_str(&json).unwrap();
 
-    // Combine into the Master Signature
-    let bls_signature = lagrange::combine(&[(1, sig_1), (2, sig_2)]).expect("Should combine");
+    assert_eq!(restored.as_slice(), dealt.as_slice());
 
-    // Players commit signed deck hash onchain (in case game was fully off-chain)
+    let card = PokerDeck::new().poker_cards()[0];
+    let json = serde_json::to_string(&card).unwrap();
+    let restored: crate::poker_deck::PokerCard = serde_json::from_str(&json).unwrap();
 
-    let pk_1 = make_public_key_from_signing_key(&sk_1);
-    let pk_2 = make_public_key_from_signing_key(&sk_2);
+    assert_eq!(json, "\"2s\"");
+    assert_eq!(restored.to_bytes(), card.to_bytes());
+}
 
-    let master_pk =
-        lagrange::recover(&[(1, pk_1), (2, pk_2)]).expect("Failed to recover master key");
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_rejects_malformed_points() {
+    // Wrong length
+    let json = serde_json::to_string(&vec![vec![0u8; 47]]).unwrap();
+    assert!(serde_json::from_str::<UnmaskedCards>(&json).is_err());
+
+    // Not a point on the curve
+    let json = serde_json::to_string(&vec![vec![0xffu8; 48]]).unwrap();
+    assert!(serde_json::from_str::<UnmaskedCards>(&json).is_err());
+
+    // Non-canonical encoding: compression flag missing
+    let mut bytes = PokerDeck::new().as_slice()[0].to_compressed();
+    bytes[0] &= 0x7f;
+    let json = serde_json::to_string(&vec![bytes.to_vec()]).unwrap();
+    assert!(serde_json::from_str::<crate::poker_deck::MaskedCards>(&json).is_err());
+}
 
-    // We can verify that deck matches the hash signed and committed onchain by players
+#[test]
+fn test_deck_exhausted() {
+    let mut masked_deck = PokerDeck::new().masked_cards();
+    assert_eq!(masked_deck.deal(50).unwrap().len(), 50);
+    assert_eq!(masked_deck.deal(3).unwrap_err(), PokerError::DeckExhausted);
+    assert_eq!(masked_deck.len(), 2);
+
+    // 27 players need 54 hole cards, which is more than the deck has
+    assert_eq!(
+        PokerHand::new(27, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).err(),
+        Some(PokerError::TooManyPlayers)
+    );
 
-    assert!(
-        verify::verify(&deck_hash, &master_pk, &bls_signature),
-        "Failed to verify deck"
+    // Dealing still stops at the end of the deck when hand needs more cards
+    // than checked on creation, i.e. 18 players with three hole cards each
+    let (sks, mut traces, mut hand) = holdem_hand(18, 0);
+    hand.hole_cards = 3;
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::BigBlind { .. })
+    });
+
+    let player = hand.get_big_blind_player();
+    assert_eq!(
+        hand.submit_big_blind(player),
+        Err(PokerError::DeckExhausted)
     );
+    assert_eq!(hand.get_chips_remaining(player), 100);
+}
 
-    // ^^^ (Optional) should whole Poker game happen off-chain
+#[test]
+fn test_deck_commitment() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SmallBlind { .. })
+    });
+
+    // Players commit hash of the deck once everyone has shuffled
+    let commitment = hand.deck_commitment();
+    assert_eq!(commitment, hand.get_shuffled_deck().hash());
+    assert!(hand.verify_deck_commitment(commitment));
+
+    // Dealing cards does not change the commitment
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert!(hand.verify_deck_commitment(commitment));
+
+    // Replacing any single card point changes the commitment
+    let mut cards = hand.shuffle_history.last().unwrap().cards();
+    cards[7] = cards[8];
+    *hand.shuffle_history.last_mut().unwrap() = MaskedCards::new(cards);
+    assert!(!hand.verify_deck_commitment(commitment));
+}
 
-    // Player 1 turn (small blind)
-    // Player 2 turn (big blind)
+// Keys, signatures and shuffle proofs
 
-    // Posting blinds
-    // The players agree off-chain that P1 posts 5 (SB) and P2 posts 10 (BB).
-    let blind_state = b"STATE_1: POT=15, P1=-5, P2=-10";
+#[test]
+fn test_key_bytes() {
+    let mut rng = rand::thread_rng();
+    let sk = Scalar::random(&mut rng);
+    let pk = make_public_key_from_signing_key(&sk);
+    let sig = sign::sign(b"Crumble", sk);
 
-    let blind_sig_1 = sign::sign(blind_state, sk_1);
-    let blind_sig_2 = sign::sign(blind_state, sk_2);
+    let sk_bytes = signing_key_to_bytes(&sk);
+    assert_eq!(sk_bytes.len(), SIGNING_KEY_LEN);
+    assert_eq!(signing_key_from_bytes(&sk_bytes), Ok(sk));
 
-    // They combine signatures to prove they both committed to this pot size
-    let blinds_master_sig = lagrange::combine(&[(1, blind_sig_1), (2, blind_sig_2)])
-        .expect("Failed to combine blind signatures");
+    let pk_bytes = public_key_to_bytes(&pk);
+    assert_eq!(pk_bytes.len(), PUBLIC_KEY_COMPRESSED_LEN);
+    assert_eq!(make_public_key_from_compressed_slice(&pk_bytes), Ok(pk));
 
-    assert!(
-        verify::verify(blind_state, &master_pk, &blinds_master_sig),
-        "Failed to verify blinds consensus"
+    let sig_bytes = signature_to_bytes(&sig);
+    assert_eq!(sig_bytes.len(), SIGNATURE_COMPRESSED_LEN);
+    assert_eq!(make_signature_from_compressed_slice(&sig_bytes), Ok(sig));
+
+    assert_eq!(
+        signing_key_from_bytes(&sk_bytes[..SIGNING_KEY_LEN - 1]),
+        Err("Len Error")
+    );
+
+    // Identity points would pass pairing checks trivially
+    assert_eq!(
+        make_public_key_from_compressed_slice(&G2Affine::identity().to_compressed()),
+        Err("Invalid key: identity")
+    );
+    assert_eq!(
+        make_signature_from_compressed_slice(&G1Affine::identity().to_compressed()),
+        Err("Invalid signature: identity")
+    );
+    // Value not below the group order is not a valid key
+    assert_eq!(
+        signing_key_from_bytes(&[0xff; SIGNING_KEY_LEN]),
+        Err("Decode Error")
+    );
+}
+
+#[test]
+fn test_secret_key() {
+    let mut rng = rand::thread_rng();
+    let sk = SecretKey::random(&mut rng);
+    let signing_key = *sk.expose();
+
+    assert_eq!(
+        sk.public_key(),
+        make_public_key_from_signing_key(&signing_key)
+    );
+    assert_eq!(
+        sk.sign(b"Player B bets 10 USDC"),
+        sign::sign(b"Player B bets 10 USDC", signing_key)
+    );
+
+    let card = PokerDeck::new().as_slice()[0];
+    assert_eq!(sk.mask(card), sign::mask(card, signing_key));
+    assert_eq!(sk.unmask(sk.mask(card)).unwrap(), card);
+    assert_eq!(sk.invert().unwrap().mask(sk.mask(card)), card);
+
+    let mut masked_cards = PokerDeck::new().masked_cards();
+    masked_cards.mask_with(&sk);
+    let mut unmasked_cards = masked_cards.deal(2).unwrap();
+    unmasked_cards.unmask_with(&sk).unwrap();
+    assert_eq!(unmasked_cards.as_slice(), &PokerDeck::new().as_slice()[..2]);
+
+    // Peek at memory where key was stored after it has been dropped
+    let mut sk = std::mem::ManuallyDrop::new(sk);
+    let key_ptr: *const Scalar = sk.expose();
+    assert_eq!(unsafe { key_ptr.read_volatile() }, signing_key);
+
+    unsafe { std::mem::ManuallyDrop::drop(&mut sk) };
+    assert_eq!(unsafe { key_ptr.read_volatile() }, Scalar::ZERO);
+}
+
+#[test]
+fn test_secret_key_redacted() {
+    let sk = SecretKey::random(rand::thread_rng());
+    let bytes = sk.expose().to_bytes();
+    let hex_le: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    let hex_be: String = bytes.iter().rev().map(|b| format!("{b:02x}")).collect();
+
+    for printed in [
+        format!("{sk:?}"),
+        format!("{sk}"),
+        format!("{:?}", Some(&sk)),
+    ] {
+        assert!(printed.contains("SecretKey(***)"));
+        assert!(!printed.contains(&hex_le));
+        assert!(!printed.contains(&hex_be));
+    }
+    assert_eq!(format!("{:?}", sk.expose()), format!("0x{hex_be}"));
+}
+
+#[test]
+fn test_unmask_zero_key() {
+    let card = PokerDeck::new().as_slice()[0];
+    assert_eq!(validate_signing_key(&Scalar::ZERO), Err("Zero Key"));
+    assert_eq!(validate_signing_key(&Scalar::ONE), Ok(()));
+
+    // Malformed key is reported instead of crashing
+    assert_eq!(sign::unmask(card, Scalar::ZERO), Err("Zero Key"));
+    assert_eq!(SecretKey::new(Scalar::ZERO).unmask(card), Err("Zero Key"));
+    assert!(SecretKey::new(Scalar::ZERO).invert().is_err());
+
+    let mut cards = PokerDeck::new().masked_cards().deal(2).unwrap();
+    assert_eq!(
+        cards.unmask(Scalar::ZERO),
+        Err(PokerError::InvalidSigningKey)
+    );
+    assert_eq!(
+        cards.unmask_with(&SecretKey::new(Scalar::ZERO)),
+        Err(PokerError::InvalidSigningKey)
+    );
+    assert_eq!(cards.as_slice(), &PokerDeck::new().as_slice()[..2]);
+
+    let sk = SecretKey::random(rand::thread_rng());
+    assert_eq!(validate_signing_key(sk.expose()), Ok(()));
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn test_hash_to_curve_sha256() {
+    use crum_bls::hash_to_curve::{hash_to_curve_sha256, hash_to_curve_sha256_with_dst};
+
+    // RFC 9380, J.9.1: BLS12381G1_XMD:SHA-256_SSWU_RO_ with msg = ""
+    let point =
+        hash_to_curve_sha256_with_dst(b"", b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_")
+            .to_affine();
+    let uncompressed = point.to_uncompressed();
+    assert_eq!(
+        alloy_primitives::hex::encode(&uncompressed[..48]),
+        "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1"
+    );
+    assert_eq!(
+        alloy_primitives::hex::encode(&uncompressed[48..]),
+        "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265"
+    );
+
+    let message = b"Player B bets 10 USDC";
+    let keccak_point = hash_to_curve(message).to_affine();
+    let sha256_point = hash_to_curve_sha256(message).to_affine();
+
+    for point in [keccak_point, sha256_point] {
+        assert!(bool::from(point.is_on_curve()));
+        assert!(bool::from(point.is_torsion_free()));
+        assert!(!bool::from(point.is_identity()));
+    }
+
+    // Both suites coexist and map message to different points
+    assert_ne!(keccak_point, sha256_point);
+    assert_eq!(sha256_point, hash_to_curve_sha256(message).to_affine());
+
+    // Adapter is plain SHA-256, whether one-shot or reused after reset
+    use crum_bls::hash_to_curve::Sha256Hash;
+    use digest::Digest;
+
+    let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    assert_eq!(
+        alloy_primitives::hex::encode(Sha256Hash::digest(b"abc")),
+        abc
+    );
+    let mut hasher = Sha256Hash::new();
+    hasher.update(b"abc");
+    assert_eq!(alloy_primitives::hex::encode(hasher.finalize_reset()), abc);
+    hasher.update(b"abc");
+    assert_eq!(alloy_primitives::hex::encode(hasher.finalize()), abc);
+}
+
+#[test]
+fn test_aggregate_verify() {
+    let mut rng = rand::thread_rng();
+    let message = b"Player B bets 10 USDC";
+
+    for num_signers in [2, 5] {
+        let sks: Vec<Scalar> = (0..num_signers).map(|_| Scalar::random(&mut rng)).collect();
+        let pks: Vec<_> = sks.iter().map(make_public_key_from_signing_key).collect();
+
+        let sigs: Vec<_> = sks.iter().map(|sk| sign::sign(message, *sk)).collect();
+        let aggregated_sig = sign::aggregate(&sigs);
+
+        assert!(verify::aggregate_verify(message, &pks, &aggregated_sig));
+        assert!(!verify::aggregate_verify(
+            b"Player B bets 20 USDC",
+            &pks,
+            &aggregated_sig
+        ));
+
+        // Signature is not endorsed by a player whose key was swapped
+        let mut wrong_pks = pks.clone();
+        wrong_pks[1] = make_public_key_from_signing_key(&Scalar::random(&mut rng));
+        assert!(!verify::aggregate_verify(
+            message,
+            &wrong_pks,
+            &aggregated_sig
+        ));
+
+        // Signature of one player alone is not enough
+        assert!(!verify::aggregate_verify(
+            message,
+            &pks[..1],
+            &aggregated_sig
+        ));
+    }
+
+    assert!(!verify::aggregate_verify(
+        message,
+        &[],
+        &G1Affine::identity()
+    ));
+}
+
+#[test]
+fn test_aggregate_signatures() {
+    let mut rng = rand::thread_rng();
+    let message = b"Player B bets 10 USDC";
+
+    let sk_a = Scalar::random(&mut rng);
+    let sk_b = Scalar::random(&mut rng);
+    let pk_a = make_public_key_from_signing_key(&sk_a);
+    let pk_b = make_public_key_from_signing_key(&sk_b);
+
+    let sig_a = sign::sign(message, sk_a);
+    let sig_b = sign::sign(message, sk_b);
+    let aggregated_sig = sign::aggregate(&[sig_a, sig_b]);
+
+    // Aggregated signature is the signature by sum of signing keys
+    assert_eq!(aggregated_sig, sign::sign(message, sk_a + sk_b));
+
+    let summed_pk = (G2Projective::from(pk_a) + pk_b).to_affine();
+    assert!(verify::verify(message, &summed_pk, &aggregated_sig));
+    assert!(verify::aggregate_verify(
+        message,
+        &[pk_a, pk_b],
+        &aggregated_sig
+    ));
+    assert!(!verify::verify(message, &pk_a, &aggregated_sig));
+
+    assert_eq!(sign::aggregate(&[sig_a]), sig_a);
+    assert_eq!(sign::aggregate(&[]), G1Affine::identity());
+}
+
+#[test]
+fn test_aggregate_verify_distinct() {
+    let mut rng = rand::thread_rng();
+    let messages: [&[u8]; 3] = [
+        b"Player A bets 10 USDC",
+        b"Player B calls 10 USDC",
+        b"Player C folds",
+    ];
+
+    let sks: Vec<Scalar> = (0..3).map(|_| Scalar::random(&mut rng)).collect();
+    let pks: Vec<_> = sks.iter().map(make_public_key_from_signing_key).collect();
+
+    let sigs: Vec<_> = messages
+        .iter()
+        .zip(&sks)
+        .map(|(message, sk)| sign::sign(message, *sk))
+        .collect();
+    let aggregated_sig = sign::aggregate(&sigs);
+
+    assert!(verify::aggregate_verify_distinct(
+        &messages,
+        &pks,
+        &aggregated_sig
+    ));
+
+    // Player C claims to have called instead
+    let mut tampered = messages;
+    tampered[2] = b"Player C calls 10 USDC";
+    assert!(!verify::aggregate_verify_distinct(
+        &tampered,
+        &pks,
+        &aggregated_sig
+    ));
+
+    // Messages attributed to wrong players
+    let mut swapped_pks = pks.clone();
+    swapped_pks.swap(0, 1);
+    assert!(!verify::aggregate_verify_distinct(
+        &messages,
+        &swapped_pks,
+        &aggregated_sig
+    ));
+
+    // Each message needs its signer
+    assert!(!verify::aggregate_verify_distinct(
+        &messages,
+        &pks[..2],
+        &aggregated_sig
+    ));
+
+    // Same message signed twice is rejected
+    let repeated: [&[u8]; 2] = [messages[0], messages[0]];
+    let repeated_sig = sign::aggregate(&[
+        sign::sign(messages[0], sks[0]),
+        sign::sign(messages[0], sks[1]),
+    ]);
+    assert!(!verify::aggregate_verify_distinct(
+        &repeated,
+        &pks[..2],
+        &repeated_sig
+    ));
+}
+
+#[test]
+fn test_threshold_subsets() {
+    let mut rng = rand::thread_rng();
+    let message = b"Player B bets 10 USDC";
+
+    // Shares of 2-of-3 scheme lie on line f(x) = secret + slope * x
+    let secret = Scalar::random(&mut rng);
+    let slope = Scalar::random(&mut rng);
+    let shares: Vec<(u64, Scalar)> = (1..=3)
+        .map(|label| (label, secret + slope * Scalar::from(label)))
+        .collect();
+
+    let master_pk = make_public_key_from_signing_key(&secret);
+
+    for subset in shares.iter().combinations(2) {
+        let pk_shares: Vec<_> = subset
+            .iter()
+            .map(|(label, sk)| (*label, make_public_key_from_signing_key(sk)))
+            .collect();
+        let sig_shares: Vec<_> = subset
+            .iter()
+            .map(|(label, sk)| (*label, sign::sign(message, *sk)))
+            .collect();
+
+        let recovered_pk = lagrange::recover_subset(&pk_shares, 2).unwrap();
+        assert_eq!(recovered_pk, master_pk);
+
+        let combined_sig = lagrange::combine_subset(&sig_shares, 2).unwrap();
+        assert!(verify::verify(message, &master_pk, &combined_sig));
+
+        // Single share is below threshold
+        assert_eq!(
+            lagrange::combine_subset(&sig_shares[..1], 2),
+            Err("Not enough shares")
+        );
+        assert_eq!(
+            lagrange::recover_subset(&pk_shares[..1], 2),
+            Err("Not enough shares")
+        );
+    }
+
+    let sig = sign::sign(message, shares[0].1);
+    assert_eq!(
+        lagrange::combine(&[(1, sig), (1, sig)]),
+        Err("duplicate or zero label")
+    );
+    assert_eq!(
+        lagrange::combine(&[(0, sig), (1, sig)]),
+        Err("duplicate or zero label")
+    );
+    assert_eq!(
+        lagrange::recover(&[(0, master_pk), (2, master_pk)]),
+        Err("duplicate or zero label")
+    );
+}
+
+#[test]
+fn test_verify_combined() {
+    let mut rng = rand::thread_rng();
+    let sk_1 = Scalar::random(&mut rng);
+    let sk_2 = Scalar::random(&mut rng);
+    let pk_1 = make_public_key_from_signing_key(&sk_1);
+    let pk_2 = make_public_key_from_signing_key(&sk_2);
+
+    // Both players sign state of the pot after posting blinds
+    let blind_state = b"STATE_1: POT=15, P1=-5, P2=-10";
+    let blind_sig_1 = sign::sign(blind_state, sk_1);
+    let blind_sig_2 = sign::sign(blind_state, sk_2);
+
+    let shares = [(1, blind_sig_1), (2, blind_sig_2)];
+    let pub_shares = [(1, pk_1), (2, pk_2)];
+
+    assert!(lagrange::verify_combined(blind_state, &shares, &pub_shares));
+
+    // Same as combining and recovering separately
+    let master_sig = lagrange::combine(&shares).unwrap();
+    let master_pk = lagrange::recover(&pub_shares).unwrap();
+    assert!(verify::verify(blind_state, &master_pk, &master_sig));
+
+    // Different pot
+    assert!(!lagrange::verify_combined(
+        b"STATE_1: POT=20, P1=-10, P2=-10",
+        &shares,
+        &pub_shares
+    ));
+
+    // Player 2 never signed
+    assert!(!lagrange::verify_combined(
+        blind_state,
+        &shares[..1],
+        &pub_shares
+    ));
+    assert!(!lagrange::verify_combined(
+        blind_state,
+        &shares,
+        &pub_shares[..1]
+    ));
+
+    // Labels must match
+    assert!(!lagrange::verify_combined(
+        blind_state,
+        &shares,
+        &[(1, pk_1), (3, pk_2)]
+    ));
+    assert!(!lagrange::verify_combined(blind_state, &[], &[]));
+}
+
+#[test]
+fn test_shuffle_seeded() {
+    let seed = [7u8; 32];
+    let masked_deck = PokerDeck::new().masked_cards();
+
+    let mut deck_a = masked_deck.clone();
+    let mut deck_b = masked_deck.clone();
+    deck_a.shuffle_seeded(seed);
+    deck_b.shuffle_seeded(seed);
+    assert_eq!(deck_a.as_slice(), deck_b.as_slice());
+    assert_ne!(deck_a.as_slice(), masked_deck.as_slice());
+
+    let mut deck_c = masked_deck.clone();
+    deck_c.shuffle_seeded([8u8; 32]);
+    assert_ne!(deck_a.as_slice(), deck_c.as_slice());
+
+    // Traced shuffle yields same order and same traces
+    let mut traced_a = masked_deck.clone();
+    let mut traced_b = masked_deck.clone();
+    let traces_a = traced_a.shuffle_traced_seeded(seed);
+    let traces_b = traced_b.shuffle_traced_seeded(seed);
+    assert_eq!(traced_a.as_slice(), traced_b.as_slice());
+    assert_eq!(traces_a, traces_b);
+
+    for trace in &traces_a {
+        assert_eq!(
+            traced_a.as_slice()[trace.after_index],
+            masked_deck.as_slice()[trace.claimed_before_index]
+        );
+    }
+}
+
+#[test]
+fn test_shuffle_traced_round_trip() {
+    let mut rng = rand::thread_rng();
+    let sk = Scalar::random(&mut rng);
+    let pk = make_public_key_from_signing_key(&sk);
+
+    let poker_deck = PokerDeck::new();
+    let mut masked_deck = poker_deck.masked_cards();
+    masked_deck.mask(sk);
+    let mut traces = masked_deck.shuffle_traced(&mut rng);
+
+    assert_eq!(traces.len(), poker_deck.len());
+    assert!(
+        verify::verify_shuffle_traced(poker_deck.as_slice(), masked_deck.as_slice(), &pk, &traces)
+            .is_ok()
+    );
+
+    // Trace inconsistent with permutation applied must not verify
+    traces.truncate(1);
+    traces[0].claimed_before_index = (traces[0].claimed_before_index + 1) % poker_deck.len();
+
+    assert!(
+        verify::verify_shuffle_traced(poker_deck.as_slice(), masked_deck.as_slice(), &pk, &traces)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_shuffle_trace_bytes() {
+    let mut rng = rand::thread_rng();
+    let sk = Scalar::random(&mut rng);
+    let pk = make_public_key_from_signing_key(&sk);
+
+    let poker_deck = PokerDeck::new();
+    let mut masked_deck = poker_deck.masked_cards();
+    masked_deck.mask(sk);
+    let traces = masked_deck.shuffle_traced(&mut rng);
+
+    // One byte per index
+    let data = verify::encode_traces(&traces).unwrap();
+    assert_eq!(data.len(), poker_deck.len() * verify::SHUFFLE_TRACE_LEN);
+
+    let decoded = verify::decode_traces(&data, poker_deck.len()).unwrap();
+    assert_eq!(decoded, traces);
+    assert!(
+        verify::verify_shuffle_traced(poker_deck.as_slice(), masked_deck.as_slice(), &pk, &decoded)
+            .is_ok()
+    );
+
+    // Truncated input is rejected
+    assert_eq!(
+        verify::decode_traces(&data[..data.len() - 1], poker_deck.len()),
+        Err("Len Error")
+    );
+    assert_eq!(
+        verify::decode_traces(&data[..data.len() - 2], poker_deck.len()),
+        Err("Len Error")
+    );
+    assert_eq!(
+        verify::ShuffleTrace::from_bytes(&data[..1], poker_deck.len()),
+        Err("Len Error")
+    );
+
+    // Indices past the end of the deck are rejected
+    let mut out_of_range = data.clone();
+    out_of_range[1] = poker_deck.len() as u8;
+    assert_eq!(
+        verify::decode_traces(&out_of_range, poker_deck.len()),
+        Err("Index Error")
+    );
+
+    // Index that does not fit a byte cannot be encoded
+    let trace = verify::ShuffleTrace {
+        after_index: 256,
+        claimed_before_index: 0,
+    };
+    assert_eq!(trace.to_bytes(), Err("Index Error"));
+}
+
+#[test]
+fn test_shuffle_traced_bijection() {
+    let mut rng = rand::thread_rng();
+    let sk = Scalar::random(&mut rng);
+    let pk = make_public_key_from_signing_key(&sk);
+
+    let poker_deck = PokerDeck::new();
+    let mut masked_deck = poker_deck.masked_cards();
+    masked_deck.mask(sk);
+    let traces = masked_deck.shuffle_traced(&mut rng);
+
+    let verify_traces = |traces: &[verify::ShuffleTrace]| {
+        verify::verify_shuffle_traced(poker_deck.as_slice(), masked_deck.as_slice(), &pk, traces)
+    };
+
+    // Complete permutation passes
+    assert_eq!(verify_traces(&traces), Ok(()));
+
+    // Cheater leaves one card untraced, e.g. one they replaced
+    let mut missing = traces.clone();
+    missing.pop();
+    assert_eq!(
+        verify_traces(&missing),
+        Err("Trace count must match number of shuffled cards")
+    );
+
+    // Cheater traces same output card twice, leaving another one untraced
+    let mut duplicated = traces.clone();
+    duplicated[1].after_index = duplicated[0].after_index;
+    assert_eq!(
+        verify_traces(&duplicated),
+        Err("Duplicate output index! Cheater attempted to skip a card.")
+    );
+
+    // Cheater traces two output cards to same input card, i.e. cloned it
+    let mut cloned = traces.clone();
+    cloned[1].claimed_before_index = cloned[0].claimed_before_index;
+    assert_eq!(
+        verify_traces(&cloned),
+        Err("Duplicate input index! Cheater attempted to clone a card.")
+    );
+}
+
+#[test]
+fn test_verify_shuffle_prepared() {
+    let mut rng = rand::thread_rng();
+    let sk = Scalar::random(&mut rng);
+    let pk = make_public_key_from_signing_key(&sk);
+    let pk_prepared = G2Prepared::from(pk);
+
+    let poker_deck = PokerDeck::new();
+    let mut masked_deck = poker_deck.masked_cards();
+    masked_deck.mask(sk);
+    let traces = masked_deck.shuffle_traced(&mut rng);
+
+    let before = poker_deck.as_slice();
+    let after = masked_deck.as_slice();
+
+    let verify_both = |after: &[G1Affine]| {
+        let result = verify::verify_shuffle_traced(before, after, &pk, &traces);
+        assert_eq!(
+            result,
+            verify::verify_shuffle_traced_prepared(before, after, &pk_prepared, &traces)
+        );
+        result
+    };
+
+    assert!(verify_both(after).is_ok());
+
+    let mut forged = after.to_vec();
+    forged[0] = before[0];
+    assert!(verify_both(&forged).is_err());
+
+    // Brute-force check on single suit keeps it quick in debug builds
+    assert_eq!(
+        verify::verify_shuffle(&before[..13], &after[..5], &pk),
+        verify::verify_shuffle_prepared(&before[..13], &after[..5], &pk_prepared)
+    );
+
+    // Hand caches prepared keys as they are submitted
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SubmitPublicKey { .. })
+    });
+    let shuffle_traces: Vec<_> = traces.iter().map(|t| t.clone().unwrap()).collect();
+    assert_eq!(
+        hand.verify_shuffle(0, &shuffle_traces[0]),
+        Err(PokerError::MissingPublicKey(0))
+    );
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.prepared_public_keys().unwrap().len(), 3);
+    for (player, traces) in shuffle_traces.iter().enumerate() {
+        assert_eq!(hand.verify_shuffle(player, traces), Ok(true));
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_verify_shuffle_parallel() {
+    let mut rng = rand::thread_rng();
+    let sk = Scalar::random(&mut rng);
+    let pk = make_public_key_from_signing_key(&sk);
+
+    // Single suit keeps brute-force serial check quick in debug builds
+    let poker_deck = PokerDeck::new();
+    let before = &poker_deck.as_slice()[..13];
+    let mut masked_deck = MaskedCards::new(before.to_vec());
+    masked_deck.mask(sk);
+    masked_deck.shuffle(&mut rng);
+
+    let mut after = masked_deck.cards();
+
+    let verify_both = |after: &[G1Affine]| {
+        let serial = verify::verify_shuffle(before, after, &pk);
+        let parallel = verify::verify_shuffle_parallel(before, after, &pk);
+        assert_eq!(serial, parallel);
+        parallel
+    };
+
+    // Known shuffle passes, and so does any subset of it
+    assert_eq!(verify_both(&after), Ok(()));
+    assert_eq!(verify_both(&after[..7]), Ok(()));
+
+    // Cloned card cannot claim same input twice
+    after[1] = after[0];
+    assert!(verify_both(&after).is_err());
+
+    // Card not masked by shuffler does not match any input
+    after[1] = before[0];
+    assert!(verify_both(&after).is_err());
+}
+
+#[test]
+fn test_unmask_proof() {
+    let mut rng = rand::thread_rng();
+    let sk = SecretKey::random(&mut rng);
+    let pk = sk.public_key();
+
+    let card = PokerDeck::new().as_slice()[0];
+    let masked = sk.mask(card);
+    let proof = sk.prove_unmask(&masked, &card, &mut rng);
+
+    assert!(verify::verify_unmask_proof(&masked, &card, &pk, &proof));
+
+    // Proof does not hold for other card, other key or identity key
+    let other_card = PokerDeck::new().as_slice()[1];
+    let other_pk = SecretKey::random(&mut rng).public_key();
+    assert!(!verify::verify_unmask_proof(
+        &masked,
+        &other_card,
+        &pk,
+        &proof
+    ));
+    assert!(!verify::verify_unmask_proof(
+        &masked, &card, &other_pk, &proof
+    ));
+    assert!(!verify::verify_unmask_proof(
+        &masked,
+        &card,
+        &G2Affine::identity(),
+        &proof
+    ));
+
+    // Forged unmasking cannot be proven using the real key
+    let forged_proof = sk.prove_unmask(&masked, &other_card, &mut rng);
+    assert!(!verify::verify_unmask_proof(
+        &masked,
+        &other_card,
+        &pk,
+        &forged_proof
+    ));
+}
+
+// Course of the hand
+
+#[test]
+fn test_lifecycle() {
+    // --- 1. SETUP ---
+    let mut rng = rand::thread_rng();
+
+    // Player A (Dealer) and Player B (Player)
+    let sk_a = Scalar::random(&mut rng);
+    let sk_b = Scalar::random(&mut rng);
+
+    // Public Keys in G2 for the Miller Loop "Audit"
+    let pk_a_g2 = make_public_key_from_signing_key(&sk_a);
+    let pk_b_g2 = make_public_key_from_signing_key(&sk_b);
+
+    // The "Ace of Spades" base point
+    let card_base = hash_to_curve(b"AS").to_affine();
+
+    // --- 2. SHUFFLE (Commutative Masking) ---
+    // Player A masks the card
+    let masked_a = sign::mask(card_base, sk_a);
+
+    // Player B masks Player A's result
+    let masked_b = sign::mask(masked_a, sk_b);
+    // At this point, masked_b = Card * sk_a * sk_b
+
+    // --- 3. BETTING (Threshold Consensus) ---
+    let bet_message = b"Player B bets 10 USDC";
+
+    // Both players sign the bet to reach consensus
+    let sig_a = sign::sign(bet_message, sk_a);
+    let sig_b = sign::sign(bet_message, sk_b);
+
+    // Combine into a threshold signature (2-of-2)
+    // For this test, we treat them as IDs 1 and 2
+    let shares = vec![(1, sig_a), (2, sig_b)];
+    let combined_bet_sig = lagrange::combine(&shares).expect("Failed to combine signatures");
+
+    let pub_shares = vec![(1, pk_a_g2), (2, pk_b_g2)];
+    let master_pk = lagrange::recover(&pub_shares).expect("Failed to recover master public key");
+
+    // Verify that bet message was signed by all participants
+    assert!(
+        verify::verify(bet_message, &master_pk, &combined_bet_sig),
+        "Failed to verify bet message"
+    );
+
+    // --- 4. DEALING (The Stateless Audit) ---
+    // To reveal the card to Player B, Player A must first "peel" their layer
+    let unmasked_by_a = sign::unmask(masked_b, sk_a).unwrap();
+
+    // The Referee (Stylus Contract) verifies Player A was honest
+    // It checks: e(Unmasked, G2) == e(Masked, PK_A)
+    let audit_passed = verify::verify_unmasking(masked_b, unmasked_by_a, pk_a_g2);
+    assert!(audit_passed, "Player A's unmasking audit failed!");
+
+    // Finally, Player B peels their own layer to see the card
+    let final_card = sign::unmask(unmasked_by_a, sk_b).unwrap();
+
+    // Verification: The final point should be the original Ace of Spades
+    assert_eq!(final_card, card_base, "The final card point is corrupted!");
+
+    println!("Sovereign Deal Complete: Bet signed and card audited successfully.");
+}
+
+#[test]
+fn test_state_display() {
+    let cases = [
+        (
+            PokerHandStateEnum::Shuffle {
+                player: 2,
+                is_dealer: true,
+            },
+            "Shuffle(player=2, dealer)",
+        ),
+        (
+            PokerHandStateEnum::Shuffle {
+                player: 0,
+                is_dealer: false,
+            },
+            "Shuffle(player=0)",
+        ),
+        (
+            PokerHandStateEnum::SmallBlind { player: 1 },
+            "SmallBlind(player=1)",
+        ),
+        (
+            PokerHandStateEnum::BigBlind { player: 2 },
+            "BigBlind(player=2)",
+        ),
+        (
+            PokerHandStateEnum::Bet {
+                round: 1,
+                player: 0,
+            },
+            "Bet(round=1, player=0)",
+        ),
+        (
+            PokerHandStateEnum::UnmaskHoleCards { player: 1 },
+            "UnmaskHoleCards(player=1)",
+        ),
+        (
+            PokerHandStateEnum::UnmaskCommunityCards {
+                round: 3,
+                player: 2,
+            },
+            "UnmaskCommunityCards(round=3, player=2)",
+        ),
+        (
+            PokerHandStateEnum::UnmaskShowdown { player: 0 },
+            "UnmaskShowdown(player=0)",
+        ),
+        (
+            PokerHandStateEnum::SubmitPublicKey { player: 1 },
+            "SubmitPublicKey(player=1)",
+        ),
+        (
+            PokerHandStateEnum::Cheated { player: 2 },
+            "Cheated(player=2)",
+        ),
+        (PokerHandStateEnum::Finished, "Finished"),
+        (PokerHandStateEnum::Invalid, "Invalid"),
+    ];
+    for (state, expected) in cases {
+        assert_eq!(state.to_string(), expected);
+    }
+
+    let hand = PokerHand::new(3, POKER_HOLDEM_ROUNDS, 1, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    let state = hand.get_current_state();
+    assert_eq!(state.to_enum().to_string(), "Shuffle(player=1, dealer)");
+    assert_eq!(state.status_code(), (POKER_HAND_STATE_SHUFFLE, 0, 1));
+}
+
+#[test]
+#[allow(clippy::redundant_pattern_matching)]
+fn test_poker() {
+    let mut rng = rand::thread_rng();
+
+    let sk_1 = Scalar::random(&mut rng);
+    let sk_2 = Scalar::random(&mut rng);
+
+    // Player 1 table create (Heads-up/Hold-em)
+    // Player 2 join
+
+    // Player 1 turn (shuffle)
+
+    // Player 1 starts with fresh deck of cards...
+    let poker_deck = PokerDeck::new();
+    let mut masked_deck = poker_deck.masked_cards();
+
+    // ...masks them and shuffles
+    masked_deck.mask(sk_1);
+    masked_deck.shuffle(&mut rng);
+
+    // Player 1 commits onchain masked/shuffled deck
+
+    // Player 2 turn (shuffle)
+
+    // Player 2 repeats masking and shuffling...
+    masked_deck.mask(sk_2);
+    masked_deck.shuffle(&mut rng);
+
+    // Player 2 commits onchain masked/shuffled deck
+
+    // Now we have shuffled deck of cards that no one knows which card is which
+    // because when player 1 shuffled masked cards, player 2 wouldn't know which
+    // card ended up at which position, and then player 2 also masked and shuffled
+    // and player 1 can no longer tell which card is which, because all cards are
+    // doubly masked by signing key of player 1 and player 2. Cool thing about BLS
+    // is that signing is commutative, so we can unmask later on in any order, which
+    // is critical for Poker game.
+    let deck_hash = masked_deck.hash();
+
+    // Both players sign the exact same flat hash
+    let sig_1 = sign::sign(&deck_hash, sk_1);
+    let sig_2 = sign::sign(&deck_hash, sk_2);
+
+    // Combine into the Master Signature
+    let bls_signature = lagrange::combine(&[(1, sig_1), (2, sig_2)]).expect("Should combine");
+
+    // Players commit signed deck hash onchain (in case game was fully off-chain)
+
+    let pk_1 = make_public_key_from_signing_key(&sk_1);
+    let pk_2 = make_public_key_from_signing_key(&sk_2);
+
+    let master_pk =
+        lagrange::recover(&[(1, pk_1), (2, pk_2)]).expect("Failed to recover master key");
+
+    // We can verify that deck matches the hash signed and committed onchain by players
+
+    assert!(
+        verify::verify(&deck_hash, &master_pk, &bls_signature),
+        "Failed to verify deck"
+    );
+
+    // ^^^ (Optional) should whole Poker game happen off-chain
+
+    // Player 1 turn (small blind)
+    // Player 2 turn (big blind)
+
+    // Posting blinds
+    // The players agree off-chain that P1 posts 5 (SB) and P2 posts 10 (BB).
+    let blind_state = b"STATE_1: POT=15, P1=-5, P2=-10";
+
+    let blind_sig_1 = sign::sign(blind_state, sk_1);
+    let blind_sig_2 = sign::sign(blind_state, sk_2);
+
+    // They combine signatures to prove they both committed to this pot size
+    let blinds_master_sig = lagrange::combine(&[(1, blind_sig_1), (2, blind_sig_2)])
+        .expect("Failed to combine blind signatures");
+
+    assert!(
+        verify::verify(blind_state, &master_pk, &blinds_master_sig),
+        "Failed to verify blinds consensus"
+    );
+
+    let mut p1_dealt_cards = masked_deck.deal(2).unwrap();
+    let mut p2_dealt_cards = masked_deck.deal(2).unwrap();
+
+    // normally there would be flop (3 cards), turn (1 card), river (1 card)
+    // but just for demo we deal all 5
+    let mut community_dealt_cards = masked_deck.deal(5).unwrap();
+
+    // Each player "peels" away their layer of masking from all cards delt
+    // except cards delt to them-selves
+    // In case of heads-up: Player 2 unpeels Player 1 cards
+    // and then Player 1 unpeels Player 2 cards.
+    p1_dealt_cards.unmask(sk_2).unwrap();
+    p2_dealt_cards.unmask(sk_1).unwrap();
+
+    // At this stage cards require last unpeel, and that must be done by
+    // target player, so that no other player will see fully revealed cards.
+    // Player 1 unpeels their own cards, and Player 2 unpeels their own cards.
+    p1_dealt_cards.unmask(sk_1).unwrap();
+    p2_dealt_cards.unmask(sk_2).unwrap();
+
+    // An community cards are unmasked by everyone.
+    community_dealt_cards.unmask(sk_1).unwrap();
+    community_dealt_cards.unmask(sk_2).unwrap();
+
+    // Decipher from G1 points to actual poker cards
+
+    let p1_hole_cards = poker_deck.unmasked_cards(&p1_dealt_cards);
+    let p2_hole_cards = poker_deck.unmasked_cards(&p2_dealt_cards);
+    let community_cards = poker_deck.unmasked_cards(&community_dealt_cards);
+
+    assert!(
+        matches!(p1_hole_cards[0], Some(_)),
+        "Player 1 Card 0 did not unmask correctly!"
+    );
+    assert!(
+        matches!(p1_hole_cards[1], Some(_)),
+        "Player 1 Card 1 did not unmask correctly!"
+    );
+
+    assert!(
+        matches!(p2_hole_cards[0], Some(_)),
+        "Player 2 Card 0 did not unmask correctly!"
+    );
+    assert!(
+        matches!(p2_hole_cards[1], Some(_)),
+        "Player 2 Card 1 did not unmask correctly!"
+    );
+
+    let p1_hole_cards_str = p1_hole_cards
+        .into_iter()
+        .map(|c| c.unwrap().to_string())
+        .join(", ");
+
+    let p2_hole_cards_str = p2_hole_cards
+        .into_iter()
+        .map(|c| c.unwrap().to_string())
+        .join(", ");
+
+    let community_cards_str = community_cards
+        .into_iter()
+        .map(|c| c.unwrap().to_string())
+        .join(", ");
+
+    println!("Player 1's Hole Cards are: {}", p1_hole_cards_str);
+    println!("Player 2's Hole Cards are: {}", p2_hole_cards_str);
+    println!("Community Cards are: {}", community_cards_str);
+}
+
+#[test]
+fn test_fold_skips_player() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(
+            s,
+            PokerHandStateEnum::Bet {
+                round: 1,
+                player: 1
+            }
+        )
+    });
+    assert!(!hand.player_folded(1));
+
+    // Middle player folds on the flop
+    hand.submit_action(1, PokerAction::Fold).unwrap();
+    assert!(hand.player_folded(1));
+    assert!(!hand.player_folded(0));
+    assert!(!hand.player_folded(2));
+    assert!(hand.submit_action(1, PokerAction::Check).is_err());
+
+    let mut community_peels = vec![];
+    while !hand.get_current_state().is_finished() {
+        match hand.get_current_state().to_enum() {
+            PokerHandStateEnum::Bet { round: _, player }
+            | PokerHandStateEnum::UnmaskShowdown { player } => assert_ne!(player, 1),
+            PokerHandStateEnum::UnmaskCommunityCards { round: _, player } => {
+                community_peels.push(player)
+            }
+            _ => {}
+        }
+        step_hand(&mut hand, &sks, &mut traces);
+    }
+
+    // Folded player still takes their mask off remaining community cards
+    assert!(community_peels.contains(&1));
+
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+    assert!(hand.get_revealed_cards(0).is_ok());
+    assert!(hand.get_revealed_cards(2).is_ok());
+    assert!(hand.get_revealed_cards(1).is_err());
+    assert_eq!(
+        (0..3).map(|p| hand.get_chips_remaining(p)).sum::<u64>(),
+        300
+    );
+}
+
+#[test]
+fn test_everyone_folds_preflop() {
+    // Player 1 posts small blind and player 2 big blind
+    let (sks, mut traces, mut hand) = holdem_hand(4, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    hand.submit_action(3, PokerAction::Raise(60)).unwrap();
+    hand.submit_action(0, PokerAction::Fold).unwrap();
+    hand.submit_action(1, PokerAction::Fold).unwrap();
+    hand.submit_action(2, PokerAction::Fold).unwrap();
+
+    // Hand ends without revealing the flop or showdown
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Finished
+    ));
+    assert!(hand.public_view().community_cards.is_empty());
+
+    assert_eq!(hand.get_chips_remaining(0), 100);
+    assert_eq!(hand.get_chips_remaining(1), 90);
+    assert_eq!(hand.get_chips_remaining(2), 80);
+    assert_eq!(hand.get_chips_remaining(3), 130);
+}
+
+#[test]
+fn test_community_dealt_with_big_blind() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskHoleCards { .. })
+    });
+
+    // All five community cards are fixed straight after hole cards
+    let final_deck = hand.shuffle_history.last().unwrap().as_slice().to_vec();
+    let dealt = |round: usize, hand: &PokerHand| hand.get_community_cards(round).unwrap().cards();
+    assert_eq!(dealt(POKER_HOLDEM_FLOP, &hand), final_deck[4..7]);
+    assert_eq!(dealt(POKER_HOLDEM_TURN, &hand), final_deck[7..8]);
+    assert_eq!(dealt(POKER_HOLDEM_RIVER, &hand), final_deck[8..9]);
+    assert!(hand.public_view().community_cards.is_empty());
+
+    // Flop is revealed after preflop betting, while turn and river stay masked
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 1, .. })
+    });
+    assert_eq!(hand.public_view().community_cards.len(), 3);
+    assert_eq!(dealt(POKER_HOLDEM_TURN, &hand), final_deck[7..8]);
+    assert_eq!(dealt(POKER_HOLDEM_RIVER, &hand), final_deck[8..9]);
+    assert!(
+        hand.visible_cards_for(0, sks[0])
+            .community_cards
+            .iter()
+            .all(Option::is_some)
+    );
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.public_view().community_cards.len(), 5);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+}
+
+#[test]
+fn test_community_layout_rounds_mismatch() {
+    // Every betting round needs its entry in the Hold'em layout
+    for max_rounds in [0, POKER_HOLDEM_ROUNDS + 1] {
+        assert_eq!(
+            PokerHand::new(2, max_rounds, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).err(),
+            Some(PokerError::RoundsMismatch)
+        );
+    }
+
+    // 24 players would need 53 cards, and 12 Omaha players 53 cards too
+    assert_eq!(
+        PokerHand::new(24, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).err(),
+        Some(PokerError::TooManyPlayers)
+    );
+    assert_eq!(
+        PokerHand::new_omaha(12, POKER_HOLDEM_ROUNDS, 0, 100, 10).err(),
+        Some(PokerError::TooManyPlayers)
+    );
+    assert!(PokerHand::new_omaha(11, POKER_HOLDEM_ROUNDS, 0, 100, 10).is_ok());
+
+    // Layout needs exactly one entry per betting round
+    let mut hand = PokerHand::new(2, 3, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    assert_eq!(hand.get_community_layout(), &[0, 3, 1]);
+    for layout in [vec![0, 3, 1, 1], vec![0, 5], vec![]] {
+        assert_eq!(
+            hand.set_community_layout(layout),
+            Err(PokerError::RoundsMismatch)
+        );
+    }
+    assert_eq!(hand.get_community_layout(), &[0, 3, 1]);
+
+    // Consistent layout is played and verified as usual
+    hand.set_community_layout(vec![0, 3, 2]).unwrap();
+    assert_eq!(hand.get_num_cards_needed(), 9);
+
+    let sks = make_signing_keys(2);
+    let mut traces = vec![None, None];
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.get_revealed_cards(0).unwrap().len(), 7);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+}
+
+#[test]
+fn test_community_layout() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    assert_eq!(hand.get_community_layout(), &[0, 3, 1, 1]);
+
+    hand.set_community_layout(vec![0, 2, 2, 1]).unwrap();
+
+    // Community cards of each round are dealt right after hole cards
+    let mut deck_idx = 3 * POKER_HOLDEM_HOLE_CARDS;
+
+    for round in 1..POKER_HOLDEM_ROUNDS {
+        play_hand_until(
+            &mut hand,
+            &sks,
+            &mut traces,
+            |s| matches!(s, PokerHandStateEnum::UnmaskCommunityCards { round: r, .. } if *r == round),
+        );
+
+        let count = hand.get_community_layout()[round];
+        let final_shuffled_deck = hand.shuffle_history.last().unwrap().as_slice();
+        let community_cards = hand.get_community_cards(round).unwrap();
+
+        assert_eq!(community_cards.len(), count);
+        assert_eq!(
+            community_cards.as_slice(),
+            &final_shuffled_deck[deck_idx..deck_idx + count]
+        );
+        deck_idx += count;
+    }
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    assert_eq!(hand.get_revealed_cards(0).unwrap().len(), 7);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+
+    // Settings cannot change once the hand is under way
+    let config = hand.get_config();
+    assert_eq!(
+        hand.set_community_layout(vec![0, 3, 1, 1]),
+        Err(PokerError::WrongState)
+    );
+    assert_eq!(hand.enable_run_it_twice(2), Err(PokerError::WrongState));
+    assert_eq!(hand.set_burn_cards(true), Err(PokerError::WrongState));
+    assert_eq!(hand.set_ante(5), Err(PokerError::WrongState));
+    assert_eq!(hand.set_big_blind(30), Err(PokerError::WrongState));
+    assert_eq!(
+        hand.configure(HandConfig {
+            ante: 5,
+            ..config.clone()
+        }),
+        Err(PokerError::WrongState)
+    );
+    assert_eq!(hand.get_config(), config);
+
+    // Next hand can be set up again
+    hand.reset_for_next_hand(1, &[100, 100, 100]).unwrap();
+    hand.set_ante(5).unwrap();
+    assert_eq!(hand.get_ante(), 5);
+}
+
+#[test]
+fn test_dealt_layout() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    assert_eq!(hand.dealt_layout(), Err(PokerError::NoShuffleHistory));
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskHoleCards { .. })
+    });
+
+    let layout = hand.dealt_layout().unwrap();
+    assert_eq!(layout.hole_cards, vec![0..2, 2..4, 4..6]);
+    assert_eq!(
+        layout.community_cards,
+        vec![vec![6..9], vec![9..10], vec![10..11], vec![11..11]]
+    );
+    assert!(layout.burned_cards.is_empty());
+
+    // Points are cards as dealt, before anyone unmasked them
+    let final_deck = hand.shuffle_history.last().unwrap().as_slice();
+    for (player, cards) in hand.get_player_cards().iter().enumerate() {
+        assert_eq!(layout.hole_points[player], cards.as_slice());
+        assert_eq!(
+            layout.hole_points[player],
+            final_deck[layout.hole_cards[player].clone()]
+        );
+    }
+    for round in POKER_HOLDEM_FLOP..=POKER_HOLDEM_RIVER {
+        assert_eq!(
+            layout.community_points[round - 1],
+            hand.get_community_cards(round).unwrap().as_slice()
+        );
+    }
+
+    // Layout stays the same while cards get unmasked
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.dealt_layout(), Ok(layout));
+}
+
+#[test]
+fn test_too_many_players() {
+    // 23 players need 46 hole cards and 5 community cards
+    let mut poker_table = PokerTable::new(24, POKER_HOLDEM_ROUNDS);
+    for player in 1..=23 {
+        poker_table.join(player).unwrap();
+    }
+    assert_eq!(poker_table.start_hand(100, 10), Ok(()));
+    assert_eq!(
+        poker_table
+            .get_current_hand()
+            .unwrap()
+            .get_num_cards_needed(),
+        51
+    );
+
+    // 24 players would need 53 cards
+    let mut poker_table = PokerTable::new(24, POKER_HOLDEM_ROUNDS);
+    for player in 1..=24 {
+        poker_table.join(player).unwrap();
+    }
+    assert_eq!(
+        poker_table.start_hand(100, 10),
+        Err(PokerError::TooManyPlayers)
+    );
+    assert!(poker_table.get_current_hand().is_none());
+}
+
+#[test]
+fn test_burn_cards() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    hand.set_burn_cards(true).unwrap();
+    assert_eq!(hand.get_num_cards_needed(), 12);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskHoleCards { .. })
+    });
+
+    // Card is burned before flop, turn and river, but none before preflop
+    let final_deck = hand.shuffle_history.last().unwrap().as_slice().to_vec();
+    let dealt = |round: usize, hand: &PokerHand| hand.get_community_cards(round).unwrap().cards();
+    assert_eq!(dealt(POKER_HOLDEM_FLOP, &hand), final_deck[5..8]);
+    assert_eq!(dealt(POKER_HOLDEM_TURN, &hand), final_deck[9..10]);
+    assert_eq!(dealt(POKER_HOLDEM_RIVER, &hand), final_deck[11..12]);
+    assert_eq!(
+        hand.get_burned_cards().cards(),
+        [final_deck[4], final_deck[8], final_deck[10]]
+    );
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.public_view().community_cards.len(), 5);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+    assert!(!hand.has_duplicate_cards());
+
+    // Burned cards are carried over in snapshot
+    let restored = PokerHand::restore(hand.snapshot()).unwrap();
+    assert!(restored.get_burn_cards());
+    assert_eq!(restored.get_burned_cards(), hand.get_burned_cards());
+}
+
+#[test]
+fn test_run_it_twice() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    hand.enable_run_it_twice(2).unwrap();
+
+    // Both players go all-in preflop
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    let first = hand.get_current_state().get_current_player();
+    hand.submit_action(first, PokerAction::AllIn).unwrap();
+    hand.submit_action(1 - first, PokerAction::Call).unwrap();
+    assert_eq!(hand.get_board_runs(), 2);
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+    assert!(!hand.has_duplicate_cards());
+
+    // Each run has its own five community cards
+    let board = |run: usize| hand.get_revealed_cards_in_run(0, run).unwrap()[2..].to_vec();
+    assert_eq!(board(0).len(), 5);
+    assert_eq!(board(1).len(), 5);
+    assert!(board(0).iter().all(|card| !board(1).contains(card)));
+    assert_eq!(
+        hand.get_revealed_cards(0).unwrap(),
+        hand.get_revealed_cards_in_run(0, 0).unwrap()
+    );
+
+    // Half of the pot goes to the best hand on each run
+    let mut expected = [0u64; 2];
+    for run in 0..2 {
+        let rank0 = hand.get_hand_rank_in_run(0, run).unwrap();
+        let rank1 = hand.get_hand_rank_in_run(1, run).unwrap();
+        match rank0.cmp(&rank1) {
+            Ordering::Greater => expected[0] += 100,
+            Ordering::Less => expected[1] += 100,
+            Ordering::Equal => {
+                expected[0] += 50;
+                expected[1] += 50;
+            }
+        }
+    }
+    assert_eq!(hand.get_chips_remaining(0), expected[0]);
+    assert_eq!(hand.get_chips_remaining(1), expected[1]);
+    assert_eq!(
+        hand.outcome().unwrap().hand_ranks[0],
+        Some(hand.get_hand_rank(0).unwrap())
+    );
+}
+
+#[test]
+fn test_run_it_twice_award_pots() {
+    let mut betting_state = PokerBettingState::new(2, 100);
+    betting_state.post_blind(0, 51).unwrap();
+    betting_state.post_big_blind(1, 50).unwrap();
+
+    // Odd chip of the pot goes to the first run
+    let awards = betting_state.award_pots_in_runs(&[vec![vec![1]], vec![vec![0]]]);
+    assert_eq!(awards, vec![(1, 51), (0, 50)]);
+    assert_eq!(betting_state.chips_remaining(0), 99);
+    assert_eq!(betting_state.chips_remaining(1), 101);
+}
+
+#[test]
+fn test_invalid_community_round() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskCommunityCards { .. })
+    });
+    let player = hand.get_current_state().get_current_player();
+    let mut cards = hand.get_community_cards(POKER_HOLDEM_FLOP).unwrap().clone();
+    cards.unmask(sks[player]).unwrap();
+
+    assert!(hand.get_community_cards(POKER_HOLDEM_PREFLOP).is_none());
+    assert!(hand.get_community_cards(usize::MAX).is_none());
+
+    for round in [0, POKER_HOLDEM_ROUNDS + 1, usize::MAX] {
+        assert_eq!(
+            hand.submit_community_cards(player, round, cards.clone()),
+            Err(PokerError::InvalidRound)
+        );
+        assert_eq!(
+            hand.submit_community_cards_with_proofs(
+                player,
+                round,
+                cards.clone(),
+                make_public_key_from_signing_key(&sks[player]),
+                vec![],
+            ),
+            Err(PokerError::InvalidRound)
+        );
+    }
+
+    // Hand carries on once the right round is given
+    assert!(
+        !hand
+            .submit_community_cards(player, POKER_HOLDEM_FLOP, cards)
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_wrong_community_count() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    for (round, expected) in [
+        (POKER_HOLDEM_FLOP, 3),
+        (POKER_HOLDEM_TURN, 1),
+        (POKER_HOLDEM_RIVER, 1),
+    ] {
+        play_hand_until(
+            &mut hand,
+            &sks,
+            &mut traces,
+            |s| matches!(s, PokerHandStateEnum::UnmaskCommunityCards { round: r, .. } if *r == round),
+        );
+        let player = hand.get_current_state().get_current_player();
+        let before = hand.get_community_cards(round).unwrap().clone();
+        let (cards, proofs) = unmask_with_proofs(&sks[player], &before);
+        let pk = make_public_key_from_signing_key(&sks[player]);
+
+        // Under-submitted and over-submitted cards
+        let short = UnmaskedCards::new(cards.cards()[1..].to_vec());
+        let mut long = cards.clone();
+        long.append(cards.clone());
+        for wrong in [short, long] {
+            assert_eq!(
+                hand.submit_community_cards(player, round, wrong.clone()),
+                Err(PokerError::WrongCommunityCount { expected })
+            );
+            assert_eq!(
+                hand.submit_community_cards_with_proofs(player, round, wrong, pk, proofs.clone()),
+                Err(PokerError::WrongCommunityCount { expected })
+            );
+        }
+
+        assert!(
+            !hand
+                .submit_community_cards_with_proofs(player, round, cards, pk, proofs)
+                .unwrap()
+        );
+    }
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+}
+
+#[test]
+fn test_reset_for_next_hand() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    hand.set_big_blind(30).unwrap();
+    let base_points = hand.get_poker_deck().as_slice().as_ptr();
+
+    // Next hand cannot start before this one is over
+    assert_eq!(
+        hand.reset_for_next_hand(1, &[100, 100, 100]),
+        Err(PokerError::HandInProgress)
+    );
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert!(hand.outcome().is_some());
+
+    let stacks: Vec<u64> = (0..3).map(|p| hand.get_chips_remaining(p)).collect();
+    assert_eq!(stacks.iter().sum::<u64>(), 300);
+
+    assert_eq!(
+        hand.reset_for_next_hand(1, &stacks[..2]),
+        Err(PokerError::StacksMismatch)
+    );
+    assert_eq!(
+        hand.reset_for_next_hand(3, &stacks),
+        Err(PokerError::InvalidPlayer)
+    );
+
+    hand.reset_for_next_hand(1, &stacks).unwrap();
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Shuffle {
+            player: 1,
+            is_dealer: true
+        }
+    ));
+    assert_eq!(hand.get_pot(), 0);
+    assert_eq!(hand.get_big_blind(), 30);
+    assert!(hand.outcome().is_none());
+    assert!(hand.action_log().is_empty());
+    assert!(hand.get_player_cards().iter().all(|cards| cards.is_empty()));
+    assert!(hand.shuffle_history.is_empty());
+    assert!(hand.unmasking_sequence.is_empty());
+    assert_eq!(hand.get_small_blind_player(), 2);
+
+    // Deck base points are not hashed to curve again
+    assert_eq!(hand.get_poker_deck().as_slice().as_ptr(), base_points);
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert!(hand.outcome().is_some());
+    assert_eq!(
+        (0..3).map(|p| hand.get_chips_remaining(p)).sum::<u64>(),
+        300
+    );
+    assert_eq!(hand.get_poker_deck().as_slice().as_ptr(), base_points);
+}
+
+#[test]
+fn test_action_log() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    let mut actions = vec![
+        PokerAction::Call,
+        PokerAction::Check,
+        PokerAction::Raise(40),
+        PokerAction::Call,
+        PokerAction::Check,
+        PokerAction::Check,
+        PokerAction::Raise(20),
+        PokerAction::Fold,
+    ]
+    .into_iter();
+
+    let mut expected = vec![];
+    while !hand.get_current_state().is_finished() {
+        let PokerHandStateEnum::Bet { round: _, player } = hand.get_current_state().to_enum()
+        else {
+            step_hand(&mut hand, &sks, &mut traces);
+            continue;
+        };
+        let action = actions.next().unwrap();
+        let street = hand.current_street();
+        let chips_before = hand.get_chips_remaining(player);
+        hand.submit_action(player, action).unwrap();
+        expected.push(ActionRecord {
+            player,
+            street,
+            action,
+            amount: chips_before - hand.get_chips_remaining(player),
+        });
+    }
+    assert!(actions.next().is_none());
+
+    assert_eq!(hand.action_log(), expected.as_slice());
+    assert_eq!(
+        hand.action_log()
+            .iter()
+            .map(|record| record.street)
+            .dedup()
+            .collect_vec(),
+        vec![Street::Preflop, Street::Flop, Street::Turn, Street::River]
+    );
+    assert_eq!(
+        hand.action_log()
+            .iter()
+            .map(|record| record.amount)
+            .collect_vec(),
+        vec![10, 0, 40, 40, 0, 0, 20, 0]
+    );
+
+    // Blinds are not betting decisions
+    assert_eq!(hand.action_log()[0].player, hand.get_small_blind_player());
+}
+
+#[test]
+fn test_action_timeout() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+
+    // Without timeout there is no clock
+    assert_eq!(hand.tick(100), Ok(None));
+    assert_eq!(hand.time_remaining(100), None);
+
+    hand.set_action_timeout(30);
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 0, .. })
+    });
+    let player = hand.get_current_state().get_current_player();
+
+    // Clock started as player's turn began, counting from last tick
+    assert_eq!(hand.time_remaining(110), Some(20));
+    assert_eq!(hand.tick(129), Ok(None));
+    hand.take_events();
+
+    // Player stalls while betting, and is folded
+    assert_eq!(hand.tick(130), Ok(Some(player)));
+    assert!(hand.player_folded(player));
+    assert_eq!(
+        hand.take_events(),
+        vec![
+            PokerEvent::PlayerTimedOut { player },
+            PokerEvent::BetPlaced {
+                player,
+                action: PokerAction::Fold
+            },
+        ]
+    );
+
+    // Next player gets their own full time straight away
+    assert_eq!(hand.time_remaining(130), Some(30));
+
+    // Moving in time restarts the clock for whoever is next
+    assert_eq!(hand.tick(150), Ok(None));
+    step_hand(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.time_remaining(150), Some(30));
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskCommunityCards { .. })
+    });
+    let player = hand.get_current_state().get_current_player();
+    hand.take_events();
+
+    // Player stalls unmasking, so the hand is aborted with them as cheater
+    assert_eq!(hand.tick(179), Ok(None));
+    assert_eq!(hand.tick(180), Ok(Some(player)));
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { player: p } if p == player
+    ));
+    assert_eq!(
+        hand.take_events(),
+        vec![
+            PokerEvent::PlayerTimedOut { player },
+            PokerEvent::CheatDetected { player },
+        ]
+    );
+    assert_eq!(hand.time_remaining(180), None);
+    assert_eq!(hand.tick(1000), Ok(None));
+}
+
+#[test]
+fn test_hand_outcome() {
+    let mut hand = revealed_hand(
+        &[&["Ah", "Kh"], &["9c", "9d"]],
+        &[&["Qh", "Jh", "2h"], &["3c"], &["4d"]],
+    );
+    hand.betting_state.process_action(0, 50).unwrap();
+    hand.betting_state.process_action(1, 50).unwrap();
+    assert_eq!(hand.outcome(), None);
+
+    hand.award_pot_to_best_hands().unwrap();
+
+    // Flush beats pair of nines
+    assert_eq!(
+        hand.outcome(),
+        Some(&HandOutcome {
+            winners: vec![0],
+            awards: vec![(0, 100)],
+            hand_ranks: vec![
+                Some(HandRank::Flush([14, 13, 12, 11, 2])),
+                Some(HandRank::Pair {
+                    pair: 9,
+                    kickers: [12, 11, 4]
+                }),
+            ],
+            ending: HandEnding::Showdown,
+        })
+    );
+
+    // Hand ends without showdown when everyone else folds
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 0, .. })
+    });
+    let player = hand.get_current_state().get_current_player();
+    hand.submit_action(player, PokerAction::Fold).unwrap();
+
+    assert!(hand.get_current_state().is_finished());
+    let outcome = hand.outcome().unwrap();
+    assert_eq!(outcome.ending, HandEnding::Fold);
+    assert_eq!(outcome.winners, vec![1 - player]);
+    assert_eq!(outcome.hand_ranks, vec![None, None]);
+}
+
+#[test]
+fn test_poker_error() {
+    let mut hand =
+        PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    let deck = hand.get_poker_deck().masked_cards();
+
+    assert_eq!(
+        hand.submit_shuffled_deck(1, deck.clone()),
+        Err(PokerError::NotYourTurn)
+    );
+    assert_eq!(hand.submit_small_blind(0), Err(PokerError::WrongState));
+
+    let err = hand.submit_bet(0, 10).unwrap_err();
+    assert_eq!(err.to_string(), "Action not allowed in current state");
+
+    let bytes: Vec<u8> = PokerError::UnmaskCheat(1).into();
+    assert_eq!(bytes, b"Player cheated during unmasking 1".to_vec());
+}
+
+#[test]
+fn test_public_view() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 0, .. })
+    });
+    let view = hand.public_view();
+    assert!(view.community_cards.is_empty());
+    assert_eq!(view.face_up_cards, vec![None, None]);
+    assert_eq!(view.bets, vec![10, 20]);
+    assert_eq!(view.pot, 30);
+
+    // Flop is visible once every player has removed their mask
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 1, .. })
+    });
+    let view = hand.public_view();
+    let flop = hand
+        .get_poker_deck()
+        .unmasked_cards(hand.get_community_cards(POKER_HOLDEM_FLOP).unwrap());
+    assert_eq!(view.community_cards.len(), 3);
+    assert_eq!(
+        view.community_cards,
+        flop.into_iter().map(Option::unwrap).collect_vec()
+    );
+    assert_eq!(view.face_up_cards, vec![None, None]);
+    assert_eq!(view.active_players, vec![true, true]);
+
+    // Hole cards are shown after showdown
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SubmitPublicKey { .. })
+    });
+    let view = hand.public_view();
+    assert_eq!(view.community_cards.len(), 5);
+    assert!(view.face_up_cards.iter().all(|cards| cards.is_some()));
+    assert_eq!(
+        view.face_up_cards[0].as_ref().unwrap(),
+        &hand.get_revealed_cards(0).unwrap()[..2]
+    );
+}
+
+#[test]
+fn test_visible_cards_for() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskCommunityCards { round: 1, .. })
+    });
+    let view = hand.visible_cards_for(0, sks[0]);
+    assert!(view.hole_cards.iter().all(Option::is_some));
+    assert!(view.community_cards.iter().all(Option::is_none));
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 1, .. })
+    });
+    let own_cards = |player: usize| {
+        let mut cards = hand.get_player_cards()[player].clone();
+        cards.unmask(sks[player]).unwrap();
+        hand.get_poker_deck().unmasked_cards(&cards)
+    };
+
+    // Player sees their own hole cards and the flop
+    for (player, sk) in sks.iter().enumerate() {
+        let view = hand.visible_cards_for(player, *sk);
+        assert_eq!(view.player, player);
+        assert_eq!(view.hole_cards.len(), 2);
+        assert!(view.hole_cards.iter().all(Option::is_some));
+        assert_eq!(view.hole_cards, own_cards(player));
+        assert_eq!(
+            view.community_cards,
+            hand.public_view()
+                .community_cards
+                .into_iter()
+                .map(Some)
+                .collect_vec()
+        );
+        assert_eq!(view.community_cards.len(), 3);
+    }
+
+    // Borrowed key sees the same cards
+    let sk = SecretKey::new(sks[0]);
+    assert_eq!(hand.visible_cards_for_with(0, &sk).hole_cards, own_cards(0));
+
+    // Opponent's hole cards stay hidden even when asked with own key
+    let view = hand.visible_cards_for(1, sks[0]);
+    assert!(view.hole_cards.iter().all(Option::is_none));
+    assert_eq!(view.community_cards.len(), 3);
+    assert!(hand.visible_cards_for(2, sks[0]).hole_cards.is_empty());
+}
+
+#[test]
+fn test_possible_opponent_holdings() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    // Flop is revealed, while hole cards are still masked by their owners
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { round: 1, .. })
+    });
+    let hero_cards: Vec<PokerCard> = hand
+        .visible_cards_for(0, sks[0])
+        .hole_cards
+        .into_iter()
+        .map(Option::unwrap)
+        .collect();
+    let flop = hand.public_view().community_cards;
+    assert_eq!(flop.len(), 3);
+
+    // Without hero's cards only the flop is known, C(49, 2)
+    assert_eq!(hand.possible_opponent_holdings(1, &[]).len(), 49 * 48 / 2);
+
+    // C(47, 2)
+    let holdings = hand.possible_opponent_holdings(1, &hero_cards);
+    assert_eq!(holdings.len(), 47 * 46 / 2);
+    assert!(
+        holdings
+            .iter()
+            .flatten()
+            .all(|c| !hero_cards.contains(c) && !flop.contains(c))
+    );
+}
+
+// Shuffling and unmasking
+
+#[test]
+fn test_duplicate_shuffle() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+
+    let event = next_hand_event(&hand, &sks, &mut traces);
+    let HandEvent::ShuffleDeck { player, deck } = event else {
+        panic!("Expected shuffle");
+    };
+    hand.submit_shuffled_deck(player, deck.clone()).unwrap();
+
+    // Client resubmits the same shuffle
+    assert_eq!(
+        hand.submit_shuffled_deck(player, deck),
+        Err(PokerError::AlreadyShuffled)
+    );
+    assert_eq!(hand.shuffle_history.len(), 1);
+
+    // Hand carries on with the next player
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SmallBlind { .. })
+    });
+    assert_eq!(hand.shuffle_history.len(), 3);
+}
+
+#[test]
+fn test_shuffle_size_mismatch() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    step_hand(&mut hand, &sks, &mut traces);
+
+    let HandEvent::ShuffleDeck { player, deck } = next_hand_event(&hand, &sks, &mut traces) else {
+        panic!("Expected shuffle");
+    };
+    let mut cards = deck.cards();
+
+    // Dropped card
+    cards.pop();
+    assert_eq!(cards.len(), 51);
+    assert_eq!(
+        hand.submit_shuffled_deck(player, MaskedCards::new(cards.clone())),
+        Err(PokerError::DeckSizeMismatch)
+    );
+
+    // Duplicated card
+    cards.push(cards[0]);
+    cards.push(cards[0]);
+    assert_eq!(
+        hand.submit_shuffled_deck(player, MaskedCards::new(cards)),
+        Err(PokerError::DeckSizeMismatch)
+    );
+
+    // Rejected shuffle does not count, so player may submit a valid one
+    assert_eq!(hand.shuffle_history.len(), 1);
+    hand.submit_shuffled_deck(player, deck).unwrap();
+    assert_eq!(hand.shuffle_history.len(), 2);
+}
+
+#[test]
+fn test_shuffle_with_proof() {
+    let sks = make_signing_keys(3);
+    let pks: Vec<_> = sks.iter().map(make_public_key_from_signing_key).collect();
+    let mut traces = vec![None; 3];
+
+    // Every shuffle is verified on submission, and again at the end of hand
+    let mut hand =
+        PokerHand::new(3, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    while let HandEvent::ShuffleDeck { player, deck } = next_hand_event(&hand, &sks, &mut traces) {
+        let shuffle_traces = traces[player].clone().unwrap();
+        hand.submit_shuffled_deck_with_proof(player, deck, pks[player], shuffle_traces)
+            .unwrap();
+    }
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // Cheating shuffle is caught before any chips are wagered
+    let mut traces = vec![None; 3];
+    let mut hand =
+        PokerHand::new(3, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    step_hand(&mut hand, &sks, &mut traces);
+
+    let HandEvent::ShuffleDeck { player, deck } = next_hand_event(&hand, &sks, &mut traces) else {
+        panic!("Expected shuffle");
+    };
+    let shuffle_traces = traces[player].clone().unwrap();
+    let mut cards = deck.cards();
+    cards[0] = hand.get_poker_deck().as_slice()[0];
+
+    assert_eq!(
+        hand.submit_shuffled_deck_with_proof(
+            player,
+            MaskedCards::new(cards),
+            pks[player],
+            shuffle_traces.clone()
+        ),
+        Err(PokerError::ShuffleForgery(player))
+    );
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { .. }
+    ));
+    assert_eq!(hand.get_pot(), 0);
+
+    // Genuine shuffle proven with someone else's key is rejected too
+    let mut traces = vec![None; 3];
+    let mut hand =
+        PokerHand::new(3, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    let HandEvent::ShuffleDeck { player, deck } = next_hand_event(&hand, &sks, &mut traces) else {
+        panic!("Expected shuffle");
+    };
+    let shuffle_traces = traces[player].clone().unwrap();
+    assert_eq!(
+        hand.submit_shuffled_deck_with_proof(player, deck, pks[player + 1], shuffle_traces),
+        Err(PokerError::ShuffleForgery(player))
+    );
+}
+
+#[test]
+fn test_submit_cards_with_proofs() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    // Every peel is audited as soon as it is submitted
+    while !matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::SubmitPublicKey { .. }
+    ) {
+        match hand.get_current_state().to_enum() {
+            PokerHandStateEnum::UnmaskHoleCards { player } => {
+                let mut cards = hand.get_player_cards().clone();
+                let mut proofs = vec![vec![]; 2];
+                for target in 0..2 {
+                    if target != player {
+                        (cards[target], proofs[target]) =
+                            unmask_with_proofs(&sks[player], &cards[target]);
+                    }
+                }
+                let pk = make_public_key_from_signing_key(&sks[player]);
+                hand.submit_player_cards_with_proofs(player, cards, pk, proofs)
+                    .unwrap();
+            }
+            PokerHandStateEnum::UnmaskCommunityCards { round, player } => {
+                let before = hand.get_community_cards(round).unwrap();
+                let (cards, proofs) = unmask_with_proofs(&sks[player], before);
+                let pk = make_public_key_from_signing_key(&sks[player]);
+                hand.submit_community_cards_with_proofs(player, round, cards, pk, proofs)
+                    .unwrap();
+            }
+            _ => step_hand(&mut hand, &sks, &mut traces),
+        }
+    }
+
+    // Revealed keys match keys used in proofs
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // Player forges hole cards of opponent, which proof cannot cover
+    let mut traces = vec![None, None];
+    let mut hand =
+        PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskHoleCards { .. })
+    });
+    let PokerHandStateEnum::UnmaskHoleCards { player } = hand.get_current_state().to_enum() else {
+        panic!("Expected unmasking of hole cards");
+    };
+    let target = 1 - player;
+
+    let mut cards = hand.get_player_cards().clone();
+    let mut proofs = vec![vec![]; 2];
+    (cards[target], proofs[target]) = unmask_with_proofs(&sks[player], &cards[target]);
+    cards[target] = UnmaskedCards::new(vec![G1Affine::generator(); POKER_HOLDEM_HOLE_CARDS]);
+
+    let pk = make_public_key_from_signing_key(&sks[player]);
+    assert_eq!(
+        hand.submit_player_cards_with_proofs(player, cards, pk, proofs),
+        Err(PokerError::UnmaskCheat(player))
+    );
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { player: p } if p == player
+    ));
+    assert!(
+        hand.take_events()
+            .contains(&PokerEvent::CheatDetected { player })
+    );
+}
+
+#[test]
+fn test_identity_public_key() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SubmitPublicKey { player: 0 })
+    });
+    hand.take_events();
+
+    // Identity key would make every unmasking check pass
+    assert_eq!(
+        hand.submit_public_key(0, G2Affine::identity(), traces[0].take().unwrap()),
+        Err(PokerError::UnmaskCheat(0))
+    );
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { player: 0 }
+    ));
+    assert_eq!(
+        hand.take_events(),
+        vec![PokerEvent::CheatDetected { player: 0 }]
+    );
+}
+
+#[test]
+fn test_reveal_bogus_card() {
+    let sks = make_signing_keys(2);
+    let bogus = UnmaskedCards::new(vec![G1Affine::generator(); 3]);
+
+    // Last player to peel flop reveals point that is not a card
+    let mut traces = vec![None, None];
+    let mut hand =
+        PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(
+            s,
+            PokerHandStateEnum::UnmaskCommunityCards { player: 1, .. }
+        )
+    });
+    assert_eq!(
+        hand.submit_community_cards(1, POKER_HOLDEM_FLOP, bogus.clone()),
+        Err(PokerError::UnmaskCheat(1))
+    );
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { player: 1 }
+    ));
+
+    // Player reveals bogus hole cards at showdown
+    let mut traces = vec![None, None];
+    let mut hand =
+        PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskShowdown { .. })
+    });
+    let PokerHandStateEnum::UnmaskShowdown { player } = hand.get_current_state().to_enum() else {
+        panic!("Expected showdown");
+    };
+    let mut cards = hand.get_player_cards().clone();
+    cards[player] = UnmaskedCards::new(bogus.as_slice()[..2].to_vec());
+
+    assert_eq!(
+        hand.submit_player_cards_showdown(player, cards),
+        Err(PokerError::UnmaskCheat(player))
+    );
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { player: p } if p == player
+    ));
+    assert!(
+        hand.take_events()
+            .contains(&PokerEvent::CheatDetected { player })
+    );
+}
+
+#[test]
+fn test_duplicate_cards() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SubmitPublicKey { player: 1 })
+    });
+    assert!(!hand.has_duplicate_cards());
+
+    // River card surfaces same card as one of hole cards of player 0
+    let hole_card = hand.get_player_cards()[0].as_slice()[0];
+    hand.community_cards[POKER_HOLDEM_RIVER - 1] = UnmaskedCards::new(vec![hole_card]);
+    assert!(hand.has_duplicate_cards());
+
+    hand.take_events();
+    let event = next_hand_event(&hand, &sks, &mut traces);
+    assert_eq!(hand.apply_event(event), Err(PokerError::DuplicateCards));
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Cheated { .. }
+    ));
+
+    // Duplicate cannot be traced to single player, so nobody is blamed
+    let events = hand.take_events();
+    assert!(events.contains(&PokerEvent::DuplicateCardsRevealed));
+    assert!(
+        !events
+            .iter()
+            .any(|event| matches!(event, PokerEvent::CheatDetected { .. }))
+    );
+}
+
+#[test]
+fn test_malformed_unmask() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    let resized = |cards: &UnmaskedCards, len: usize| {
+        UnmaskedCards::new(cards.cards().into_iter().cycle().take(len).collect())
+    };
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskHoleCards { player: 0 })
+    });
+    let mut cards = hand.get_player_cards().clone();
+    cards[1].unmask(sks[0]).unwrap();
+
+    // Hole cards of other player are truncated or padded
+    for len in [1, 3] {
+        let mut malformed = cards.clone();
+        malformed[1] = resized(&cards[1], len);
+        assert_eq!(
+            hand.submit_player_cards(0, malformed),
+            Err(PokerError::MalformedUnmask)
+        );
+    }
+    hand.submit_player_cards(0, cards).unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(
+            s,
+            PokerHandStateEnum::UnmaskCommunityCards { player: 0, .. }
+        )
+    });
+    let mut flop = hand.get_community_cards(POKER_HOLDEM_FLOP).unwrap().clone();
+    flop.unmask(sks[0]).unwrap();
+
+    // Flop has exactly three cards
+    for len in [2, 4] {
+        assert_eq!(
+            hand.submit_community_cards(0, POKER_HOLDEM_FLOP, resized(&flop, len)),
+            Err(PokerError::WrongCommunityCount { expected: 3 })
+        );
+    }
+    hand.submit_community_cards(0, POKER_HOLDEM_FLOP, flop)
+        .unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskShowdown { player: 0 })
+    });
+    let mut cards = hand.get_player_cards().clone();
+    cards[0] = resized(&cards[0], 1);
+    cards[0].unmask(sks[0]).unwrap();
+    assert_eq!(
+        hand.submit_player_cards_showdown(0, cards),
+        Err(PokerError::MalformedUnmask)
+    );
+
+    // Nobody is blamed for a submission that was rejected
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+}
+
+// Verification and audit
+
+#[test]
+fn test_verify_unmasking_batch() {
+    let finished_hand = |num_players: usize| {
+        let (sks, mut traces, mut hand) = holdem_hand(num_players, 0);
+        play_hand_to_end(&mut hand, &sks, &mut traces);
+        hand
+    };
+
+    let hand_a = finished_hand(2);
+    let mut hand_b = finished_hand(3);
+
+    let verify_together = |hand_a: &PokerHand, hand_b: &PokerHand| {
+        fn terms_of(hand: &PokerHand) -> Vec<verify::UnmaskingTerm<'_>> {
+            let pks = hand.public_keys().unwrap();
+            let prepared_pks = hand.prepared_public_keys().unwrap();
+            hand.unmasking_peels()
+                .unwrap()
+                .into_iter()
+                .map(|(unmasked, masked, player)| {
+                    (unmasked, masked, pks[player], prepared_pks[player])
+                })
+                .collect()
+        }
+
+        let terms = [terms_of(hand_a), terms_of(hand_b)].concat();
+
+        assert_eq!(
+            terms.len() * 2,
+            hand_a.audit_pairing_count() + hand_b.audit_pairing_count()
+        );
+
+        verify::verify_unmasking_batch(&terms)
+    };
+
+    assert!(verify_together(&hand_a, &hand_b));
+
+    // Player 0 of hand B forges a peel of hole cards of player 1
+    let (player, _, submitted_cards) = &mut hand_b.unmasking_sequence[0];
+    assert_eq!(*player, 0);
+    let mut forged = submitted_cards[1].cards();
+    forged[0] = G1Affine::generator();
+    submitted_cards[1] = UnmaskedCards::new(forged);
+
+    assert!(!verify_together(&hand_a, &hand_b));
+    assert_eq!(hand_b.verify_unmasking(), Ok(Some(0)));
+}
+
+#[test]
+fn test_unmasking_batch_cancelling_forgeries() {
+    use bls12_381::{Bls12, G1Projective};
+    use pairing::{MultiMillerLoop, group::Group};
+
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    let pks = hand.public_keys().unwrap();
+    let prepared_pks = hand.prepared_public_keys().unwrap();
+    let peels = hand.unmasking_peels().unwrap();
+    let mut terms: Vec<_> = peels
+        .iter()
+        .map(|&(unmasked, masked, player)| (unmasked, masked, pks[player], prepared_pks[player]))
+        .collect();
+    assert!(verify::verify_unmasking_batch(&terms));
+
+    // Product of all steps, each with coefficient 1
+    let naive_batch = |terms: &[verify::UnmaskingTerm]| {
+        let neg_g2_prepared = G2Prepared::from(-G2Affine::generator());
+        let miller_loop_terms: Vec<_> = terms
+            .iter()
+            .flat_map(|(unmasked, masked, _, pk)| [(unmasked, *pk), (masked, &neg_g2_prepared)])
+            .collect();
+        bool::from(
+            Bls12::multi_miller_loop(&miller_loop_terms)
+                .final_exponentiation()
+                .is_identity(),
+        )
+    };
+    assert!(naive_batch(&terms));
+
+    // Player 0 shifts one peel by some point and another peel by its negation
+    let (first, second) = peels
+        .iter()
+        .positions(|&(_, _, player)| player == 0)
+        .next_tuple()
+        .unwrap();
+    let shift = G1Projective::generator() * Scalar::from(7u64);
+    terms[first].0 = (terms[first].0 + shift).to_affine();
+    terms[second].0 = (terms[second].0 - shift).to_affine();
+
+    // Forgeries cancel out in naive product, but not with random coefficients
+    assert!(naive_batch(&terms));
+    assert!(!verify::verify_unmasking_batch(&terms));
+
+    // Each forged step fails on its own
+    for index in [first, second] {
+        assert!(!verify::verify_unmasking_batch(std::slice::from_ref(
+            &terms[index]
+        )));
+    }
+}
+
+#[test]
+fn test_unmasking_audit_trail() {
+    let num_players = 3;
+    let (sks, mut traces, mut hand) = holdem_hand(num_players, 0);
+    assert!(hand.unmasking_audit_trail().is_empty());
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // External verifier only gets the serialized trail and the public keys
+    let verify_exported = |hand: &PokerHand| {
+        let pks = hand.public_keys().unwrap();
+        let prepared_pks = hand.prepared_public_keys().unwrap();
+        let trail = hand.unmasking_audit_trail();
+        assert_eq!(trail.len() * 2, hand.audit_pairing_count());
+
+        let terms: Vec<_> = trail
+            .iter()
+            .map(|entry| {
+                let (unmasked, masked) = entry.to_points().unwrap();
+                (
+                    unmasked,
+                    masked,
+                    pks[entry.player],
+                    prepared_pks[entry.player],
+                )
+            })
+            .collect();
+        verify::verify_unmasking_batch(&terms)
+    };
+
+    assert!(verify_exported(&hand));
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+
+    // Some player forges a peel of community cards
+    let (player, _, submitted_cards) = hand
+        .unmasking_sequence
+        .iter_mut()
+        .find(|(_, state_type, _)| *state_type == POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS)
+        .unwrap();
+    let cheater = *player;
+    let mut forged = submitted_cards[0].cards();
+    forged[0] = G1Affine::generator();
+    submitted_cards[0] = UnmaskedCards::new(forged);
+
+    assert!(!verify_exported(&hand));
+    assert_eq!(hand.verify_unmasking(), Ok(Some(cheater)));
+
+    // Entries that are not valid points cannot be decoded
+    let mut entry = hand.unmasking_audit_trail().remove(0);
+    entry.masked.truncate(47);
+    assert_eq!(entry.to_points(), None);
+}
+
+#[test]
+fn test_audit_full() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 1);
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // Dealer shuffles first
+    let report = hand.audit_full().unwrap();
+    assert!(report.is_fair());
+    assert_eq!(report.shuffles, vec![(1, true), (0, true)]);
+    assert_eq!(report.peels * 2, hand.audit_pairing_count());
+
+    // Traces are kept in snapshot, so restored hand audits the same
+    let restored = PokerHand::restore(hand.snapshot()).unwrap();
+    assert_eq!(restored.audit_full(), Ok(report));
+
+    // Shuffle with cards left out of traces is not proven
+    let mut partial = PokerHand::restore(hand.snapshot()).unwrap();
+    partial.shuffle_traces[0].as_mut().unwrap().pop();
+    let report = partial.audit_full().unwrap();
+    assert_eq!(report.shuffles, vec![(1, true), (0, false)]);
+    assert_eq!(report.cheater, Some(0));
+
+    // Some player forges a peel of community cards
+    let (player, _, submitted_cards) = hand
+        .unmasking_sequence
+        .iter_mut()
+        .find(|(_, state_type, _)| *state_type == POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS)
+        .unwrap();
+    let cheater = *player;
+    let mut forged = submitted_cards[0].cards();
+    forged[0] = G1Affine::generator();
+    submitted_cards[0] = UnmaskedCards::new(forged);
+
+    let report = hand.audit_full().unwrap();
+    assert_eq!(report.cheater, Some(cheater));
+    assert!(report.shuffles.iter().all(|(_, valid)| *valid));
+
+    // Audit leaves the hand as it was
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Finished
+    ));
+}
+
+#[test]
+fn test_estimated_verification_gas() {
+    let heads_up =
+        PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    let nine_max =
+        PokerHand::new(9, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+
+    let gas_per_pairing = 45_000;
+
+    assert!(
+        nine_max.estimated_verification_gas(gas_per_pairing)
+            > heads_up.estimated_verification_gas(gas_per_pairing)
+    );
+
+    // Everyone all-in preflop with burned cards and board run twice
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    hand.set_burn_cards(true).unwrap();
+    hand.enable_run_it_twice(2).unwrap();
+    let estimated = hand.estimated_verification_gas(gas_per_pairing);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    let first = hand.get_current_state().get_current_player();
+    hand.submit_action(first, PokerAction::AllIn).unwrap();
+    while let PokerHandStateEnum::Bet { player, .. } = hand.get_current_state().to_enum() {
+        hand.submit_action(player, PokerAction::Call).unwrap();
+    }
+    assert_eq!(hand.get_board_runs(), 2);
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // Estimate covers every pairing the audit actually runs
+    let report = hand.audit_full().unwrap();
+    assert!(report.is_fair());
+    let pairings = 2 * report.peels + hand.shuffle_pairing_count();
+    assert_eq!(hand.audit_pairing_count(), 2 * report.peels);
+    assert_eq!(
+        hand.estimated_verification_gas(gas_per_pairing),
+        pairings as u64 * gas_per_pairing
+    );
+    assert_eq!(estimated, pairings as u64 * gas_per_pairing);
+
+    // Player who folds never peels own hole cards at showdown
+    let mut traces = vec![None, None, None];
+    let mut hand =
+        PokerHand::new(3, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    let upper_bound = hand.audit_pairing_count();
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    let first = hand.get_current_state().get_current_player();
+    hand.submit_action(first, PokerAction::Fold).unwrap();
+    assert_eq!(
+        hand.audit_pairing_count(),
+        upper_bound - 2 * POKER_HOLDEM_HOLE_CARDS
+    );
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(
+        hand.audit_pairing_count(),
+        2 * hand.unmasking_peels().unwrap().len()
+    );
+}
+
+#[test]
+fn test_referee_verify_hand() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 1);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SubmitPublicKey { .. })
+    });
+    let shuffle_traces: Vec<Vec<u8>> = (0..3)
+        .map(|step| {
+            let player = (1 + step) % 3;
+            verify::encode_traces(traces[player].as_ref().unwrap()).unwrap()
+        })
+        .collect();
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    let card_bytes = |cards: &[G1Affine]| -> Vec<u8> {
+        cards.iter().flat_map(|card| card.to_compressed()).collect()
+    };
+    let card_codes: Vec<u8> = hand
+        .get_poker_deck()
+        .poker_cards()
+        .iter()
+        .flat_map(PokerCard::to_bytes)
+        .collect();
+    let dealt_cards = hand.dealt_cards().unwrap();
+    let shuffled_decks: Vec<Vec<u8>> = hand
+        .shuffle_history
+        .iter()
+        .map(|deck| card_bytes(deck.as_slice()))
+        .collect();
+    let public_keys: Vec<u8> = sks
+        .iter()
+        .flat_map(|sk| public_key_to_bytes(&make_public_key_from_signing_key(sk)))
+        .collect();
+    let audit_trail: Vec<u8> = hand
+        .unmasking_audit_trail()
+        .iter()
+        .flat_map(|entry| entry.to_bytes().unwrap())
+        .collect();
+    let deck_commitment = hand.deck_commitment();
+
+    let verdict = |shuffled_decks: &[Vec<u8>],
+                   shuffle_traces: &[Vec<u8>],
+                   audit_trail: &[u8],
+                   deck_commitment: &[u8]| {
+        let shuffled_decks: Vec<&[u8]> = shuffled_decks.iter().map(Vec::as_slice).collect();
+        let shuffle_traces: Vec<&[u8]> = shuffle_traces.iter().map(Vec::as_slice).collect();
+        referee::verify_hand(referee::RefereeInput {
+            deck_commitment,
+            card_codes: &card_codes,
+            shuffled_decks: &shuffled_decks,
+            shuffle_traces: &shuffle_traces,
+            public_keys: &public_keys,
+            dealer: 1,
+            audit_trail,
+            dealt_cards: &dealt_cards,
+        })
+    };
+
+    // Known good hand
+    assert_eq!(
+        verdict(
+            &shuffled_decks,
+            &shuffle_traces,
+            &audit_trail,
+            &deck_commitment
+        ),
+        RefereeVerdict::Fair
+    );
+
+    // Last peel of a card reveals a different card
+    let mut bad_trail = audit_trail.clone();
+    let last = bad_trail.len() - referee::AUDIT_ENTRY_LEN;
+    let cheater = bad_trail[last] as usize;
+    bad_trail[last + 1..last + 1 + referee::CARD_COMPRESSED_LEN]
+        .copy_from_slice(&hand.get_poker_deck().as_slice()[0].to_compressed());
+    assert_eq!(
+        verdict(
+            &shuffled_decks,
+            &shuffle_traces,
+            &bad_trail,
+            &deck_commitment
+        ),
+        RefereeVerdict::Cheated(cheater)
+    );
+
+    // Second shuffler traces two cards to the same one
+    let mut bad_traces = shuffle_traces.clone();
+    bad_traces[1][1] = bad_traces[1][3];
+    assert_eq!(
+        verdict(&shuffled_decks, &bad_traces, &audit_trail, &deck_commitment),
+        RefereeVerdict::Cheated(2)
+    );
+
+    // Second shuffler replaces a card
+    let mut bad_decks = shuffled_decks.clone();
+    bad_decks[1][..referee::CARD_COMPRESSED_LEN]
+        .copy_from_slice(&hand.get_poker_deck().as_slice()[0].to_compressed());
+    assert_eq!(
+        verdict(&bad_decks, &shuffle_traces, &audit_trail, &deck_commitment),
+        RefereeVerdict::Cheated(2)
+    );
+
+    // Last shuffler drops a card
+    let mut bad_decks = shuffled_decks.clone();
+    bad_decks[2].truncate(51 * referee::CARD_COMPRESSED_LEN);
+    assert_eq!(
+        verdict(&bad_decks, &shuffle_traces, &audit_trail, &deck_commitment),
+        RefereeVerdict::Cheated(0)
+    );
+
+    // Record is not of the committed hand
+    assert!(matches!(
+        verdict(&shuffled_decks, &shuffle_traces, &audit_trail, &[0u8; 32]),
+        RefereeVerdict::Malformed(_)
+    ));
+    assert!(matches!(
+        verdict(
+            &shuffled_decks,
+            &shuffle_traces,
+            &audit_trail[1..],
+            &deck_commitment
+        ),
+        RefereeVerdict::Malformed(_)
+    ));
+    assert!(matches!(
+        verdict(
+            &shuffled_decks[..2],
+            &shuffle_traces,
+            &audit_trail,
+            &deck_commitment
+        ),
+        RefereeVerdict::Malformed(_)
+    ));
+
+    // Trail leaves out last peel of the first community card
+    let community = dealt_cards
+        .iter()
+        .position(|&owner| owner == referee::COMMUNITY_CARD)
+        .unwrap();
+    let entries: Vec<&[u8]> = audit_trail.chunks(referee::AUDIT_ENTRY_LEN).collect();
+    let mut card = &shuffled_decks[2][community * referee::CARD_COMPRESSED_LEN..]
+        [..referee::CARD_COMPRESSED_LEN];
+    let mut last_peel = None;
+    for (index, entry) in entries.iter().enumerate() {
+        if &entry[1 + referee::CARD_COMPRESSED_LEN..] == card {
+            card = &entry[1..1 + referee::CARD_COMPRESSED_LEN];
+            last_peel = Some(index);
+        }
+    }
+    let short_trail: Vec<u8> = entries
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| Some(index) != last_peel)
+        .flat_map(|(_, entry)| entry.to_vec())
+        .collect();
+    assert_eq!(
+        verdict(
+            &shuffled_decks,
+            &shuffle_traces,
+            &short_trail,
+            &deck_commitment
+        ),
+        RefereeVerdict::Malformed("Audit trail incomplete")
+    );
+
+    // Base points are derived from valid and distinct card codes only
+    let shuffled_decks: Vec<&[u8]> = shuffled_decks.iter().map(Vec::as_slice).collect();
+    let shuffle_traces: Vec<&[u8]> = shuffle_traces.iter().map(Vec::as_slice).collect();
+    let mut bad_codes = card_codes.clone();
+    bad_codes[0] = b'1';
+    let mut duplicate_codes = card_codes.clone();
+    duplicate_codes.copy_within(..2, 2);
+    for card_codes in [bad_codes, duplicate_codes] {
+        assert_eq!(
+            referee::verify_hand(referee::RefereeInput {
+                deck_commitment: &deck_commitment,
+                card_codes: &card_codes,
+                shuffled_decks: &shuffled_decks,
+                shuffle_traces: &shuffle_traces,
+                public_keys: &public_keys,
+                dealer: 1,
+                audit_trail: &audit_trail,
+                dealt_cards: &dealt_cards,
+            }),
+            RefereeVerdict::Malformed("Initial deck")
+        );
+    }
+}
+
+// Betting
+
+#[test]
+fn test_poker_actions() {
+    let mut betting_state = PokerBettingState::from_stacks(vec![100, 100, 100, 30]);
+    betting_state.set_big_blind(20);
+
+    // Nothing to call yet
+    assert_eq!(
+        betting_state.apply_action(0, PokerAction::Call),
+        Err(PokerError::IllegalAction)
+    );
+    assert_eq!(
+        betting_state.apply_action(0, PokerAction::Raise(0)),
+        Err(PokerError::IllegalAction)
+    );
+    betting_state.apply_action(0, PokerAction::Check).unwrap();
+    betting_state
+        .apply_action(1, PokerAction::Raise(20))
+        .unwrap();
+    assert_eq!(betting_state.round_bet(1), 20);
+
+    // Facing a bet
+    assert_eq!(
+        betting_state.apply_action(2, PokerAction::Check),
+        Err(PokerError::IllegalAction)
+    );
+    betting_state.apply_action(2, PokerAction::Call).unwrap();
+    assert_eq!(betting_state.round_bet(2), 20);
+
+    betting_state.apply_action(3, PokerAction::AllIn).unwrap();
+    assert_eq!(betting_state.round_bet(3), 30);
+    assert_eq!(betting_state.chips_remaining(3), 0);
+    assert_eq!(
+        betting_state.apply_action(3, PokerAction::AllIn),
+        Err(PokerError::IllegalAction)
+    );
+
+    betting_state.apply_action(0, PokerAction::Fold).unwrap();
+    assert!(!betting_state.get_active_players()[0]);
+    assert_eq!(
+        betting_state.apply_action(0, PokerAction::Check),
+        Err(PokerError::PlayerFolded)
+    );
+
+    // Amount of chips put in translates to an action
+    assert_eq!(betting_state.action_for_amount(1, 0), Ok(PokerAction::Fold));
+    assert_eq!(
+        betting_state.action_for_amount(1, 10),
+        Ok(PokerAction::Call)
+    );
+    assert_eq!(
+        betting_state.action_for_amount(1, 5),
+        Err(PokerError::BetTooSmall)
+    );
+    assert_eq!(
+        betting_state.action_for_amount(1, 50),
+        Ok(PokerAction::Raise(70))
+    );
+    assert_eq!(
+        betting_state.action_for_amount(1, 80),
+        Ok(PokerAction::AllIn)
+    );
+    assert_eq!(
+        betting_state.action_for_amount(1, 81),
+        Err(PokerError::InsufficientChips)
+    );
+}
+
+#[test]
+fn test_submit_action() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    // Small blind is facing the big blind
+    assert_eq!(hand.get_pot(), 30);
+    assert_eq!(hand.get_current_highest_bet(), 20);
+    assert_eq!(hand.get_player_bet(0), 10);
+    assert_eq!(hand.get_player_bet(1), 20);
+    assert_eq!(
+        hand.submit_action(1, PokerAction::Call),
+        Err(PokerError::NotYourTurn)
+    );
+    assert_eq!(
+        hand.submit_action(0, PokerAction::Check),
+        Err(PokerError::IllegalAction)
+    );
+    hand.apply_event(HandEvent::Action {
+        player: 0,
+        action: PokerAction::Raise(60),
+    })
+    .unwrap();
+    assert_eq!(hand.get_pot(), 80);
+    assert_eq!(hand.get_current_highest_bet(), 60);
+    assert_eq!(hand.get_player_bet(0), 60);
+    hand.submit_action(1, PokerAction::Call).unwrap();
+
+    assert_eq!(hand.get_pot(), 120);
+    assert_eq!(hand.get_chips_remaining(0), 40);
+    assert_eq!(hand.get_chips_remaining(1), 40);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    hand.submit_action(1, PokerAction::Check).unwrap();
+    hand.submit_action(0, PokerAction::Fold).unwrap();
+    assert!(!hand.is_player_active(0));
+    assert!(hand.is_player_active(1));
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Finished
+    ));
+}
+
+#[test]
+fn test_submit_call() {
+    let sks = make_signing_keys(3);
+    let mut traces = vec![None; 3];
+    let mut hand = PokerHand::from_stacks(
+        vec![100, 30, 100],
+        POKER_HOLDEM_ROUNDS,
+        0,
+        10,
+        POKER_HOLDEM_HOLE_CARDS,
+    )
+    .unwrap();
+    assert_eq!(hand.get_small_blind_player(), 1);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    hand.submit_action(0, PokerAction::Raise(80)).unwrap();
+
+    // Small blind has 20 chips left facing 70 to call, so goes all-in for less
+    assert_eq!(hand.get_call_amount_required(1), Ok(70));
+    hand.submit_call(1).unwrap();
+    assert!(hand.is_player_all_in(1));
+    assert_eq!(hand.get_chips_remaining(1), 0);
+    assert_eq!(
+        hand.action_log().last(),
+        Some(&ActionRecord {
+            player: 1,
+            street: Street::Preflop,
+            action: PokerAction::AllIn,
+            amount: 20,
+        })
+    );
+
+    // Big blind covers the call
+    hand.submit_call(2).unwrap();
+    assert_eq!(hand.get_chips_remaining(2), 20);
+    assert_eq!(hand.action_log().last().unwrap().action, PokerAction::Call);
+    assert_eq!(hand.get_pot(), 190);
+
+    // Nothing to call on the flop
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    let player = hand.get_current_state().get_current_player();
+    let other = if player == 0 { 2 } else { 0 };
+    assert_eq!(hand.submit_call(other), Err(PokerError::NotYourTurn));
+    hand.submit_call(player).unwrap();
+    assert_eq!(hand.action_log().last().unwrap().action, PokerAction::Check);
+}
+
+#[test]
+fn test_betting_order() {
+    // Dealer is player 3, so player 4 posts small blind and player 0 big blind
+    let (sks, mut traces, mut hand) = holdem_hand(5, 3);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SmallBlind { .. })
+    });
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::SmallBlind { player: 4 }
+    ));
+
+    step_hand(&mut hand, &sks, &mut traces);
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::BigBlind { player: 0 }
+    ));
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    // Player left of big blind (UTG) opens with a raise, so everyone else must act
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_PREFLOP,
+            player: 1
+        }
+    ));
+    hand.submit_bet(1, 40).unwrap();
+
+    let mut preflop = vec![1];
+    let mut flop = vec![];
+
+    loop {
+        match hand.get_current_state().to_enum() {
+            PokerHandStateEnum::Bet { round, player } if round == POKER_HOLDEM_PREFLOP => {
+                preflop.push(player)
+            }
+            PokerHandStateEnum::Bet { round, player } if round == POKER_HOLDEM_FLOP => {
+                flop.push(player)
+            }
+            PokerHandStateEnum::UnmaskCommunityCards { round, .. }
+                if round == POKER_HOLDEM_TURN =>
+            {
+                break;
+            }
+            _ => {}
+        }
+        step_hand(&mut hand, &sks, &mut traces);
+    }
+
+    assert_eq!(preflop, vec![1, 2, 3, 4, 0]);
+
+    // After the flop action starts left of the button
+    assert_eq!(flop, vec![4, 0, 1, 2, 3]);
+}
+
+#[test]
+fn test_players_to_act() {
+    let sks = make_signing_keys(4);
+    let mut traces = vec![None; 4];
+    let mut hand =
+        PokerHand::new(4, POKER_HOLDEM_ROUNDS, 0, 500, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    assert_eq!(hand.current_street(), Street::Preflop);
+
+    // UTG raises, and everyone else must call around the table
+    hand.submit_action(3, PokerAction::Raise(60)).unwrap();
+    assert_eq!(hand.players_to_act(), vec![0, 1, 2]);
+    hand.submit_action(0, PokerAction::Call).unwrap();
+    assert_eq!(hand.players_to_act(), vec![1, 2]);
+    hand.submit_action(1, PokerAction::Call).unwrap();
+    assert_eq!(hand.players_to_act(), vec![2]);
+    hand.submit_action(2, PokerAction::Call).unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    assert_eq!(hand.current_street(), Street::Flop);
+
+    // Nobody acted on the flop yet
+    assert_eq!(hand.players_to_act(), vec![0, 1, 2, 3]);
+    hand.submit_action(1, PokerAction::Check).unwrap();
+    assert_eq!(hand.players_to_act(), vec![0, 2, 3]);
+
+    // Bet reopens the action for the player who already checked
+    hand.submit_action(2, PokerAction::Raise(20)).unwrap();
+    assert_eq!(hand.players_to_act(), vec![0, 1, 3]);
+    hand.submit_action(3, PokerAction::Fold).unwrap();
+    assert_eq!(hand.players_to_act(), vec![0, 1]);
+}
+
+#[test]
+fn test_heads_up_order() {
+    // Player 1 has the button, and in heads-up the dealer posts small blind
+    let (sks, mut traces, mut hand) = holdem_hand(2, 1);
+    assert_eq!(hand.get_small_blind_player(), 1);
+    assert_eq!(hand.get_big_blind_player(), 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SmallBlind { .. })
+    });
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::SmallBlind { player: 1 }
+    ));
+    hand.submit_small_blind(1).unwrap();
+
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::BigBlind { player: 0 }
+    ));
+    hand.submit_big_blind(0).unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    // Dealer acts first preflop
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_PREFLOP,
+            player: 1
+        }
+    ));
+    assert_eq!(hand.get_call_amount_required(1), Ok(10));
+    hand.submit_bet(1, 30).unwrap();
+
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_PREFLOP,
+            player: 0
+        }
+    ));
+    hand.submit_bet(0, 20).unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    // Dealer acts last after the flop
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_FLOP,
+            player: 0
+        }
+    ));
+    hand.submit_bet(0, 0).unwrap();
+
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_FLOP,
+            player: 1
+        }
+    ));
+    hand.submit_bet(1, 0).unwrap();
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+    assert_eq!(
+        hand.get_chips_remaining(0) + hand.get_chips_remaining(1),
+        200
+    );
+}
+
+#[test]
+fn test_big_blind_option() {
+    // Heads-up: dealer limps in from the small blind
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    hand.submit_action(0, PokerAction::Call).unwrap();
+
+    // Bets are equal, but big blind still gets to act
+    assert_eq!(hand.players_to_act(), vec![1]);
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_PREFLOP,
+            player: 1
+        }
+    ));
+    hand.submit_action(1, PokerAction::Check).unwrap();
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::UnmaskCommunityCards {
+            round: POKER_HOLDEM_FLOP,
+            ..
+        }
+    ));
+
+    // Three-handed: everyone limps and big blind raises
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+    hand.submit_action(0, PokerAction::Call).unwrap();
+    hand.submit_action(1, PokerAction::Call).unwrap();
+    assert_eq!(hand.players_to_act(), vec![2]);
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_PREFLOP,
+            player: 2
+        }
+    ));
+    hand.submit_action(2, PokerAction::Raise(40)).unwrap();
+
+    // Raise reopens the action for the limpers
+    assert_eq!(hand.players_to_act(), vec![0, 1]);
+    hand.submit_action(0, PokerAction::Call).unwrap();
+    hand.submit_action(1, PokerAction::Call).unwrap();
+    assert_eq!(hand.get_pot(), 120);
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::UnmaskCommunityCards {
+            round: POKER_HOLDEM_FLOP,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_min_raise() {
+    let mut betting_state = PokerBettingState::from_stacks(vec![500, 500, 500, 150]);
+    betting_state.set_big_blind(20);
+
+    // Opening bet must be at least the big blind
+    assert_eq!(betting_state.min_raise_to(0), 20);
+    assert_eq!(
+        betting_state.process_action(0, 10),
+        Err(PokerError::RaiseTooSmall { min: 20 })
+    );
+
+    // Bet 40, then raise by 60 to 100
+    betting_state.process_action(0, 40).unwrap();
+    assert_eq!(betting_state.min_raise_to(1), 80);
+    betting_state.process_action(1, 100).unwrap();
+
+    // Next raise must be by at least 60
+    assert_eq!(betting_state.min_raise_to(2), 160);
+    assert_eq!(betting_state.min_raise_amount(2), 160);
+    assert_eq!(
+        betting_state.process_action(2, 150),
+        Err(PokerError::RaiseTooSmall { min: 160 })
+    );
+    assert_eq!(betting_state.chips_remaining(2), 500);
+
+    // Player with exactly 150 chips may raise all-in for less
+    assert_eq!(betting_state.min_raise_to(3), 150);
+    betting_state.process_action(3, 150).unwrap();
+    assert_eq!(betting_state.chips_remaining(3), 0);
+
+    // All-in for less does not change the minimum raise size
+    assert_eq!(betting_state.min_raise_to(2), 210);
+    betting_state.process_action(2, 210).unwrap();
+
+    // Minimum raise resets to big blind on next street
+    betting_state.next_street();
+    assert_eq!(betting_state.min_raise_to(0), 20);
+}
+
+#[test]
+fn test_antes() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
+    hand.set_ante(5).unwrap();
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SmallBlind { .. })
+    });
+    assert_eq!(hand.get_pot(), 0);
+    hand.take_events();
+
+    // Antes seed the pot from every player along with the small blind
+    hand.submit_small_blind(1).unwrap();
+    assert_eq!(hand.get_pot(), 25);
+    assert_eq!(hand.get_chips_remaining(0), 95);
+    assert_eq!(hand.get_chips_remaining(1), 85);
+    assert_eq!(hand.get_chips_remaining(2), 95);
+    assert_eq!(
+        hand.take_events(),
+        vec![
+            PokerEvent::AntePosted {
+                player: 0,
+                amount: 5
+            },
+            PokerEvent::AntePosted {
+                player: 1,
+                amount: 5
+            },
+            PokerEvent::AntePosted {
+                player: 2,
+                amount: 5
+            },
+            PokerEvent::BlindPosted {
+                player: 1,
+                amount: 10
+            },
+        ]
+    );
+
+    // Antes are dead money and do not count towards the preflop bet
+    hand.submit_big_blind(2).unwrap();
+    assert_eq!(hand.get_pot(), 45);
+    assert_eq!(hand.get_player_bet(1), 10);
+    assert_eq!(hand.get_current_highest_bet(), 20);
+    assert_eq!(hand.get_call_amount_required(0), Ok(20));
+
+    // Player short of the full ante is all-in for what they have
+    let mut betting_state = PokerBettingState::from_stacks(vec![100, 3]);
+    assert_eq!(betting_state.post_antes(5), vec![(0, 5), (1, 3)]);
+    assert_eq!(betting_state.pot(), 8);
+    assert_eq!(betting_state.chips_remaining(1), 0);
+    assert_eq!(betting_state.contribution(1), 3);
+}
+
+#[test]
+fn test_side_pots() {
+    let mut hand = revealed_hand(
+        &[&["Ah", "Ad"], &["Kh", "Kd"], &["Qh", "Jd"]],
+        &[&["As", "7c", "8d"], &["2h"], &["3s"]],
+    );
+    hand.betting_state = PokerBettingState::from_stacks(vec![50, 200, 200]);
+
+    // Short stack shoves for less than others will bet
+    hand.betting_state.process_action(0, 50).unwrap();
+    hand.betting_state.process_action(1, 100).unwrap();
+    hand.betting_state.process_action(2, 100).unwrap();
+
+    assert_eq!(
+        hand.betting_state.pots(),
+        vec![
+            Pot {
+                amount: 150,
+                eligible_players: vec![0, 1, 2]
+            },
+            Pot {
+                amount: 100,
+                eligible_players: vec![1, 2]
+            },
+        ]
+    );
+
+    let awards = hand.award_pot_to_best_hands().unwrap();
+
+    // Short stack wins only the main pot, side pot goes to the next best hand
+    assert_eq!(awards, vec![(0, 150), (1, 100)]);
+    assert_eq!(hand.get_chips_remaining(0), 150);
+    assert_eq!(hand.get_chips_remaining(1), 200);
+    assert_eq!(hand.get_chips_remaining(2), 100);
+    assert!(hand.betting_state.pots().is_empty());
+}
+
+#[test]
+fn test_all_in_for_less() {
+    let mut betting_state = PokerBettingState::from_stacks(vec![30, 100]);
+
+    betting_state.process_action(1, 50).unwrap();
+
+    // Putting in less than the call is only legal with the whole stack
+    assert_eq!(
+        betting_state.process_action(0, 20),
+        Err(PokerError::BetTooSmall)
+    );
+    assert_eq!(
+        betting_state.process_action(0, 40),
+        Err(PokerError::InsufficientChips)
+    );
+    betting_state.process_action(0, 30).unwrap();
+
+    assert_eq!(betting_state.chips_remaining(0), 0);
+    assert_eq!(betting_state.contribution(0), 30);
+    assert_eq!(
+        betting_state.pots(),
+        vec![
+            Pot {
+                amount: 60,
+                eligible_players: vec![0, 1]
+            },
+            // Uncalled part of the bet can only go back to player who bet it
+            Pot {
+                amount: 20,
+                eligible_players: vec![1]
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_all_in_for_less_completes_round() {
+    let mut betting_state = PokerBettingState::from_stacks(vec![100, 30, 100]);
+
+    betting_state
+        .apply_action(0, PokerAction::Raise(60))
+        .unwrap();
+    betting_state.apply_action(1, PokerAction::AllIn).unwrap();
+    assert!(!betting_state.is_betting_round_complete());
+    assert_eq!(betting_state.players_to_act(), vec![2]);
+
+    // Short stack is done even though their bet is below the highest bet
+    betting_state.apply_action(2, PokerAction::Call).unwrap();
+    assert_eq!(betting_state.round_bet(1), 30);
+    assert_eq!(betting_state.current_highest_bet(), 60);
+    assert!(betting_state.players_to_act().is_empty());
+    assert!(betting_state.is_betting_round_complete());
+
+    assert_eq!(
+        betting_state.pots(),
+        vec![
+            Pot {
+                amount: 90,
+                eligible_players: vec![0, 1, 2]
+            },
+            Pot {
+                amount: 60,
+                eligible_players: vec![0, 2]
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_all_in_not_waited_on() {
+    let sks = make_signing_keys(3);
+    let mut traces = vec![None; 3];
+    let mut hand = PokerHand::from_stacks(
+        vec![100, 40, 100],
+        POKER_HOLDEM_ROUNDS,
+        0,
+        10,
+        POKER_HOLDEM_HOLE_CARDS,
+    )
+    .unwrap();
+
+    // Short stack shoves preflop, and others call
+    let mut turns_after_shove = vec![];
+    while !hand.get_current_state().is_finished() {
+        let PokerHandStateEnum::Bet { round, player } = hand.get_current_state().to_enum() else {
+            step_hand(&mut hand, &sks, &mut traces);
+            continue;
+        };
+        if hand.is_player_all_in(1) {
+            turns_after_shove.push((round, player));
+        }
+        let action = if player == 1 {
+            PokerAction::AllIn
+        } else if hand.get_call_amount_required(player).unwrap() > 0 {
+            PokerAction::Call
+        } else {
+            PokerAction::Check
+        };
+        hand.submit_action(player, action).unwrap();
+    }
+
+    assert!(hand.is_player_all_in(1));
+    assert!(!hand.is_player_all_in(0));
+    assert!(!hand.is_player_all_in(2));
+    assert!(!turns_after_shove.iter().any(|&(_, player)| player == 1));
+
+    // Remaining players still bet on every street
+    for round in 1..POKER_HOLDEM_ROUNDS {
+        assert!(turns_after_shove.iter().any(|&(r, _)| r == round));
+    }
+    assert_eq!(
+        (0..3).map(|p| hand.get_chips_remaining(p)).sum::<u64>(),
+        240
+    );
+
+    // Heads-up, nobody is left to bet against once the shove is called
+    let sks = make_signing_keys(2);
+    let mut traces = vec![None; 2];
+    let mut hand = PokerHand::from_stacks(
+        vec![100, 40],
+        POKER_HOLDEM_ROUNDS,
+        0,
+        10,
+        POKER_HOLDEM_HOLE_CARDS,
+    )
+    .unwrap();
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { player: 1, .. })
+    });
+    hand.submit_action(1, PokerAction::AllIn).unwrap();
+    hand.submit_action(0, PokerAction::Call).unwrap();
+    assert!(hand.betting_state.is_betting_round_complete());
+    assert_eq!(hand.betting_state.get_acting_players(), vec![true, false]);
+
+    while !hand.get_current_state().is_finished() {
+        assert!(!matches!(
+            hand.get_current_state().to_enum(),
+            PokerHandStateEnum::Bet { .. }
+        ));
+        step_hand(&mut hand, &sks, &mut traces);
+    }
+    assert_eq!(
+        hand.get_chips_remaining(0) + hand.get_chips_remaining(1),
+        140
+    );
+}
+
+// Showdown and hand ranking
+
+#[test]
+fn test_best_hand() {
+    let seven = |codes: [&str; 7]| -> [PokerCard; 7] { codes.map(|code| code.parse().unwrap()) };
+    let five = |codes: [&str; 5]| -> [PokerCard; 5] { codes.map(|code| code.parse().unwrap()) };
+
+    // Wheel: Ace plays low and is read last
+    let (rank, cards) = best_hand(&seven(["As", "2d", "3c", "4h", "5s", "9d", "Kc"]));
+    assert_eq!(rank, HandRank::Straight { high: 5 });
+    assert_eq!(cards, five(["5s", "4h", "3c", "2d", "As"]));
+
+    // Six-high straight beats the wheel made of same board
+    let (six_high, cards) = best_hand(&seven(["As", "2d", "3c", "4h", "5s", "6d", "Kc"]));
+    assert_eq!(six_high, HandRank::Straight { high: 6 });
+    assert_eq!(cards, five(["6d", "5s", "4h", "3c", "2d"]));
+    assert!(six_high > rank);
+
+    // Steel wheel is straight flush, not royal flush
+    let (rank, cards) = best_hand(&seven(["Ah", "2h", "3h", "4h", "5h", "Kh", "Qd"]));
+    assert_eq!(rank, HandRank::StraightFlush { high: 5 });
+    assert_eq!(cards, five(["5h", "4h", "3h", "2h", "Ah"]));
+
+    // Ace cannot wrap around, i.e. Q-K-A-2-3 is not a straight
+    let (rank, _) = best_hand(&seven(["Qs", "Kd", "Ac", "2h", "3s", "8d", "7c"]));
+    assert_eq!(rank, HandRank::HighCard([14, 13, 12, 8, 7]));
+
+    // Pair of Kings, compared by top three kickers
+    let board = ["Kh", "Kd", "9c", "7s", "4d"];
+    let (pair_a, cards) = best_hand(&seven([
+        "As", "2c", board[0], board[1], board[2], board[3], board[4],
+    ]));
+    assert_eq!(
+        pair_a,
+        HandRank::Pair {
+            pair: 13,
+            kickers: [14, 9, 7]
+        }
+    );
+    assert_eq!(cards, five(["Kh", "Kd", "As", "9c", "7s"]));
+
+    let (pair_q, _) = best_hand(&seven([
+        "Qs", "Jc", board[0], board[1], board[2], board[3], board[4],
+    ]));
+    assert_eq!(
+        pair_q,
+        HandRank::Pair {
+            pair: 13,
+            kickers: [12, 11, 9]
+        }
+    );
+    assert!(pair_a > pair_q);
+
+    // Only top three kickers play, so the fourth kicker does not matter
+    let (pair_5, _) = best_hand(&seven(["Qd", "5c", "Kh", "Kd", "Jh", "9s", "2d"]));
+    let (pair_4, _) = best_hand(&seven(["Qh", "4c", "Ks", "Kc", "Jd", "9d", "2h"]));
+    assert_eq!(pair_5, pair_4);
+
+    // Two pair kicker breaks the tie
+    let (two_pair_a, cards) = best_hand(&seven(["As", "3c", "Kh", "Kd", "9c", "9s", "4d"]));
+    let (two_pair_q, _) = best_hand(&seven(["Qs", "3d", "Kh", "Kd", "9c", "9s", "4d"]));
+    assert_eq!(
+        two_pair_a,
+        HandRank::TwoPair {
+            high: 13,
+            low: 9,
+            kicker: 14
+        }
+    );
+    assert_eq!(cards, five(["Kh", "Kd", "9c", "9s", "As"]));
+    assert!(two_pair_a > two_pair_q);
+
+    // Flushes are compared card by card down to the last card
+    let (flush_a, cards) = best_hand(&seven(["Ah", "Jh", "9h", "6h", "4h", "2h", "Kc"]));
+    let (flush_b, _) = best_hand(&seven(["Ah", "Jh", "9h", "6h", "3h", "2h", "Kc"]));
+    assert_eq!(flush_a, HandRank::Flush([14, 11, 9, 6, 4]));
+    assert_eq!(cards, five(["Ah", "Jh", "9h", "6h", "4h"]));
+    assert_eq!(flush_b, HandRank::Flush([14, 11, 9, 6, 3]));
+    assert!(flush_a > flush_b);
+
+    // Flush beats straight made of same seven cards
+    let (rank, cards) = best_hand(&seven(["9h", "8h", "7s", "6h", "5c", "2h", "Kh"]));
+    assert_eq!(rank, HandRank::Flush([13, 9, 8, 6, 2]));
+    assert_eq!(cards, five(["Kh", "9h", "8h", "6h", "2h"]));
+    assert!(rank > HandRank::Straight { high: 9 });
+
+    // Full house picks the highest three, and pair out of second three
+    let (rank, cards) = best_hand(&seven(["7s", "7h", "7d", "Qs", "Qh", "Qc", "2d"]));
+    assert_eq!(rank, HandRank::FullHouse { three: 12, pair: 7 });
+    assert!(cards[..3].iter().all(|c| c.rank() == Rank::Queen));
+    assert!(cards[3..].iter().all(|c| c.rank() == Rank::Seven));
+
+    // Full house beats flush
+    let (rank, _) = best_hand(&seven(["Ks", "Kh", "Kd", "5h", "5s", "9h", "2h"]));
+    assert_eq!(rank, HandRank::FullHouse { three: 13, pair: 5 });
+    assert!(rank > HandRank::Flush([14, 13, 12, 11, 9]));
+
+    // Rank agrees with rank_hand()
+    let all = seven(["Ts", "Js", "Qs", "Ks", "As", "Ah", "Ad"]);
+    assert_eq!(best_hand(&all).0, rank_hand(&all).unwrap());
+    assert_eq!(best_hand(&all).0, HandRank::RoyalFlush);
+}
+
+#[test]
+fn test_rank_omaha_hand() {
+    let hole_cards = unmasked_cards_of(&["As", "2d", "3c", "4h"]);
+    let community_cards = unmasked_cards_of(&["Ks", "Qs", "Js", "Ts", "9s"]);
+
+    let deck = PokerDeck::new();
+    let hole_cards: Vec<_> = deck
+        .unmasked_cards(&hole_cards)
+        .into_iter()
+        .flatten()
+        .collect();
+    let community_cards: Vec<_> = deck
+        .unmasked_cards(&community_cards)
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let all_cards: Vec<_> = hole_cards.iter().chain(&community_cards).cloned().collect();
+    assert_eq!(rank_hand(&all_cards), Some(HandRank::RoyalFlush));
+
+    // Omaha hand must use exactly two hole cards, so there is no flush
+    assert_eq!(
+        rank_omaha_hand(&hole_cards, &community_cards),
+        Some(HandRank::HighCard([14, 13, 12, 11, 4]))
+    );
+
+    let hand = revealed_hand(
+        &[&["As", "2d", "3c", "4h"], &["8s", "7s", "2c", "2h"]],
+        &[&["Ks", "Qs", "Js"], &["Ts"], &["9s"]],
+    );
+    assert_eq!(
+        hand.get_hand_rank(0),
+        Ok(HandRank::HighCard([14, 13, 12, 11, 4]))
+    );
+    assert_eq!(
+        hand.get_hand_rank(1),
+        Ok(HandRank::StraightFlush { high: 11 })
+    );
+}
+
+#[test]
+fn test_omaha_hand() {
+    let sks = make_signing_keys(3);
+    let mut traces = vec![None; 3];
+
+    let mut hand = PokerHand::new_omaha(3, POKER_HOLDEM_ROUNDS, 0, 100, 10).unwrap();
+    assert_eq!(hand.get_hole_cards_count(), POKER_OMAHA_HOLE_CARDS);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskHoleCards { .. })
+    });
+
+    for cards in hand.get_player_cards() {
+        assert_eq!(cards.len(), POKER_OMAHA_HOLE_CARDS);
+    }
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // Every player revealed four hole cards at showdown
+    for player in 0..3 {
+        let cards = hand.get_revealed_cards(player).unwrap();
+        assert_eq!(cards.len(), POKER_OMAHA_HOLE_CARDS + 5);
+        assert!(hand.get_hand_rank(player).is_ok());
+    }
+
+    // Unmasking replays with four card stride per player
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+
+    let total_chips: u64 = (0..3).map(|p| hand.get_chips_remaining(p)).sum();
+    assert_eq!(total_chips, 300);
+}
+
+#[test]
+fn test_estimate_strength() {
+    let cards = |codes: &[&str]| -> Vec<PokerCard> {
+        codes.iter().map(|code| code.parse().unwrap()).collect()
+    };
+    let board = cards(&["Kd", "8s", "3c"]);
+    let mut rng = ChaCha20Rng::from_seed([7; 32]);
+
+    // Pocket aces are well ahead of 7-2 offsuit on a dry flop
+    let aces = estimate_strength_with_rng(&cards(&["As", "Ah"]), &board, 1, 2000, &mut rng);
+    let seven_deuce = estimate_strength_with_rng(&cards(&["7h", "2d"]), &board, 1, 2000, &mut rng);
+    assert!(aces > 0.8, "aces strength {aces}");
+    assert!(seven_deuce < 0.25, "seven deuce strength {seven_deuce}");
+
+    // More opponents make the same hand weaker
+    let aces_vs_three = estimate_strength(&cards(&["As", "Ah"]), &board, 3, 2000);
+    assert!(aces_vs_three < aces);
+
+    // Royal flush on the river cannot lose
+    let royal = estimate_strength(
+        &cards(&["As", "Ks"]),
+        &cards(&["Qs", "Js", "Ts", "2d", "3c"]),
+        2,
+        100,
+    );
+    assert_eq!(royal, 1.0);
+    assert_eq!(estimate_strength(&cards(&["As", "Ah"]), &board, 1, 0), 0.0);
+}
+
+#[test]
+fn test_verify_claim() {
+    let hand = revealed_hand(
+        &[&["Ah", "Kh"], &["9c", "9d"]],
+        &[&["Qh", "Jh", "2h"], &["3c"], &["4d"]],
+    );
+
+    assert_eq!(
+        hand.get_hand_rank(0).unwrap(),
+        HandRank::Flush([14, 13, 12, 11, 2])
+    );
+    assert_eq!(
+        hand.get_hand_rank(1).unwrap(),
+        HandRank::Pair {
+            pair: 9,
+            kickers: [12, 11, 4]
+        }
+    );
+
+    assert!(
+        hand.verify_claim(0, HandRank::Flush([14, 13, 12, 11, 2]))
+            .unwrap()
+    );
+
+    // Inflated claim: there is no straight on this board
+    assert!(
+        !hand
+            .verify_claim(0, HandRank::Straight { high: 14 })
+            .unwrap()
+    );
+    assert!(
+        !hand
+            .verify_claim(1, HandRank::Straight { high: 12 })
+            .unwrap()
+    );
+
+    // Cards that were never revealed cannot back any claim
+    let hidden =
+        PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+    assert!(hidden.verify_claim(0, HandRank::RoyalFlush).is_err());
+}
+
+#[test]
+fn test_showdown_policy() {
+    let sks = make_signing_keys(2);
+
+    for policy in [ShowdownPolicy::MustReveal, ShowdownPolicy::MayMuck] {
+        let mut traces = vec![None, None];
+        let mut hand =
+            PokerHand::new(2, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_HOLDEM_HOLE_CARDS).unwrap();
+        hand.set_showdown_policy(policy);
+
+        play_hand_until(&mut hand, &sks, &mut traces, |s| {
+            matches!(s, PokerHandStateEnum::UnmaskShowdown { player: 0 })
+        });
+
+        // Nobody has shown yet, so there is nothing to concede to
+        assert!(hand.submit_muck(0).is_err());
+
+        let mut cards = hand.get_player_cards().clone();
+        cards[0].unmask(sks[0]).unwrap();
+        hand.submit_player_cards_showdown(0, cards).unwrap();
+
+        match policy {
+            ShowdownPolicy::MustReveal => {
+                assert!(hand.submit_muck(1).is_err());
+                assert!(!hand.is_player_mucked(1));
+            }
+            ShowdownPolicy::MayMuck => {
+                assert!(hand.submit_muck(1).unwrap());
+                assert!(hand.is_player_mucked(1));
+
+                play_hand_to_end(&mut hand, &sks, &mut traces);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_muck_wins_uncontested() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    hand.set_showdown_policy(ShowdownPolicy::MayMuck);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskShowdown { player: 0 })
+    });
+
+    let pot = hand.get_pot();
+    let mut cards = hand.get_player_cards().clone();
+    cards[0].unmask(sks[0]).unwrap();
+    hand.submit_player_cards_showdown(0, cards).unwrap();
+
+    // Second player concedes without peeling their hole cards
+    assert!(hand.submit_muck(1).unwrap());
+    let p2_cards = hand
+        .get_poker_deck()
+        .unmasked_cards(&hand.get_player_cards()[1]);
+    assert!(p2_cards.iter().all(|c| c.is_none()));
+    hand.take_events();
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    // Missing showdown peel of mucked player is not cheating
+    assert_eq!(hand.verify_unmasking(), Ok(None));
+
+    // First player wins the whole pot, whatever the mucked hand was
+    assert!(hand.take_events().contains(&PokerEvent::PotsAwarded {
+        awards: vec![(0, pot)]
+    }));
+    assert_eq!(hand.get_chips_remaining(0), 100 - pot / 2 + pot);
+    assert_eq!(hand.get_chips_remaining(1), 100 - pot / 2);
+}
+
+#[test]
+fn test_award_pot_to_best_hand() {
+    let mut hand = revealed_hand(
+        &[&["Ah", "Kh"], &["9c", "9d"]],
+        &[&["Qh", "Jh", "2h"], &["3c"], &["4d"]],
+    );
+    hand.betting_state.process_action(0, 50).unwrap();
+    hand.betting_state.process_action(1, 50).unwrap();
+
+    // Flush beats pair of nines
+    let awards = hand.award_pot_to_best_hands().unwrap();
+
+    assert_eq!(awards, vec![(0, 100)]);
+    assert_eq!(hand.get_chips_remaining(0), 150);
+    assert_eq!(hand.get_chips_remaining(1), 50);
+}
+
+#[test]
+fn test_award_pot_odd_chip() {
+    let mut betting_state = PokerBettingState::from_stacks(vec![100, 100, 1]);
+    betting_state.process_action(0, 50).unwrap();
+    betting_state.process_action(1, 50).unwrap();
+    betting_state.process_action(2, 1).unwrap();
+    assert_eq!(betting_state.pot(), 101);
+
+    // Odd chip goes to the winner given first, i.e. earlier seat left of the button
+    assert_eq!(betting_state.award_pot(&[0, 1]), vec![(0, 51), (1, 50)]);
+    assert_eq!(betting_state.pot(), 0);
+    assert_eq!(betting_state.chips_remaining(0), 101);
+    assert_eq!(betting_state.chips_remaining(1), 100);
+    assert_eq!(betting_state.chips_remaining(2), 0);
+}
+
+#[test]
+fn test_award_pot_split_with_odd_chip() {
+    // Players 1 and 2 both play the broadway straight on board
+    let mut hand = revealed_hand(
+        &[&["2c", "3d"], &["4c", "5d"], &["6c", "7d"]],
+        &[&["As", "Kd", "Qc"], &["Jh"], &["Ts"]],
+    );
+    // Player 0 has the same straight, but folded
+    hand.betting_state.set_big_blind(1);
+    hand.betting_state.process_action(0, 1).unwrap();
+    hand.betting_state.process_action(1, 1).unwrap();
+    hand.betting_state.process_action(2, 1).unwrap();
+    hand.betting_state.next_street();
+    hand.betting_state.process_action(1, 1).unwrap();
+    hand.betting_state.process_action(2, 1).unwrap();
+    hand.betting_state.process_action(0, 0).unwrap();
+
+    let awards = hand.award_pot_to_best_hands().unwrap();
+
+    // Player 1 sits first left of the button (player 0) and gets the odd chip
+    assert_eq!(awards, vec![(1, 3), (2, 2)]);
+    assert_eq!(hand.get_chips_remaining(0), 99);
+    assert_eq!(hand.get_chips_remaining(1), 101);
+    assert_eq!(hand.get_chips_remaining(2), 100);
+}
+
+#[test]
+fn test_short_deck_showdown() {
+    // Player 0 has flush and player 1 has full house
+    let mut hand = revealed_hand(
+        &[&["Ah", "7h"], &["Qs", "7d"]],
+        &[&["Qh", "Qd", "7s"], &["9h"], &["Th"]],
+    );
+    hand.set_short_deck(true).unwrap();
+    assert!(hand.is_short_deck());
+    assert_eq!(hand.get_poker_deck().len(), 36);
+    hand.betting_state.set_big_blind(1);
+    hand.betting_state.process_action(0, 1).unwrap();
+    hand.betting_state.process_action(1, 1).unwrap();
+
+    // Flush beats full house in short deck
+    let awards = hand.award_pot_to_best_hands().unwrap();
+    assert_eq!(awards, vec![(0, 2)]);
+    assert!(matches!(hand.get_hand_rank(0), Ok(HandRank::Flush(_))));
+
+    // Same cards with full deck go to full house
+    let mut hand = revealed_hand(
+        &[&["Ah", "7h"], &["Qs", "7d"]],
+        &[&["Qh", "Qd", "7s"], &["9h"], &["Th"]],
+    );
+    hand.betting_state.set_big_blind(1);
+    hand.betting_state.process_action(0, 1).unwrap();
+    hand.betting_state.process_action(1, 1).unwrap();
+    let awards = hand.award_pot_to_best_hands().unwrap();
+    assert_eq!(awards, vec![(1, 2)]);
+
+    // Omaha ranks by short-deck rules too
+    let mut hand = revealed_hand(
+        &[&["Ah", "7h", "6c", "8d"], &["Qs", "7d", "6d", "8c"]],
+        &[&["Qh", "Qd", "7s"], &["9h"], &["Th"]],
     );
+    hand.set_short_deck(true).unwrap();
+    assert!(matches!(hand.get_hand_rank(0), Ok(HandRank::Flush(_))));
+    assert!(matches!(
+        hand.get_hand_rank(1),
+        Ok(HandRank::FullHouse { .. })
+    ));
+    hand.betting_state.set_big_blind(1);
+    hand.betting_state.process_action(0, 1).unwrap();
+    hand.betting_state.process_action(1, 1).unwrap();
+    let awards = hand.award_pot_to_best_hands().unwrap();
+    assert_eq!(awards, vec![(0, 2)]);
+
+    // Short deck must hold enough cards for everyone
+    let mut hand =
+        PokerHand::new(8, POKER_HOLDEM_ROUNDS, 0, 100, 10, POKER_OMAHA_HOLE_CARDS).unwrap();
+    assert_eq!(hand.set_short_deck(true), Err(PokerError::TooManyPlayers));
+    assert!(!hand.is_short_deck());
+    assert_eq!(hand.get_poker_deck().len(), 52);
+
+    // Deck cannot change once shuffling has started
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+    let HandEvent::ShuffleDeck { player, deck } = next_hand_event(&hand, &sks, &mut traces) else {
+        panic!("Expected shuffle");
+    };
+    hand.submit_shuffled_deck(player, deck).unwrap();
+    assert_eq!(hand.set_short_deck(true), Err(PokerError::WrongState));
+    assert!(!hand.is_short_deck());
+}
 
-    let mut p1_dealt_cards = masked_deck.deal(2);
-    let mut p2_dealt_cards = masked_deck.deal(2);
+#[test]
+fn test_hand_pays_out() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 0);
 
-    // normally there would be flop (3 cards), turn (1 card), river (1 card)
-    // but just for demo we deal all 5
-    let mut community_dealt_cards = masked_deck.deal(5);
+    play_hand_to_end(&mut hand, &sks, &mut traces);
 
-    // Each player "peels" away their layer of masking from all cards delt
-    // except cards delt to them-selves
-    // In case of heads-up: Player 2 unpeels Player 1 cards
-    // and then Player 1 unpeels Player 2 cards.
-    p1_dealt_cards.unmask(sk_2);
-    p2_dealt_cards.unmask(sk_1);
+    let chips: Vec<_> = (0..3).map(|p| hand.get_chips_remaining(p)).collect();
 
-    // At this stage cards require last unpeel, and that must be done by
-    // target player, so that no other player will see fully revealed cards.
-    // Player 1 unpeels their own cards, and Player 2 unpeels their own cards.
-    p1_dealt_cards.unmask(sk_1);
-    p2_dealt_cards.unmask(sk_2);
+    // Pot has been paid out in full to the winner(s)
+    assert_eq!(chips.iter().sum::<u64>(), 300);
+}
 
-    // An community cards are unmasked by everyone.
-    community_dealt_cards.unmask(sk_1);
-    community_dealt_cards.unmask(sk_2);
+// Table and tournament
 
-    // Decipher from G1 points to actual poker cards
+/// Agent that calls every bet to the showdown
+struct CallingAgent {
+    sk: SecretKey,
+    trace: Option<Vec<verify::ShuffleTrace>>,
+}
 
-    let p1_hole_cards = poker_deck.unmasked_cards(&p1_dealt_cards);
-    let p2_hole_cards = poker_deck.unmasked_cards(&p2_dealt_cards);
-    let community_cards = poker_deck.unmasked_cards(&community_dealt_cards);
+impl PlayerAgent for CallingAgent {
+    fn provide_shuffle(&mut self, deck: &mut MaskedCards) {
+        deck.mask_with(&self.sk);
+        self.trace
+            .replace(deck.shuffle_traced(&mut rand::thread_rng()));
+    }
 
-    assert!(
-        matches!(p1_hole_cards[0], Some(_)),
-        "Player 1 Card 0 did not unmask correctly!"
-    );
-    assert!(
-        matches!(p1_hole_cards[1], Some(_)),
-        "Player 1 Card 1 did not unmask correctly!"
-    );
+    fn unmask(&mut self, cards: &mut UnmaskedCards) -> Result<(), PokerError> {
+        cards.unmask_with(&self.sk)
+    }
 
-    assert!(
-        matches!(p2_hole_cards[0], Some(_)),
-        "Player 2 Card 0 did not unmask correctly!"
-    );
-    assert!(
-        matches!(p2_hole_cards[1], Some(_)),
-        "Player 2 Card 1 did not unmask correctly!"
-    );
+    fn choose_action(&mut self, hand: &PokerHand, player: usize) -> PokerAction {
+        match hand.get_call_amount_required(player) {
+            Ok(0) => PokerAction::Check,
+            _ => PokerAction::Call,
+        }
+    }
 
-    let p1_hole_cards_str = p1_hole_cards
-        .into_iter()
-        .map(|c| c.unwrap().to_string())
-        .join(", ");
+    fn public_key(&self) -> PublicKey {
+        self.sk.public_key()
+    }
 
-    let p2_hole_cards_str = p2_hole_cards
-        .into_iter()
-        .map(|c| c.unwrap().to_string())
-        .join(", ");
+    fn shuffle_trace(&mut self) -> Vec<verify::ShuffleTrace> {
+        self.trace.take().unwrap_or_default()
+    }
+}
 
-    let community_cards_str = community_cards
-        .into_iter()
-        .map(|c| c.unwrap().to_string())
-        .join(", ");
+/// Agent that goes all-in whenever it is their turn to bet
+struct ShovingAgent(CallingAgent);
 
-    println!("Player 1's Hole Cards are: {}", p1_hole_cards_str);
-    println!("Player 2's Hole Cards are: {}", p2_hole_cards_str);
-    println!("Community Cards are: {}", community_cards_str);
+impl PlayerAgent for ShovingAgent {
+    fn provide_shuffle(&mut self, deck: &mut MaskedCards) {
+        self.0.provide_shuffle(deck)
+    }
+
+    fn unmask(&mut self, cards: &mut UnmaskedCards) -> Result<(), PokerError> {
+        self.0.unmask(cards)
+    }
+
+    fn choose_action(&mut self, _hand: &PokerHand, _player: usize) -> PokerAction {
+        PokerAction::AllIn
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.0.public_key()
+    }
+
+    fn shuffle_trace(&mut self) -> Vec<verify::ShuffleTrace> {
+        self.0.shuffle_trace()
+    }
 }
 
 #[test]
@@ -241,8 +4459,8 @@ fn test_poker_table() {
 
     let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
 
-    poker_table.join(1);
-    poker_table.join(2);
+    poker_table.join(1).unwrap();
+    poker_table.join(2).unwrap();
 
     poker_table.start_hand(100, 10).unwrap();
 
@@ -331,7 +4549,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_player_cards().clone();
-        cards[1].unmask(sk_1);
+        cards[1].unmask(sk_1).unwrap();
 
         println!("Player 1 unmasks hole cards of Player 2");
 
@@ -348,7 +4566,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_player_cards().clone();
-        cards[0].unmask(sk_2);
+        cards[0].unmask(sk_2).unwrap();
 
         println!("Player 2 unmasks hole cards of Player 1");
 
@@ -368,7 +4586,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_player_cards().clone();
-        cards[0].unmask(sk_1);
+        cards[0].unmask(sk_1).unwrap();
 
         let p1_cards = hand.get_poker_deck().unmasked_cards(&cards[0]);
 
@@ -383,7 +4601,8 @@ fn test_poker_table() {
 
         println!("Player 1's Hole Cards are: {}", p1_cards_str);
 
-        hand.submit_bet(0, 10).unwrap();
+        // Player 1 has small blind in, and raises to 40
+        hand.submit_bet(0, 30).unwrap();
     }
 
     // Player 2 unmasks own cards and bets
@@ -399,7 +4618,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_player_cards().clone();
-        cards[1].unmask(sk_2);
+        cards[1].unmask(sk_2).unwrap();
 
         let p2_cards = hand.get_poker_deck().unmasked_cards(&cards[1]);
 
@@ -414,7 +4633,7 @@ fn test_poker_table() {
 
         println!("Player 2's Hole Cards are: {}", p2_cards_str);
 
-        hand.submit_bet(1, 10).unwrap();
+        hand.submit_bet(1, 20).unwrap();
     }
 
     // Player 1 unmasks community cards
@@ -430,7 +4649,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_community_cards(1).cloned().unwrap();
-        cards.unmask(sk_1);
+        cards.unmask(sk_1).unwrap();
 
         // community cards are also masked by player 2
         let community_cards = hand.get_poker_deck().unmasked_cards(&cards);
@@ -454,7 +4673,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_community_cards(1).cloned().unwrap();
-        cards.unmask(sk_2);
+        cards.unmask(sk_2).unwrap();
 
         println!("Player 2 unmasks community cards");
 
@@ -477,7 +4696,7 @@ fn test_poker_table() {
         println!("Community Cards (Flop) are: {}", community_cards_str);
     }
 
-    // Player 1 bets
+    // Player 2 bets
     {
         let hand = poker_table.get_current_hand_mut().unwrap();
 
@@ -485,16 +4704,16 @@ fn test_poker_table() {
             hand.get_current_state().to_enum(),
             PokerHandStateEnum::Bet {
                 round: 1,
-                player: 0
+                player: 1
             }
         ));
 
-        println!("Player 1 bets");
+        println!("Player 2 bets");
 
-        hand.submit_bet(0, 10).unwrap();
+        hand.submit_bet(1, 20).unwrap();
     }
 
-    // Player 2 bets
+    // Player 1 calls
     {
         let hand = poker_table.get_current_hand_mut().unwrap();
 
@@ -502,13 +4721,13 @@ fn test_poker_table() {
             hand.get_current_state().to_enum(),
             PokerHandStateEnum::Bet {
                 round: 1,
-                player: 1
+                player: 0
             }
         ));
 
-        println!("Player 2 bets");
+        println!("Player 1 calls");
 
-        hand.submit_bet(1, 10).unwrap();
+        hand.submit_bet(0, 20).unwrap();
     }
 
     // Player 1 unmasks community cards
@@ -524,7 +4743,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_community_cards(2).cloned().unwrap();
-        cards.unmask(sk_1);
+        cards.unmask(sk_1).unwrap();
 
         // community cards are also masked by player 2
         let community_cards = hand.get_poker_deck().unmasked_cards(&cards);
@@ -548,7 +4767,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_community_cards(2).cloned().unwrap();
-        cards.unmask(sk_2);
+        cards.unmask(sk_2).unwrap();
 
         println!("Player 2 unmasks community cards");
 
@@ -571,7 +4790,7 @@ fn test_poker_table() {
         println!("Community Cards (Turn) are: {}", community_cards_str);
     }
 
-    // Player 1 bets
+    // Player 2 bets
     {
         let hand = poker_table.get_current_hand_mut().unwrap();
 
@@ -579,16 +4798,16 @@ fn test_poker_table() {
             hand.get_current_state().to_enum(),
             PokerHandStateEnum::Bet {
                 round: 2,
-                player: 0
+                player: 1
             }
         ));
 
-        println!("Player 1 bets");
+        println!("Player 2 bets");
 
-        hand.submit_bet(0, 10).unwrap();
+        hand.submit_bet(1, 20).unwrap();
     }
 
-    // Player 2 bets
+    // Player 1 calls
     {
         let hand = poker_table.get_current_hand_mut().unwrap();
 
@@ -596,13 +4815,13 @@ fn test_poker_table() {
             hand.get_current_state().to_enum(),
             PokerHandStateEnum::Bet {
                 round: 2,
-                player: 1
+                player: 0
             }
         ));
 
-        println!("Player 2 bets");
+        println!("Player 1 calls");
 
-        hand.submit_bet(1, 10).unwrap();
+        hand.submit_bet(0, 20).unwrap();
     }
 
     // Player 1 unmasks community cards
@@ -618,7 +4837,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_community_cards(3).cloned().unwrap();
-        cards.unmask(sk_1);
+        cards.unmask(sk_1).unwrap();
 
         // community cards are also masked by player 2
         let community_cards = hand.get_poker_deck().unmasked_cards(&cards);
@@ -642,7 +4861,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_community_cards(3).cloned().unwrap();
-        cards.unmask(sk_2);
+        cards.unmask(sk_2).unwrap();
 
         println!("Player 2 unmasks community cards");
 
@@ -665,7 +4884,7 @@ fn test_poker_table() {
         println!("Community Cards (River) are: {}", community_cards_str);
     }
 
-    // Player 1 bets
+    // Player 2 bets
     {
         let hand = poker_table.get_current_hand_mut().unwrap();
 
@@ -673,16 +4892,16 @@ fn test_poker_table() {
             hand.get_current_state().to_enum(),
             PokerHandStateEnum::Bet {
                 round: 3,
-                player: 0
+                player: 1
             }
         ));
 
-        println!("Player 1 bets");
+        println!("Player 2 bets");
 
-        hand.submit_bet(0, 10).unwrap();
+        hand.submit_bet(1, 20).unwrap();
     }
 
-    // Player 2 bets
+    // Player 1 calls
     {
         let hand = poker_table.get_current_hand_mut().unwrap();
 
@@ -690,13 +4909,13 @@ fn test_poker_table() {
             hand.get_current_state().to_enum(),
             PokerHandStateEnum::Bet {
                 round: 3,
-                player: 1
+                player: 0
             }
         ));
 
-        println!("Player 2 bets");
+        println!("Player 1 calls");
 
-        hand.submit_bet(1, 10).unwrap();
+        hand.submit_bet(0, 20).unwrap();
     }
 
     // Player 1 unmasks hole cards for showdown
@@ -709,7 +4928,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_player_cards().clone();
-        cards[0].unmask(sk_1);
+        cards[0].unmask(sk_1).unwrap();
 
         println!("Player 1 unmasks their own cards for showdown");
 
@@ -726,7 +4945,7 @@ fn test_poker_table() {
         ));
 
         let mut cards = hand.get_player_cards().clone();
-        cards[1].unmask(sk_2);
+        cards[1].unmask(sk_2).unwrap();
 
         println!("Player 2 unmasks their own cards for showdown");
 
@@ -779,3 +4998,882 @@ fn test_poker_table() {
         println!("Finished");
     }
 }
+
+#[test]
+fn test_join_table() {
+    let mut poker_table = PokerTable::new(3, POKER_HOLDEM_ROUNDS);
+
+    poker_table.join(1).unwrap();
+    assert_eq!(poker_table.join(1), Err(PokerError::AlreadyJoined));
+
+    // Last seat can still be taken
+    poker_table.join(2).unwrap();
+    assert_eq!(poker_table.join(2), Err(PokerError::AlreadyJoined));
+    assert_eq!(poker_table.join(3), Ok(()));
+
+    assert_eq!(poker_table.join(4), Err(PokerError::TableFull));
+    assert_eq!(poker_table.get_current_player_count(), 3);
+}
+
+#[test]
+fn test_leave_table() {
+    let sks = make_signing_keys(3);
+    let mut traces = vec![None; 3];
+
+    let mut poker_table = PokerTable::new(4, POKER_HOLDEM_ROUNDS);
+    for player in 1..=4 {
+        poker_table.join(player).unwrap();
+    }
+
+    assert_eq!(poker_table.leave(5), Err(PokerError::InvalidPlayer));
+
+    // Player 2 leaves before any hand is dealt
+    poker_table.leave(2).unwrap();
+    assert_eq!(poker_table.get_current_player_count(), 3);
+    assert_eq!(poker_table.get_player(1), Some(3));
+
+    poker_table.start_hand(100, 10).unwrap();
+
+    // Nobody dealt into the hand can leave until it is finished
+    assert_eq!(poker_table.leave(3), Err(PokerError::HandInProgress));
+    assert_eq!(poker_table.get_current_player_count(), 3);
+
+    {
+        let hand = poker_table.get_current_hand_mut().unwrap();
+        play_hand_until(hand, &sks, &mut traces, |s| {
+            matches!(s, PokerHandStateEnum::Bet { .. })
+        });
+        hand.submit_action(0, PokerAction::Raise(60)).unwrap();
+        hand.submit_action(1, PokerAction::Fold).unwrap();
+        hand.submit_action(2, PokerAction::Fold).unwrap();
+    }
+
+    // Button moves to player 3, and player 1 seated before it leaves
+    poker_table.leave(1).unwrap();
+    assert_eq!(poker_table.get_current_player_count(), 2);
+    assert_eq!(poker_table.get_dealer_button(), 0);
+    assert_eq!(poker_table.get_player(0), Some(3));
+    assert_eq!(poker_table.player_chips(1), None);
+    assert_eq!(poker_table.player_chips(3), Some(90));
+    assert_eq!(poker_table.player_chips(4), Some(80));
+
+    poker_table.start_hand(100, 10).unwrap();
+    let hand = poker_table.get_current_hand().unwrap();
+    assert_eq!(hand.get_num_players(), 2);
+    assert_eq!(hand.get_small_blind_player(), 0);
+    assert_eq!(hand.get_chips_remaining(0), 90);
+}
+
+#[test]
+fn test_start_hand_validation() {
+    // Nobody to play against
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    assert_eq!(
+        poker_table.start_hand(100, 10),
+        Err(PokerError::NotEnoughPlayers)
+    );
+    poker_table.join(1).unwrap();
+    assert_eq!(
+        poker_table.start_hand(100, 10),
+        Err(PokerError::NotEnoughPlayers)
+    );
+    assert!(poker_table.get_current_hand().is_none());
+
+    // Two players is enough to start
+    poker_table.join(2).unwrap();
+    poker_table.start_hand(100, 10).unwrap();
+    let hand = poker_table.get_current_hand().unwrap();
+    assert_eq!(hand.get_num_players(), 2);
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Shuffle {
+            player: 0,
+            is_dealer: true
+        }
+    ));
+
+    // Rounds must fit the community card layout
+    for max_rounds in [0, POKER_HOLDEM_ROUNDS + 1] {
+        let mut poker_table = PokerTable::new(2, max_rounds);
+        poker_table.join(1).unwrap();
+        poker_table.join(2).unwrap();
+        assert_eq!(
+            poker_table.start_hand(100, 10),
+            Err(PokerError::RoundsMismatch)
+        );
+    }
+}
+
+#[test]
+fn test_consecutive_hands() {
+    let sks = make_signing_keys(3);
+    let mut traces = vec![None; 3];
+
+    let mut poker_table = PokerTable::new(3, POKER_HOLDEM_ROUNDS);
+    for player in 1..=3 {
+        poker_table.join(player).unwrap();
+    }
+
+    poker_table.start_hand(100, 10).unwrap();
+    assert_eq!(poker_table.get_dealer_button(), 0);
+
+    // Player 0 raises and takes the blinds
+    {
+        let hand = poker_table.get_current_hand_mut().unwrap();
+        play_hand_until(hand, &sks, &mut traces, |s| {
+            matches!(s, PokerHandStateEnum::Bet { .. })
+        });
+        hand.submit_action(0, PokerAction::Raise(60)).unwrap();
+        hand.submit_action(1, PokerAction::Fold).unwrap();
+        hand.submit_action(2, PokerAction::Fold).unwrap();
+    }
+
+    assert_eq!(poker_table.start_hand(100, 10), Ok(()));
+
+    // Button moved on and stacks carried over from previous hand
+    assert_eq!(poker_table.get_dealer_button(), 1);
+    assert_eq!(poker_table.player_chips(1), Some(130));
+    assert_eq!(poker_table.player_chips(2), Some(90));
+    assert_eq!(poker_table.player_chips(3), Some(80));
+
+    let hand = poker_table.get_current_hand_mut().unwrap();
+    assert_eq!(hand.get_small_blind_player(), 2);
+    assert_eq!(hand.get_big_blind_player(), 0);
+
+    play_hand_until(hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::Bet { .. })
+    });
+
+    assert!(matches!(
+        hand.get_current_state().to_enum(),
+        PokerHandStateEnum::Bet {
+            round: POKER_HOLDEM_PREFLOP,
+            player: 1
+        }
+    ));
+    assert_eq!(poker_table.player_chips(1), Some(110));
+    assert_eq!(poker_table.player_chips(3), Some(70));
+}
+
+#[test]
+fn test_last_outcome() {
+    let sks = make_signing_keys(2);
+    let mut traces = vec![None; 2];
+
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    poker_table.join(1).unwrap();
+    poker_table.join(2).unwrap();
+    assert!(poker_table.last_outcome().is_none());
+
+    poker_table.start_hand(100, 10).unwrap();
+    {
+        let hand = poker_table.get_current_hand_mut().unwrap();
+        play_hand_until(hand, &sks, &mut traces, |s| {
+            matches!(s, PokerHandStateEnum::Finished)
+        });
+    }
+    let outcome = poker_table
+        .get_current_hand()
+        .unwrap()
+        .outcome()
+        .unwrap()
+        .clone();
+    assert_eq!(outcome.ending, HandEnding::Showdown);
+    assert_eq!(poker_table.last_outcome(), Some(&outcome));
+
+    // Outcome stays available once next hand replaced the finished one
+    poker_table.start_hand(100, 10).unwrap();
+    assert!(poker_table.get_current_hand().unwrap().outcome().is_none());
+    assert_eq!(poker_table.last_outcome(), Some(&outcome));
+
+    // Until the next hand finishes
+    {
+        let hand = poker_table.get_current_hand_mut().unwrap();
+        play_hand_until(hand, &sks, &mut traces, |s| {
+            matches!(s, PokerHandStateEnum::Bet { .. })
+        });
+        let player = hand.get_current_state().get_current_player();
+        hand.submit_action(player, PokerAction::Fold).unwrap();
+    }
+    poker_table.end_hand().unwrap();
+    let last_outcome = poker_table.last_outcome().unwrap();
+    assert_eq!(last_outcome.ending, HandEnding::Fold);
+    assert_ne!(last_outcome, &outcome);
+}
+
+#[test]
+fn test_eliminate_busted_players() {
+    let mut poker_table = PokerTable::new(3, POKER_HOLDEM_ROUNDS);
+    for player in 1..=3 {
+        poker_table.join(player).unwrap();
+    }
+
+    // Player 2 goes all-in with a weaker hand and loses to player 1
+    let mut hand = revealed_hand(
+        &[&["Ah", "Ad"], &["2c", "7d"], &["Kh", "Qh"]],
+        &[&["As", "9d", "4c"], &["Jh"], &["3s"]],
+    );
+    hand.betting_state.process_action(0, 100).unwrap();
+    hand.betting_state.process_action(1, 100).unwrap();
+    hand.betting_state.process_action(2, 0).unwrap();
+    hand.award_pot_to_best_hands().unwrap();
+    hand.current_state.current_state = POKER_HAND_STATE_FINISHED;
+    poker_table.current_hand = Some(hand);
+
+    poker_table.start_hand(100, 10).unwrap();
+
+    assert!(poker_table.is_player_eliminated(2));
+    assert!(!poker_table.is_player_eliminated(3));
+    assert_eq!(poker_table.get_eliminated_players(), &[2]);
+
+    // Player 2 is not dealt into the next hand
+    assert_eq!(poker_table.get_current_player_count(), 2);
+    assert_eq!(poker_table.get_player(0), Some(1));
+    assert_eq!(poker_table.get_player(1), Some(3));
+    assert_eq!(poker_table.get_current_hand().unwrap().get_num_players(), 2);
+
+    // Button moved from player 1 to player 3, skipping player 2
+    assert_eq!(poker_table.get_dealer_button(), 1);
+    assert_eq!(poker_table.player_chips(1), Some(200));
+    assert_eq!(poker_table.player_chips(3), Some(100));
+    assert_eq!(poker_table.player_chips(2), None);
+
+    // Player 3 loses everything, and there is nobody left to play against
+    let mut hand = revealed_hand(
+        &[&["Ah", "Ad"], &["Kh", "Qh"]],
+        &[&["As", "9d", "4c"], &["Jh"], &["3s"]],
+    );
+    hand.betting_state.process_action(1, 100).unwrap();
+    hand.betting_state.process_action(0, 100).unwrap();
+    hand.award_pot_to_best_hands().unwrap();
+    hand.current_state.current_state = POKER_HAND_STATE_FINISHED;
+    poker_table.current_hand = Some(hand);
+
+    assert_eq!(
+        poker_table.start_hand(100, 10),
+        Err(PokerError::NotEnoughPlayers)
+    );
+    assert_eq!(poker_table.get_eliminated_players(), &[2, 3]);
+    assert_eq!(poker_table.player_chips(1), Some(200));
+}
+
+#[test]
+fn test_rebuy() {
+    let mut poker_table = PokerTable::new(3, POKER_HOLDEM_ROUNDS);
+    for player in 1..=3 {
+        poker_table.join(player).unwrap();
+    }
+    poker_table.set_max_buy_in(150);
+
+    // Player 2 goes all-in with a weaker hand and loses to player 1
+    let mut hand = revealed_hand(
+        &[&["Ah", "Ad"], &["2c", "7d"], &["Kh", "Qh"]],
+        &[&["As", "9d", "4c"], &["Jh"], &["3s"]],
+    );
+    hand.betting_state.process_action(0, 100).unwrap();
+    hand.betting_state.process_action(1, 100).unwrap();
+
+    // No re-buy during the hand
+    poker_table.current_hand = Some(hand);
+    assert_eq!(poker_table.rebuy(2, 100), Err(PokerError::HandInProgress));
+
+    let mut hand = poker_table.current_hand.take().unwrap();
+    hand.betting_state.process_action(2, 0).unwrap();
+    hand.award_pot_to_best_hands().unwrap();
+    hand.current_state.current_state = POKER_HAND_STATE_FINISHED;
+    poker_table.current_hand = Some(hand);
+    assert_eq!(poker_table.player_chips(2), Some(0));
+
+    assert_eq!(poker_table.rebuy(4, 100), Err(PokerError::InvalidPlayer));
+    assert_eq!(poker_table.rebuy(1, 1), Err(PokerError::ExceedsMaxBuyIn));
+    assert_eq!(poker_table.rebuy(2, 160), Err(PokerError::ExceedsMaxBuyIn));
+    poker_table.rebuy(2, 100).unwrap();
+    assert_eq!(poker_table.rebuy(2, 60), Err(PokerError::ExceedsMaxBuyIn));
+    poker_table.rebuy(2, 20).unwrap();
+
+    poker_table.start_hand(100, 10).unwrap();
+
+    // Player 2 bought back in and is dealt into the next hand
+    assert!(!poker_table.is_player_eliminated(2));
+    assert!(poker_table.get_eliminated_players().is_empty());
+    assert_eq!(poker_table.get_current_hand().unwrap().get_num_players(), 3);
+    assert_eq!(poker_table.player_chips(1), Some(200));
+    assert_eq!(poker_table.player_chips(2), Some(120));
+    assert_eq!(poker_table.player_chips(3), Some(100));
+}
+
+#[test]
+fn test_blind_schedule() {
+    let sks = make_signing_keys(2);
+    let mut traces = vec![None, None];
+
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    poker_table.join(1).unwrap();
+    poker_table.join(2).unwrap();
+    poker_table.record_events(true);
+    poker_table.set_blind_schedule(
+        BlindSchedule::new(vec![
+            BlindLevel::new(5, 10, 0, LevelLength::Hands(2)),
+            BlindLevel::new(10, 25, 5, LevelLength::Hands(2)),
+        ])
+        .unwrap(),
+    );
+
+    // Two hands at first level, then blinds go up and the last level stays
+    let mut blinds = vec![];
+    for _ in 0..5 {
+        poker_table.start_hand(1000, 1).unwrap();
+        let hand = poker_table.get_current_hand().unwrap();
+        blinds.push((
+            hand.get_small_blind(),
+            hand.get_big_blind(),
+            hand.get_ante(),
+        ));
+
+        // Moves go through the table, so they are recorded, and first
+        // player to bet folds
+        loop {
+            let hand = poker_table.get_current_hand().unwrap();
+            let event = match hand.get_current_state().to_enum() {
+                PokerHandStateEnum::Finished => break,
+                PokerHandStateEnum::Bet { player, .. } => HandEvent::Action {
+                    player,
+                    action: PokerAction::Fold,
+                },
+                _ => next_hand_event(hand, &sks, &mut traces),
+            };
+            poker_table.apply_event(TableEvent::Hand(event)).unwrap();
+        }
+    }
+    assert_eq!(
+        blinds,
+        vec![
+            (5, 10, 0),
+            (5, 10, 0),
+            (10, 25, 5),
+            (10, 25, 5),
+            (10, 25, 5)
+        ]
+    );
+    assert_eq!(poker_table.current_level().unwrap().big_blind, 25);
+    assert!(!poker_table.advance_level());
+
+    // Replay deals hands with the same blinds
+    let replayed = PokerTable::replay_events(&poker_table.get_recorded_events()).unwrap();
+    let hand = replayed.get_current_hand().unwrap();
+    assert_eq!(
+        (
+            hand.get_small_blind(),
+            hand.get_big_blind(),
+            hand.get_ante()
+        ),
+        (10, 25, 5)
+    );
+    for player in 1..=2 {
+        assert_eq!(
+            replayed.player_chips(player),
+            poker_table.player_chips(player)
+        );
+    }
+
+    // Level lasting given time starts on first tick
+    let mut schedule = BlindSchedule::new(vec![
+        BlindLevel::new(5, 10, 0, LevelLength::Duration(100)),
+        BlindLevel::new(10, 20, 0, LevelLength::Duration(100)),
+        BlindLevel::new(20, 40, 0, LevelLength::Hands(1)),
+    ])
+    .unwrap();
+    assert!(!schedule.tick(1000));
+    assert!(!schedule.tick(1099));
+    assert!(schedule.tick(1100));
+    assert_eq!(schedule.current_level().small_blind, 10);
+    assert!(!schedule.tick(1199));
+    assert!(schedule.tick(1250));
+    assert_eq!(schedule.current_level_index(), 2);
+    assert!(!schedule.tick(5000));
+
+    assert_eq!(
+        BlindSchedule::new(vec![]),
+        Err(PokerError::EmptyBlindSchedule)
+    );
+}
+
+#[test]
+fn test_hand_driver() {
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    poker_table.join(7).unwrap();
+    poker_table.join(9).unwrap();
+    poker_table.record_events(true);
+    poker_table.start_hand(100, 10).unwrap();
+
+    let mut rng = rand::thread_rng();
+    let agents = [7, 9]
+        .into_iter()
+        .map(|player_id| {
+            let agent = CallingAgent {
+                sk: SecretKey::random(&mut rng),
+                trace: None,
+            };
+            (player_id, agent)
+        })
+        .collect();
+
+    let mut driver = HandDriver::new(agents);
+    driver.run(&mut poker_table).unwrap();
+
+    let hand = poker_table.get_current_hand().unwrap();
+    assert!(hand.get_current_state().is_finished());
+    assert_eq!(
+        hand.get_chips_remaining(0) + hand.get_chips_remaining(1),
+        200
+    );
+
+    // Nothing more to do, and the moves were recorded for replay
+    assert!(driver.step(&mut poker_table).unwrap());
+    let replayed = PokerTable::replay_events(&poker_table.get_recorded_events()).unwrap();
+    assert!(
+        replayed
+            .get_current_hand()
+            .unwrap()
+            .get_current_state()
+            .is_finished()
+    );
+}
+
+#[test]
+fn test_tournament() {
+    let mut rng = rand::thread_rng();
+    let agents = (1..=12)
+        .map(|player_id| {
+            let agent = ShovingAgent(CallingAgent {
+                sk: SecretKey::random(&mut rng),
+                trace: None,
+            });
+            (player_id, agent)
+        })
+        .collect();
+
+    let blind_schedule = BlindSchedule::new(vec![
+        BlindLevel::new(5, 10, 0, LevelLength::Hands(4)),
+        BlindLevel::new(10, 20, 0, LevelLength::Hands(4)),
+        BlindLevel::new(20, 40, 5, LevelLength::Hands(1)),
+    ])
+    .unwrap();
+    let mut tournament = Tournament::new(agents, 100, blind_schedule).unwrap();
+    let table_sizes = |tournament: &Tournament<ShovingAgent>| {
+        tournament
+            .get_tables()
+            .iter()
+            .map(PokerTable::get_current_player_count)
+            .collect_vec()
+    };
+    assert_eq!(table_sizes(&tournament), vec![6, 6]);
+    assert_eq!(tournament.current_level().small_blind, 5);
+
+    let mut hands_played = 0;
+    while !tournament.step().unwrap() {
+        if tournament.get_hands_played() == hands_played {
+            continue;
+        }
+        hands_played = tournament.get_hands_played();
+
+        // Tables stay balanced, and are broken once players fit at fewer
+        let sizes = table_sizes(&tournament);
+        assert!(sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1);
+        assert_eq!(
+            sizes.len(),
+            tournament.players_left().div_ceil(TOURNAMENT_TABLE_SIZE)
+        );
+        assert_eq!(
+            tournament.players_left() + tournament.get_eliminated_players().len(),
+            12
+        );
+        assert_eq!(
+            tournament.current_level().small_blind,
+            match hands_played {
+                1..=4 => 5,
+                5..=8 => 10,
+                _ => 20,
+            }
+        );
+    }
+
+    assert!(tournament.is_finished());
+    assert_eq!(tournament.players_left(), 1);
+    assert_eq!(tournament.get_eliminated_players().len(), 11);
+
+    // Winner has all the chips, and the rest finish in reverse order of elimination
+    let standings = tournament.standings();
+    assert_eq!(standings.len(), 12);
+    assert_eq!(standings[0].1, 1200);
+    assert!(standings[1..].iter().all(|&(_, chips)| chips == 0));
+    assert_eq!(
+        standings[1..]
+            .iter()
+            .map(|&(player, _)| player)
+            .collect_vec(),
+        tournament
+            .get_eliminated_players()
+            .iter()
+            .rev()
+            .copied()
+            .collect_vec()
+    );
+    assert_eq!(
+        standings
+            .iter()
+            .map(|&(player, _)| player)
+            .sorted()
+            .collect_vec(),
+        (1..=12).collect_vec()
+    );
+}
+
+// Replay and snapshots
+
+#[test]
+fn test_hand_events() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_to_end(&mut hand, &sks, &mut traces);
+
+    let unmasked = |player, state, round| PokerEvent::CardsUnmasked {
+        player,
+        state,
+        round,
+    };
+    let street = |round| {
+        [
+            unmasked(0, POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS, round),
+            unmasked(1, POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS, round),
+            PokerEvent::BetPlaced {
+                player: 1,
+                action: PokerAction::Check,
+            },
+            PokerEvent::BetPlaced {
+                player: 0,
+                action: PokerAction::Check,
+            },
+        ]
+    };
+
+    let mut expected = vec![
+        PokerEvent::ShuffleSubmitted { player: 0 },
+        PokerEvent::ShuffleSubmitted { player: 1 },
+        PokerEvent::BlindPosted {
+            player: 0,
+            amount: 10,
+        },
+        PokerEvent::BlindPosted {
+            player: 1,
+            amount: 20,
+        },
+        unmasked(0, POKER_HAND_STATE_UNMASK_HOLE_CARDS, POKER_HOLDEM_PREFLOP),
+        unmasked(1, POKER_HAND_STATE_UNMASK_HOLE_CARDS, POKER_HOLDEM_PREFLOP),
+        PokerEvent::BetPlaced {
+            player: 0,
+            action: PokerAction::Call,
+        },
+        PokerEvent::BetPlaced {
+            player: 1,
+            action: PokerAction::Check,
+        },
+    ];
+    expected.extend(street(POKER_HOLDEM_FLOP));
+    expected.extend(street(POKER_HOLDEM_TURN));
+    expected.extend(street(POKER_HOLDEM_RIVER));
+    expected.extend([
+        unmasked(0, POKER_HAND_STATE_UNMASK_SHOWDOWN, POKER_HOLDEM_ROUNDS),
+        unmasked(1, POKER_HAND_STATE_UNMASK_SHOWDOWN, POKER_HOLDEM_ROUNDS),
+        PokerEvent::PublicKeySubmitted { player: 0 },
+        PokerEvent::PublicKeySubmitted { player: 1 },
+    ]);
+
+    let events = hand.take_events();
+    assert_eq!(events[..expected.len()], expected[..]);
+
+    // Pot is awarded to the winner, or split on a tie
+    let PokerEvent::PotsAwarded { awards } = &events[expected.len()] else {
+        panic!("Expected pots to be awarded");
+    };
+    assert_eq!(awards.iter().map(|(_, amount)| amount).sum::<u64>(), 40);
+    assert_eq!(events[expected.len() + 1..], [PokerEvent::HandFinished]);
+
+    // Events are only delivered once
+    assert!(hand.take_events().is_empty());
+}
+
+#[test]
+fn test_replay_events() {
+    let sks = make_signing_keys(2);
+    let mut traces = vec![None, None];
+
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    poker_table.record_events(true);
+
+    poker_table
+        .apply_event(TableEvent::Join { player: 1 })
+        .unwrap();
+    poker_table
+        .apply_event(TableEvent::Join { player: 2 })
+        .unwrap();
+    poker_table
+        .apply_event(TableEvent::StartHand {
+            initial_chips: 100,
+            small_blind: 10,
+        })
+        .unwrap();
+
+    loop {
+        let hand = poker_table.get_current_hand().unwrap();
+        if hand.get_current_state().is_finished() {
+            break;
+        }
+        let event = next_hand_event(hand, &sks, &mut traces);
+        poker_table.apply_event(TableEvent::Hand(event)).unwrap();
+    }
+
+    let events = poker_table.get_recorded_events();
+    let replayed = PokerTable::replay_events(&events).unwrap();
+
+    assert_eq!(replayed.get_recorded_events().len(), events.len());
+    assert_eq!(
+        replayed.get_current_player_count(),
+        poker_table.get_current_player_count()
+    );
+
+    let hand = poker_table.get_current_hand().unwrap();
+    let replayed_hand = replayed.get_current_hand().unwrap();
+
+    assert_eq!(
+        replayed_hand.get_current_state().to_tuple(),
+        hand.get_current_state().to_tuple()
+    );
+    assert_eq!(
+        replayed_hand.get_shuffled_deck().hash(),
+        hand.get_shuffled_deck().hash()
+    );
+    for player in 0..2 {
+        assert_eq!(
+            replayed_hand.get_chips_remaining(player),
+            hand.get_chips_remaining(player)
+        );
+    }
+
+    // Log must start with table creation
+    assert!(PokerTable::replay_events(&events[1..]).is_err());
+}
+
+#[test]
+fn test_replay_moves_made_on_hand() {
+    let sks = make_signing_keys(2);
+    let pks: Vec<_> = sks.iter().map(make_public_key_from_signing_key).collect();
+    let mut traces = vec![None, None];
+
+    let mut poker_table = PokerTable::new(2, POKER_HOLDEM_ROUNDS);
+    poker_table.join(1).unwrap();
+    poker_table.join(2).unwrap();
+    poker_table.set_max_buy_in(500);
+    poker_table.record_events(true);
+    poker_table.start_hand(100, 10).unwrap();
+
+    // Moves are made on the hand itself, bypassing the table
+    let hand = poker_table.get_current_hand_mut().unwrap();
+    hand.set_burn_cards(true).unwrap();
+    hand.set_action_timeout(10);
+
+    let mut now = 0;
+    loop {
+        let hand = poker_table.get_current_hand_mut().unwrap();
+        match hand.get_current_state().to_enum() {
+            PokerHandStateEnum::Finished => break,
+            PokerHandStateEnum::Shuffle { .. } => {
+                let HandEvent::ShuffleDeck { player, deck } =
+                    next_hand_event(hand, &sks, &mut traces)
+                else {
+                    panic!("Expected shuffle");
+                };
+                let shuffle_traces = traces[player].clone().unwrap();
+                hand.submit_shuffled_deck_with_proof(player, deck, pks[player], shuffle_traces)
+                    .unwrap();
+            }
+            PokerHandStateEnum::UnmaskHoleCards { player } => {
+                let mut cards = hand.get_player_cards().clone();
+                let mut proofs = vec![vec![]; 2];
+                for target in 0..2 {
+                    if target != player {
+                        (cards[target], proofs[target]) =
+                            unmask_with_proofs(&sks[player], &cards[target]);
+                    }
+                }
+                hand.submit_player_cards_with_proofs(player, cards, pks[player], proofs)
+                    .unwrap();
+            }
+            PokerHandStateEnum::UnmaskCommunityCards { round, player } => {
+                let before = hand.get_community_cards(round).unwrap();
+                let (cards, proofs) = unmask_with_proofs(&sks[player], before);
+                hand.submit_community_cards_with_proofs(player, round, cards, pks[player], proofs)
+                    .unwrap();
+            }
+            // Player to act on the river lets their clock run out
+            PokerHandStateEnum::Bet { round: 3, .. } => {
+                now += 10;
+                hand.tick(now).unwrap();
+            }
+            PokerHandStateEnum::Bet { player, .. } => hand.submit_call(player).unwrap(),
+            _ => step_hand(hand, &sks, &mut traces),
+        }
+    }
+
+    let events = poker_table.get_recorded_events();
+    assert!(matches!(
+        events[1],
+        TableEvent::Configure {
+            max_buy_in: Some(500),
+            ..
+        }
+    ));
+    for recorded in [
+        |e: &HandEvent| matches!(e, HandEvent::Configure(_)),
+        |e: &HandEvent| matches!(e, HandEvent::ShuffleDeckWithProof { .. }),
+        |e: &HandEvent| matches!(e, HandEvent::UnmaskHoleCardsWithProofs { .. }),
+        |e: &HandEvent| matches!(e, HandEvent::UnmaskCommunityCardsWithProofs { .. }),
+        |e: &HandEvent| matches!(e, HandEvent::Call { .. }),
+        |e: &HandEvent| matches!(e, HandEvent::Tick { .. }),
+    ] {
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, TableEvent::Hand(e) if recorded(e)))
+        );
+    }
+
+    // Replay makes the same moves, including the fold on timeout
+    let replayed = PokerTable::replay_events(&events).unwrap();
+    assert_eq!(replayed.get_recorded_events().len(), events.len());
+    assert_eq!(replayed.get_max_buy_in(), Some(500));
+
+    let hand = poker_table.get_current_hand().unwrap();
+    let replayed_hand = replayed.get_current_hand().unwrap();
+    assert_eq!(replayed_hand.get_config(), hand.get_config());
+    assert_eq!(replayed_hand.get_burned_cards(), hand.get_burned_cards());
+    assert_eq!(replayed_hand.action_log(), hand.action_log());
+    assert_eq!(replayed_hand.outcome(), hand.outcome());
+    assert_eq!(hand.action_log().last().unwrap().action, PokerAction::Fold);
+
+    // Moves of the settled hand stay in the log
+    poker_table.end_hand().unwrap();
+    let replayed = PokerTable::replay_events(&poker_table.get_recorded_events()).unwrap();
+    assert!(replayed.get_current_hand().is_none());
+    for player in 1..=2 {
+        assert_eq!(
+            replayed.player_chips(player),
+            poker_table.player_chips(player)
+        );
+    }
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let (sks, mut traces, mut hand) = holdem_hand(3, 1);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::UnmaskCommunityCards { .. })
+    });
+    let mut restored = PokerHand::restore(hand.snapshot()).unwrap();
+
+    // Both hands carry on with the same moves to the same result
+    while !hand.get_current_state().is_finished() {
+        let event = next_hand_event(&hand, &sks, &mut traces);
+        hand.apply_event(event.clone()).unwrap();
+        restored.apply_event(event).unwrap();
+        assert_eq!(
+            hand.get_current_state().to_tuple(),
+            restored.get_current_state().to_tuple()
+        );
+    }
+    for player in 0..3 {
+        assert_eq!(
+            hand.get_chips_remaining(player),
+            restored.get_chips_remaining(player)
+        );
+    }
+    assert_eq!(hand.deck_commitment(), restored.deck_commitment());
+    assert_eq!(restored.verify_unmasking(), Ok(None));
+
+    // Public keys survive the round trip
+    let finished = PokerHand::restore(restored.snapshot()).unwrap();
+    assert_eq!(finished.player_keys, hand.player_keys);
+    assert!(finished.get_current_state().is_finished());
+
+    // Inconsistent snapshots are rejected
+    let mut snapshot = hand.snapshot();
+    snapshot.current_state.current_state = 42;
+    assert!(matches!(
+        PokerHand::restore(snapshot),
+        Err(PokerError::InvalidSnapshot)
+    ));
+    let mut snapshot = hand.snapshot();
+    snapshot.player_cards.pop();
+    assert!(matches!(
+        PokerHand::restore(snapshot),
+        Err(PokerError::InvalidSnapshot)
+    ));
+    let mut snapshot = hand.snapshot();
+    snapshot.player_keys[0] = Some(vec![0; 95]);
+    assert!(matches!(
+        PokerHand::restore(snapshot),
+        Err(PokerError::InvalidSnapshot)
+    ));
+
+    // Malformed unmasking sequence, cards or decks are rejected rather than
+    // panicking once the hand is audited
+    let malformations: [fn(&mut HandSnapshot); 7] = [
+        |snapshot| snapshot.unmasking_sequence[0].2.truncate(1),
+        |snapshot| snapshot.unmasking_sequence[0].2[1] = UnmaskedCards::default(),
+        |snapshot| {
+            let (player, _, cards) = snapshot.unmasking_sequence[0].clone();
+            snapshot.unmasking_sequence.push((
+                player,
+                POKER_HAND_STATE_UNMASK_COMMUNITY_CARDS,
+                cards,
+            ));
+        },
+        |snapshot| snapshot.hole_cards = POKER_OMAHA_HOLE_CARDS,
+        |snapshot| {
+            snapshot.community_cards[POKER_HOLDEM_RIVER] = snapshot.community_cards[0].clone()
+        },
+        |snapshot| snapshot.shuffle_history.truncate(1),
+        |snapshot| {
+            let cards = snapshot.shuffle_history[0].cards();
+            snapshot.shuffled_deck = MaskedCards::new([cards.clone(), cards].concat());
+        },
+    ];
+    let mut finished = PokerHand::restore(hand.snapshot()).unwrap();
+    assert_eq!(finished.verify_unmasking(), Ok(None));
+    for malform in malformations {
+        let mut snapshot = hand.snapshot();
+        malform(&mut snapshot);
+        assert!(matches!(
+            PokerHand::restore(snapshot),
+            Err(PokerError::InvalidSnapshot)
+        ));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_snapshot() {
+    let (sks, mut traces, mut hand) = holdem_hand(2, 0);
+
+    play_hand_until(&mut hand, &sks, &mut traces, |s| {
+        matches!(s, PokerHandStateEnum::SubmitPublicKey { player: 1 })
+    });
+
+    let json = serde_json::to_string(&hand.snapshot()).unwrap();
+    let mut restored = PokerHand::restore(serde_json::from_str(&json).unwrap()).unwrap();
+    assert_eq!(restored.player_keys, hand.player_keys);
+
+    play_hand_to_end(&mut restored, &sks, &mut traces);
+    assert_eq!(restored.verify_unmasking(), Ok(None));
+}